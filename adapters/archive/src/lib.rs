@@ -1,10 +1,14 @@
+use std::cell::Cell;
 use std::fmt;
 use std::fs::{self, File};
-use std::io::{BufReader, BufWriter};
+use std::io::{BufReader, BufWriter, Read, Seek, Write};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Instant;
 
 mod backup;
+mod zip_extract;
 
 pub use backup::{
     FsBackupAdapter, FsBackupArchiveRepository, MAX_BACKUP_ENTRIES, MAX_BACKUP_EXPANDED_BYTES,
@@ -15,11 +19,16 @@ pub use backup::{
 pub enum ArchiveOperation {
     InspectSource,
     OpenArchive,
+    DetectFormat,
     CreateTargetDirectory,
     ReadEntries,
     ReadEntry,
     ReadEntryPath,
+    ReadEntryData,
+    ParseIncludePattern,
+    CheckFreeSpace,
     ExtractEntry,
+    CollapseTopLevelDirectory,
     CreateArchiveParent,
     CreateArchive,
     ReadSourceDirectory,
@@ -29,6 +38,8 @@ pub enum ArchiveOperation {
     AppendDirectory,
     AppendFile,
     FinishArchive,
+    /// The caller's cancel flag was set; see [`ArchiveError::is_cancelled`].
+    Cancelled,
 }
 
 impl fmt::Display for ArchiveOperation {
@@ -36,11 +47,16 @@ impl fmt::Display for ArchiveOperation {
         let value = match self {
             Self::InspectSource => "inspect source",
             Self::OpenArchive => "open archive",
+            Self::DetectFormat => "detect archive format",
             Self::CreateTargetDirectory => "create target directory",
             Self::ReadEntries => "read archive entries",
             Self::ReadEntry => "read archive entry",
             Self::ReadEntryPath => "read archive entry path",
+            Self::ReadEntryData => "read archive entry data",
+            Self::ParseIncludePattern => "parse include pattern",
+            Self::CheckFreeSpace => "check free space",
             Self::ExtractEntry => "extract archive entry",
+            Self::CollapseTopLevelDirectory => "collapse top-level directory",
             Self::CreateArchiveParent => "create archive parent directory",
             Self::CreateArchive => "create archive",
             Self::ReadSourceDirectory => "read source directory",
@@ -50,6 +66,7 @@ impl fmt::Display for ArchiveOperation {
             Self::AppendDirectory => "append directory",
             Self::AppendFile => "append file",
             Self::FinishArchive => "finish archive",
+            Self::Cancelled => "extraction",
         };
         formatter.write_str(value)
     }
@@ -64,6 +81,12 @@ pub struct ArchiveError {
 }
 
 impl ArchiveError {
+    /// Whether the extraction stopped because it was cancelled rather than
+    /// because something went wrong.
+    pub fn is_cancelled(&self) -> bool {
+        self.operation == ArchiveOperation::Cancelled
+    }
+
     fn with_target(
         operation: ArchiveOperation,
         source: impl Into<PathBuf>,
@@ -103,6 +126,354 @@ pub fn extract_tar_bz2<F>(
 ) -> Result<(), ArchiveError>
 where
     F: FnMut(&str),
+{
+    let never_cancelled = AtomicBool::new(false);
+    extract_tar_bz2_matching(
+        archive_path,
+        target_dir,
+        &[],
+        false,
+        0,
+        None,
+        &never_cancelled,
+        |progress| on_progress(progress.path),
+    )
+    .map(|_| ())
+}
+
+/// Progress reported by [`extract_tar_bz2_matching`], measured in compressed
+/// bytes consumed from the archive file. That needs only one pass and never
+/// goes backwards, though it runs unevenly when parts of the archive
+/// compress better than others. Callers that scanned the archive first with
+/// [`scan_tar_bz2`] also get an even percentage from `bytes_total`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractProgress<'a> {
+    /// The entry being extracted.
+    pub path: &'a str,
+    pub compressed_read: u64,
+    pub compressed_total: u64,
+    /// Uncompressed size of the entries before `path`.
+    pub bytes_done: u64,
+    /// Uncompressed size of every entry, when known up front.
+    pub bytes_total: Option<u64>,
+}
+
+impl ExtractProgress<'_> {
+    /// Share of the uncompressed bytes handled so far, from 0 to 100, when
+    /// `bytes_total` is known.
+    pub fn percent(&self) -> Option<f64> {
+        self.bytes_total.map(|total| {
+            if total == 0 {
+                100.0
+            } else {
+                (self.bytes_done.min(total) as f64 / total as f64) * 100.0
+            }
+        })
+    }
+}
+
+/// What [`scan_tar_bz2`] found in an archive.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveTotals {
+    pub entries: u64,
+    /// Sum of the uncompressed entry sizes.
+    pub bytes: u64,
+}
+
+/// Entry counts reported by [`extract_tar_bz2_matching`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractSummary {
+    pub matched: usize,
+    pub skipped: usize,
+    /// Matched files left in place because a previous run already extracted them.
+    pub resumed: usize,
+    /// Regular files unpacked by this run and their total size.
+    pub files_extracted: usize,
+    pub bytes_written: u64,
+}
+
+/// Written next to the archive while [`extract_tar_bz2_matching`] runs and
+/// removed once it succeeds, so an extraction that was killed can report how
+/// far it got and a resumed run can skip the entries already handled.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtractionCheckpoint {
+    pub target_dir: PathBuf,
+    pub include: Vec<String>,
+    pub strip_components: u32,
+    /// Archive entries handled so far, in archive order, including skipped ones.
+    pub entries_done: u64,
+    /// Uncompressed size of those entries.
+    pub bytes_done: u64,
+    pub compressed_read: u64,
+    pub compressed_total: u64,
+}
+
+const EXTRACTION_CHECKPOINT_SUFFIX: &str = ".extract.json";
+/// How often a running extraction rewrites its checkpoint.
+const EXTRACTION_CHECKPOINT_INTERVAL_MS: u128 = 1000;
+
+pub fn extraction_checkpoint_path(archive_path: &Path) -> PathBuf {
+    let mut path = archive_path.as_os_str().to_os_string();
+    path.push(EXTRACTION_CHECKPOINT_SUFFIX);
+    PathBuf::from(path)
+}
+
+/// The checkpoint left by an unfinished extraction of `archive_path`. Missing
+/// or unreadable checkpoints are `None`.
+pub fn read_extraction_checkpoint(archive_path: &str) -> Option<ExtractionCheckpoint> {
+    let bytes = fs::read(extraction_checkpoint_path(Path::new(archive_path))).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Best effort: a checkpoint that cannot be written only costs a resumed run
+/// its shortcut, so errors are ignored. Written to a temporary file first so
+/// a kill mid-write leaves the previous checkpoint intact.
+fn write_extraction_checkpoint(path: &Path, checkpoint: &ExtractionCheckpoint) {
+    let Ok(bytes) = serde_json::to_vec(checkpoint) else {
+        return;
+    };
+    let mut temp_path = path.as_os_str().to_os_string();
+    temp_path.push(".tmp");
+    if fs::write(&temp_path, bytes).is_ok() {
+        let _ = fs::rename(&temp_path, path);
+    }
+}
+
+/// Include filter for archive entries. Each item is either a glob pattern
+/// (`*/tokens.txt`) or a plain path prefix (`sherpa-onnx-whisper-tiny/`); a
+/// prefix matches whole path components only.
+struct EntryFilter {
+    globs: Vec<glob::Pattern>,
+    prefixes: Vec<String>,
+}
+
+impl EntryFilter {
+    fn parse(include: &[String]) -> Result<Self, String> {
+        let mut globs = Vec::new();
+        let mut prefixes = Vec::new();
+        for item in include {
+            let item = normalize_entry_path(item.trim());
+            if item.is_empty() {
+                continue;
+            }
+            if item.contains(['*', '?', '[']) {
+                let pattern = glob::Pattern::new(&item)
+                    .map_err(|error| format!("Invalid include pattern {item:?}: {error}"))?;
+                globs.push(pattern);
+            } else {
+                prefixes.push(item.trim_end_matches('/').to_string());
+            }
+        }
+        Ok(Self { globs, prefixes })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.globs.is_empty() && self.prefixes.is_empty()
+    }
+
+    fn matches(&self, entry_path: &str) -> bool {
+        let entry_path = normalize_entry_path(entry_path);
+        let entry_path = entry_path.trim_end_matches('/');
+        self.prefixes.iter().any(|prefix| {
+            entry_path == prefix
+                || entry_path
+                    .strip_prefix(prefix.as_str())
+                    .is_some_and(|rest| rest.starts_with('/'))
+        }) || self.globs.iter().any(|pattern| pattern.matches(entry_path))
+    }
+}
+
+fn normalize_entry_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+/// `path` without its first `strip_components` components, like
+/// `tar --strip-components`. `None` for entries that are stripped away
+/// entirely, such as the top-level directory itself.
+fn strip_entry_path(path: &str, strip_components: u32) -> Option<String> {
+    if strip_components == 0 {
+        return Some(path.to_string());
+    }
+    let normalized = normalize_entry_path(path);
+    let stripped = normalized
+        .split('/')
+        .filter(|component| !component.is_empty() && *component != ".")
+        .skip(strip_components as usize)
+        .collect::<Vec<_>>()
+        .join("/");
+    (!stripped.is_empty()).then_some(stripped)
+}
+
+/// Container format recognised from an archive's leading bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    Bzip2,
+    Gzip,
+    Xz,
+    Zip,
+    /// Uncompressed tar.
+    Tar,
+}
+
+impl fmt::Display for ArchiveFormat {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(match self {
+            Self::Bzip2 => "bzip2",
+            Self::Gzip => "gzip",
+            Self::Xz => "xz",
+            Self::Zip => "zip",
+            Self::Tar => "tar",
+        })
+    }
+}
+
+/// Enough leading bytes to reach the `ustar` magic of a tar header.
+const FORMAT_SNIFF_BYTES: usize = 262;
+const TAR_MAGIC_RANGE: std::ops::Range<usize> = 257..262;
+
+/// Picks the format from `header`, the first bytes of the file. The extension
+/// of `path` is only consulted for old tar files, which carry no magic.
+pub fn detect_archive_format(header: &[u8], path: &Path) -> Result<ArchiveFormat, String> {
+    if header.starts_with(b"BZh") {
+        return Ok(ArchiveFormat::Bzip2);
+    }
+    if header.starts_with(&[0x1f, 0x8b]) {
+        return Ok(ArchiveFormat::Gzip);
+    }
+    if header.starts_with(&[0xfd, 0x37, 0x7a, 0x58, 0x5a]) {
+        return Ok(ArchiveFormat::Xz);
+    }
+    if header.starts_with(b"PK") {
+        return Ok(ArchiveFormat::Zip);
+    }
+    if header.get(TAR_MAGIC_RANGE) == Some(b"ustar".as_slice()) {
+        return Ok(ArchiveFormat::Tar);
+    }
+    let is_tar_name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.to_ascii_lowercase().ends_with(".tar"));
+    if is_tar_name && header.len() >= TAR_MAGIC_RANGE.end {
+        return Ok(ArchiveFormat::Tar);
+    }
+
+    let magic = header
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    Err(format!(
+        "unrecognized archive format (magic bytes: [{magic}])"
+    ))
+}
+
+/// Counts the bytes the decompressor pulls from the archive file.
+struct CountingReader<R> {
+    inner: R,
+    read: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buffer)?;
+        self.read.set(self.read.get() + read as u64);
+        Ok(read)
+    }
+}
+
+/// Opens `archive_path` and reads its format from the leading bytes. Returns
+/// the file rewound to the start, its format and its size.
+fn open_archive(
+    archive_path: &Path,
+) -> Result<(File, ArchiveFormat, u64), (ArchiveOperation, String)> {
+    let open_error = |error: std::io::Error| (ArchiveOperation::OpenArchive, error.to_string());
+    let mut file = File::open(archive_path).map_err(open_error)?;
+    let mut header = Vec::with_capacity(FORMAT_SNIFF_BYTES);
+    (&mut file)
+        .take(FORMAT_SNIFF_BYTES as u64)
+        .read_to_end(&mut header)
+        .map_err(open_error)?;
+    file.rewind().map_err(open_error)?;
+    let len = file.metadata().map_err(open_error)?.len();
+
+    let format = detect_archive_format(&header, archive_path)
+        .map_err(|reason| (ArchiveOperation::DetectFormat, reason))?;
+    Ok((file, format, len))
+}
+
+/// Wraps `file` in the decoder `format` calls for. Bytes read from the file
+/// are added to `compressed_read`. Zip archives are not tar streams and go
+/// through [`zip_extract`] instead.
+fn tar_stream(
+    file: File,
+    format: ArchiveFormat,
+    compressed_read: Rc<Cell<u64>>,
+) -> Result<Box<dyn Read>, (ArchiveOperation, String)> {
+    let buffered = BufReader::new(CountingReader {
+        inner: file,
+        read: compressed_read,
+    });
+    let stream: Box<dyn Read> = match format {
+        ArchiveFormat::Bzip2 => Box::new(bzip2::read::BzDecoder::new(buffered)),
+        ArchiveFormat::Gzip => Box::new(flate2::read::GzDecoder::new(buffered)),
+        ArchiveFormat::Xz => Box::new(xz2::read::XzDecoder::new(buffered)),
+        ArchiveFormat::Tar => Box::new(buffered),
+        ArchiveFormat::Zip => {
+            return Err((
+                ArchiveOperation::DetectFormat,
+                format!("{format} is not a tar stream"),
+            ));
+        }
+    };
+    Ok(stream)
+}
+
+/// Extracts only the entries matching `include` (every entry when empty).
+///
+/// The format is chosen by [`detect_archive_format`], so a `.tar.gz`,
+/// `.tar.xz`, `.zip` or plain tar file is read correctly whatever its name
+/// says. Zip archives skip the checkpoint: their entries are reached by
+/// index, so resuming only needs the size check.
+///
+/// Skipped entries are still read past so the tar stream stays aligned; parent
+/// directories of matched entries are created on demand. With `resume`, files
+/// already on disk with the archived size and mtime are kept, so an
+/// interrupted extraction only unpacks what is missing. Progress is also
+/// saved to [`extraction_checkpoint_path`] about once a second; resuming with
+/// the same target, filter and strip count skips the entries it records
+/// without looking at the disk. The checkpoint is removed on success.
+///
+/// `strip_components` drops that many leading path components from every
+/// entry, like `tar --strip-components`; entries left with no path are
+/// skipped. `include` still matches the paths as archived.
+///
+/// `on_progress` is called for the first matched entry and then at most every
+/// 100 ms. `bytes_total`, the [`ArchiveTotals::bytes`] of an earlier
+/// [`scan_tar_bz2`], is passed through so the progress has a percentage; a
+/// tar stream cannot be sized without reading it through once.
+///
+/// `cancel` is checked before each entry. Once set, the extraction stops with
+/// an [`ArchiveError::is_cancelled`] error. Entries already written and the
+/// checkpoint stay, so a later call with `resume` continues from there.
+#[allow(clippy::too_many_arguments)]
+pub fn extract_tar_bz2_matching<F>(
+    archive_path: &str,
+    target_dir: &str,
+    include: &[String],
+    resume: bool,
+    strip_components: u32,
+    bytes_total: Option<u64>,
+    cancel: &AtomicBool,
+    mut on_progress: F,
+) -> Result<ExtractSummary, ArchiveError>
+where
+    F: FnMut(&ExtractProgress<'_>),
 {
     let archive_path = PathBuf::from(archive_path);
     let target_path = PathBuf::from(target_dir);
@@ -110,112 +481,446 @@ where
         ArchiveError::with_target(operation, &archive_path, &target_path, reason)
     };
 
-    let file = File::open(&archive_path)
-        .map_err(|error| archive_error(ArchiveOperation::OpenArchive, error.to_string()))?;
-    let buffered = BufReader::new(file);
-    let tar = bzip2::read::BzDecoder::new(buffered);
-    let mut archive = tar::Archive::new(tar);
+    let filter = EntryFilter::parse(include)
+        .map_err(|reason| archive_error(ArchiveOperation::ParseIncludePattern, reason))?;
+
+    let (file, format, compressed_total) = open_archive(&archive_path)
+        .map_err(|(operation, reason)| archive_error(operation, reason))?;
     fs::create_dir_all(&target_path).map_err(|error| {
         archive_error(ArchiveOperation::CreateTargetDirectory, error.to_string())
     })?;
+    if format == ArchiveFormat::Zip {
+        return zip_extract::extract_zip(
+            file,
+            &target_path,
+            &filter,
+            resume,
+            strip_components,
+            bytes_total,
+            cancel,
+            &mut on_progress,
+        )
+        .map_err(|(operation, reason)| archive_error(operation, reason));
+    }
+    let compressed_read = Rc::new(Cell::new(0));
+    let tar = tar_stream(file, format, compressed_read.clone())
+        .map_err(|(operation, reason)| archive_error(operation, reason))?;
+    let mut archive = tar::Archive::new(tar);
 
-    let mut last_emit = Instant::now();
+    let checkpoint_path = extraction_checkpoint_path(&archive_path);
+    let mut checkpoint = ExtractionCheckpoint {
+        target_dir: target_path.clone(),
+        include: include.to_vec(),
+        strip_components,
+        entries_done: 0,
+        bytes_done: 0,
+        compressed_read: 0,
+        compressed_total,
+    };
+    // Entries before this index were handled by the run that left the
+    // checkpoint, with the same filter, so they are skipped unopened.
+    let checkpoint_entries = if resume {
+        read_extraction_checkpoint(&archive_path.to_string_lossy())
+            .filter(|previous| {
+                previous.target_dir == checkpoint.target_dir
+                    && previous.include == checkpoint.include
+                    && previous.strip_components == checkpoint.strip_components
+            })
+            .map_or(0, |previous| previous.entries_done)
+    } else {
+        let _ = fs::remove_file(&checkpoint_path);
+        0
+    };
+    let mut last_checkpoint = Instant::now();
+    let mut previous_entry_size = 0;
 
-    for entry in archive
+    let mut last_emit: Option<Instant> = None;
+    let mut summary = ExtractSummary::default();
+
+    for (index, entry) in archive
         .entries()
         .map_err(|error| archive_error(ArchiveOperation::ReadEntries, error.to_string()))?
+        .enumerate()
     {
+        if cancel.load(Ordering::Relaxed) {
+            checkpoint.compressed_read = compressed_read.get();
+            write_extraction_checkpoint(&checkpoint_path, &checkpoint);
+            return Err(archive_error(
+                ArchiveOperation::Cancelled,
+                "cancelled".to_string(),
+            ));
+        }
         let mut entry =
             entry.map_err(|error| archive_error(ArchiveOperation::ReadEntry, error.to_string()))?;
+        checkpoint.entries_done = index as u64;
+        checkpoint.bytes_done += previous_entry_size;
+        previous_entry_size = entry.size();
+        if last_checkpoint.elapsed().as_millis() >= EXTRACTION_CHECKPOINT_INTERVAL_MS {
+            checkpoint.compressed_read = compressed_read.get();
+            write_extraction_checkpoint(&checkpoint_path, &checkpoint);
+            last_checkpoint = Instant::now();
+        }
 
-        if last_emit.elapsed().as_millis() > 100 {
-            let path = entry.path().map_err(|error| {
-                archive_error(ArchiveOperation::ReadEntryPath, error.to_string())
-            })?;
-            on_progress(&path.to_string_lossy());
-            last_emit = Instant::now();
+        let path = entry
+            .path()
+            .map_err(|error| archive_error(ArchiveOperation::ReadEntryPath, error.to_string()))?
+            .to_string_lossy()
+            .into_owned();
+
+        if !filter.is_empty() && !filter.matches(&path) {
+            summary.skipped += 1;
+            continue;
+        }
+        let Some(relative) = strip_entry_path(&path, strip_components) else {
+            summary.skipped += 1;
+            continue;
+        };
+        summary.matched += 1;
+
+        if last_emit.is_none_or(|last_emit| last_emit.elapsed().as_millis() > 100) {
+            on_progress(&ExtractProgress {
+                path: &path,
+                compressed_read: compressed_read.get(),
+                compressed_total,
+                bytes_done: checkpoint.bytes_done,
+                bytes_total,
+            });
+            last_emit = Some(Instant::now());
+        }
+
+        let Some(destination) = safe_destination(&target_path, &relative)
+            .map_err(|reason| archive_error(ArchiveOperation::ExtractEntry, reason))?
+        else {
+            continue;
+        };
+        if (index as u64) < checkpoint_entries {
+            if entry.header().entry_type().is_file() {
+                summary.resumed += 1;
+            }
+            continue;
+        }
+        if resume && is_already_extracted(&entry, &target_path, &relative) {
+            summary.resumed += 1;
+            continue;
         }
 
-        entry
-            .unpack_in(&target_path)
-            .map_err(|error| archive_error(ArchiveOperation::ExtractEntry, error.to_string()))?;
+        let is_file = entry.header().entry_type().is_file();
+        let size = entry.size();
+        if is_file {
+            ensure_disk_space(&target_path, size)
+                .map_err(|reason| archive_error(ArchiveOperation::CheckFreeSpace, reason))?;
+        }
+        let unpacked = if strip_components == 0 {
+            entry
+                .unpack_in(&target_path)
+                .map_err(|error| error.to_string())
+        } else {
+            unpack_stripped(&mut entry, &destination)
+        }
+        .map_err(|reason| archive_error(ArchiveOperation::ExtractEntry, reason))?;
+        if unpacked && is_file {
+            summary.files_extracted += 1;
+            summary.bytes_written += size;
+        }
     }
 
+    let _ = fs::remove_file(&checkpoint_path);
+    Ok(summary)
+}
+
+/// Room left free on the target volume while extracting, so unpacking a model
+/// never fills the disk completely.
+pub const DISK_SPACE_MARGIN: u64 = 64 * 1024 * 1024;
+
+/// Checks the volume holding `dir` can take a `len` byte entry plus
+/// [`DISK_SPACE_MARGIN`]. The decompressed size of a bz2 archive is only known
+/// entry by entry, so this runs before each file is written. When the free
+/// space cannot be read the entry is written anyway.
+fn ensure_disk_space(dir: &Path, len: u64) -> Result<(), String> {
+    let Ok(available) = fs3::available_space(dir) else {
+        return Ok(());
+    };
+    let needed = len.saturating_add(DISK_SPACE_MARGIN);
+    if available < needed {
+        return Err(format!(
+            "insufficient disk space: need {needed} bytes, have {available} bytes"
+        ));
+    }
     Ok(())
 }
 
-pub fn create_tar_bz2(source_dir: &str, archive_path: &str) -> Result<(), ArchiveError> {
-    fn append_directory_contents(
-        builder: &mut tar::Builder<bzip2::write::BzEncoder<BufWriter<File>>>,
-        root: &Path,
-        current: &Path,
-        archive_path: &Path,
-    ) -> Result<(), ArchiveError> {
-        for entry in fs::read_dir(current).map_err(|error| {
-            ArchiveError::with_target(
-                ArchiveOperation::ReadSourceDirectory,
-                current,
-                archive_path,
-                error.to_string(),
-            )
-        })? {
-            let entry = entry.map_err(|error| {
-                ArchiveError::with_target(
-                    ArchiveOperation::ReadSourceEntry,
-                    current,
-                    archive_path,
-                    error.to_string(),
-                )
-            })?;
-            let path = entry.path();
-            let relative = path.strip_prefix(root).map_err(|error| {
-                ArchiveError::with_target(
-                    ArchiveOperation::ResolveSourceEntry,
-                    &path,
-                    archive_path,
-                    error.to_string(),
-                )
-            })?;
+/// Decompresses the archive at `archive_path` and reads every entry to its
+/// end without writing anything, so a truncated or corrupt download fails
+/// before extraction starts rather than partway through it. Returns the
+/// number of entries.
+pub fn verify_tar_bz2(archive_path: &str) -> Result<u64, ArchiveError> {
+    scan_tar_bz2(archive_path).map(|totals| totals.entries)
+}
 
-            if entry
-                .file_type()
-                .map_err(|error| {
-                    ArchiveError::with_target(
-                        ArchiveOperation::InspectSourceEntry,
-                        &path,
-                        archive_path,
-                        error.to_string(),
-                    )
-                })?
-                .is_dir()
-            {
-                builder.append_dir(relative, &path).map_err(|error| {
-                    ArchiveError::with_target(
-                        ArchiveOperation::AppendDirectory,
-                        &path,
-                        archive_path,
-                        error.to_string(),
-                    )
-                })?;
-                append_directory_contents(builder, root, &path, archive_path)?;
-                continue;
-            }
+/// [`verify_tar_bz2`], also adding up the entry sizes for an extraction with
+/// a percentage. Decompresses the whole archive, which for bz2 takes about
+/// as long as extracting it.
+pub fn scan_tar_bz2(archive_path: &str) -> Result<ArchiveTotals, ArchiveError> {
+    let archive_path = PathBuf::from(archive_path);
+    let archive_error = |operation, reason| ArchiveError {
+        operation,
+        source: archive_path.clone(),
+        target: None,
+        reason,
+    };
 
-            builder
-                .append_path_with_name(&path, relative)
-                .map_err(|error| {
-                    ArchiveError::with_target(
-                        ArchiveOperation::AppendFile,
-                        &path,
-                        archive_path,
-                        error.to_string(),
-                    )
-                })?;
+    let (file, format, _) = open_archive(&archive_path)
+        .map_err(|(operation, reason)| archive_error(operation, reason))?;
+    if format == ArchiveFormat::Zip {
+        return zip_extract::scan_zip(file)
+            .map_err(|(operation, reason)| archive_error(operation, reason));
+    }
+    let tar = tar_stream(file, format, Rc::new(Cell::new(0)))
+        .map_err(|(operation, reason)| archive_error(operation, reason))?;
+    let mut archive = tar::Archive::new(tar);
+    let mut totals = ArchiveTotals::default();
+    for entry in archive
+        .entries()
+        .map_err(|error| archive_error(ArchiveOperation::ReadEntries, error.to_string()))?
+    {
+        let mut entry =
+            entry.map_err(|error| archive_error(ArchiveOperation::ReadEntry, error.to_string()))?;
+        let expected = entry.size();
+        let read = std::io::copy(&mut entry, &mut std::io::sink())
+            .map_err(|error| archive_error(ArchiveOperation::ReadEntryData, error.to_string()))?;
+        // A stream that ends inside an entry reads short rather than failing.
+        if read != expected {
+            return Err(archive_error(
+                ArchiveOperation::ReadEntryData,
+                format!("entry ended after {read} of {expected} bytes"),
+            ));
         }
+        totals.entries += 1;
+        totals.bytes += expected;
+    }
+    // Drain the rest so the decoder checks its trailer and checksum.
+    std::io::copy(&mut archive.into_inner(), &mut std::io::sink())
+        .map_err(|error| archive_error(ArchiveOperation::ReadEntries, error.to_string()))?;
+    Ok(totals)
+}
 
-        Ok(())
+/// Where the entry at `relative` lands under `target_dir`, with its parent
+/// directories created. Archives come from arbitrary URLs, so absolute paths,
+/// `..` components and parents that a symlink unpacked earlier points outside
+/// `target_dir` fail the extraction rather than being skipped. `None` for the
+/// target directory itself, as in a leading `./` entry.
+fn safe_destination(target_dir: &Path, relative: &str) -> Result<Option<PathBuf>, String> {
+    let relative = normalize_entry_path(relative);
+    let unsafe_path = || format!("unsafe path in archive: {relative}");
+    let mut destination = target_dir.to_path_buf();
+    let mut has_name = false;
+    for component in Path::new(&relative).components() {
+        match component {
+            std::path::Component::Normal(name) => {
+                destination.push(name);
+                has_name = true;
+            }
+            std::path::Component::CurDir => {}
+            _ => return Err(unsafe_path()),
+        }
+    }
+    if !has_name {
+        return Ok(None);
+    }
+
+    // Resolve the deepest parent that exists before creating the rest, so a
+    // symlinked parent cannot make `create_dir_all` write outside.
+    let parent = destination.parent().unwrap_or(target_dir);
+    let existing = parent
+        .ancestors()
+        .find(|ancestor| ancestor.exists())
+        .unwrap_or(target_dir);
+    let existing = existing.canonicalize().map_err(|error| error.to_string())?;
+    let target_dir = target_dir
+        .canonicalize()
+        .map_err(|error| error.to_string())?;
+    if !existing.starts_with(&target_dir) {
+        return Err(unsafe_path());
+    }
+    fs::create_dir_all(parent).map_err(|error| error.to_string())?;
+    Ok(Some(destination))
+}
+
+/// Unpacks `entry` to `destination`, from [`safe_destination`], instead of
+/// its archived path. Hard links name their target by archived path, which
+/// no longer exists once stripped, so they are refused.
+fn unpack_stripped<R: std::io::Read>(
+    entry: &mut tar::Entry<'_, R>,
+    destination: &Path,
+) -> Result<bool, String> {
+    if entry.header().entry_type().is_hard_link() {
+        return Err(format!(
+            "hard link {} cannot be extracted with stripped path components",
+            destination.display()
+        ));
+    }
+    entry
+        .unpack(destination)
+        .map_err(|error| error.to_string())?;
+    Ok(true)
+}
+
+/// Moves the contents of `dir` up out of its only child when that child is a
+/// directory, undoing the `name/name/...` nesting of archives that wrap their
+/// files in a top-level folder. Returns whether anything was moved.
+pub fn collapse_single_top_level_dir(dir: &str) -> Result<bool, ArchiveError> {
+    let dir = Path::new(dir);
+    let collapse_error = |target: &Path, error: std::io::Error| {
+        ArchiveError::with_target(
+            ArchiveOperation::CollapseTopLevelDirectory,
+            dir,
+            target,
+            error.to_string(),
+        )
+    };
+    let mut entries = fs::read_dir(dir)
+        .and_then(|entries| entries.collect::<std::io::Result<Vec<_>>>())
+        .map_err(|error| collapse_error(dir, error))?;
+    if entries.len() != 1 {
+        return Ok(false);
+    }
+    let top_level = entries.remove(0);
+    let is_dir = top_level
+        .file_type()
+        .map_err(|error| collapse_error(&top_level.path(), error))?
+        .is_dir();
+    if !is_dir {
+        return Ok(false);
+    }
+
+    // Renamed first, so a child with the same name as its folder can move up.
+    let staging = dir.join(format!(
+        ".{}.collapsing",
+        top_level.file_name().to_string_lossy()
+    ));
+    fs::rename(top_level.path(), &staging).map_err(|error| collapse_error(&staging, error))?;
+    let children = fs::read_dir(&staging)
+        .and_then(|children| children.collect::<std::io::Result<Vec<_>>>())
+        .map_err(|error| collapse_error(&staging, error))?;
+    for child in children {
+        let destination = dir.join(child.file_name());
+        fs::rename(child.path(), &destination)
+            .map_err(|error| collapse_error(&destination, error))?;
+    }
+    fs::remove_dir(&staging).map_err(|error| collapse_error(&staging, error))?;
+    Ok(true)
+}
+
+/// True when `path` is a regular file already unpacked under `target_dir`
+/// with the archived size and mtime. The mtime is applied after the contents
+/// are written, so a file cut short by an interrupted run never matches.
+fn is_already_extracted<R: std::io::Read>(
+    entry: &tar::Entry<'_, R>,
+    target_dir: &Path,
+    path: &str,
+) -> bool {
+    let header = entry.header();
+    if !header.entry_type().is_file() {
+        return false;
+    }
+    let relative = Path::new(path);
+    if !relative
+        .components()
+        .all(|component| matches!(component, std::path::Component::Normal(_)))
+    {
+        return false;
     }
+    let (Ok(size), Ok(mtime)) = (header.size(), header.mtime()) else {
+        return false;
+    };
+    let Ok(metadata) = fs::symlink_metadata(target_dir.join(relative)) else {
+        return false;
+    };
+    let on_disk_mtime = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|elapsed| elapsed.as_secs());
+
+    metadata.is_file() && metadata.len() == size && on_disk_mtime == Some(mtime)
+}
+
+pub fn create_tar_bz2(source_dir: &str, archive_path: &str) -> Result<(), ArchiveError> {
+    create_compressed_tar(
+        source_dir,
+        archive_path,
+        |writer| bzip2::write::BzEncoder::new(writer, bzip2::Compression::best()),
+        bzip2::write::BzEncoder::finish,
+    )
+}
+
+/// Same as [`create_tar_bz2`] with gzip compression, for bundles that have to
+/// open with stock tools or be accepted as an issue attachment.
+pub fn create_tar_gz(source_dir: &str, archive_path: &str) -> Result<(), ArchiveError> {
+    create_compressed_tar(
+        source_dir,
+        archive_path,
+        |writer| flate2::write::GzEncoder::new(writer, flate2::Compression::default()),
+        flate2::write::GzEncoder::finish,
+    )
+}
+
+/// Zips the contents of `source_dir` with deflate, for bundles that have to
+/// open with the file manager on every platform.
+pub fn create_zip(source_dir: &str, archive_path: &str) -> Result<(), ArchiveError> {
+    let (source_path, archive_path, writer) = create_archive_file(source_dir, archive_path)?;
+    let mut zip = zip::ZipWriter::new(writer);
+
+    append_directory_contents(&mut zip, &source_path, &source_path, &archive_path)?;
+
+    let finish_error = |reason: String| {
+        ArchiveError::with_target(
+            ArchiveOperation::FinishArchive,
+            &source_path,
+            &archive_path,
+            reason,
+        )
+    };
+    zip.finish()
+        .map_err(|error| finish_error(error.to_string()))?
+        .flush()
+        .map_err(|error| finish_error(error.to_string()))?;
+
+    Ok(())
+}
+
+/// Writes the contents of `source_dir` as a tar stream through the encoder
+/// `wrap` puts around the archive file, then flushes it with `finish`.
+fn create_compressed_tar<E: std::io::Write>(
+    source_dir: &str,
+    archive_path: &str,
+    wrap: impl FnOnce(BufWriter<File>) -> E,
+    finish: impl FnOnce(E) -> std::io::Result<BufWriter<File>>,
+) -> Result<(), ArchiveError> {
+    let (source_path, archive_path, writer) = create_archive_file(source_dir, archive_path)?;
+    let mut builder = tar::Builder::new(wrap(writer));
+
+    append_directory_contents(&mut builder, &source_path, &source_path, &archive_path)?;
+
+    let finish_error = |error: std::io::Error| {
+        ArchiveError::with_target(
+            ArchiveOperation::FinishArchive,
+            &source_path,
+            &archive_path,
+            error.to_string(),
+        )
+    };
+    finish(builder.into_inner().map_err(finish_error)?).map_err(finish_error)?;
 
+    Ok(())
+}
+
+/// Checks the source directory and creates the archive file with its parent
+/// directories.
+fn create_archive_file(
+    source_dir: &str,
+    archive_path: &str,
+) -> Result<(PathBuf, PathBuf, BufWriter<File>), ArchiveError> {
     let source_path = PathBuf::from(source_dir);
     let archive_path = PathBuf::from(archive_path);
     if !source_path.is_dir() {
@@ -246,28 +951,119 @@ pub fn create_tar_bz2(source_dir: &str, archive_path: &str) -> Result<(), Archiv
             error.to_string(),
         )
     })?;
-    let writer = BufWriter::new(file);
-    let encoder = bzip2::write::BzEncoder::new(writer, bzip2::Compression::best());
-    let mut builder = tar::Builder::new(encoder);
+    Ok((source_path, archive_path, BufWriter::new(file)))
+}
 
-    append_directory_contents(&mut builder, &source_path, &source_path, &archive_path)?;
+/// Archive writer [`append_directory_contents`] adds entries to, under paths
+/// relative to the source directory.
+trait ArchiveSink {
+    fn append_dir(&mut self, relative: &Path, path: &Path) -> std::io::Result<()>;
+    fn append_file(&mut self, relative: &Path, path: &Path) -> std::io::Result<()>;
+}
 
-    let encoder = builder.into_inner().map_err(|error| {
-        ArchiveError::with_target(
-            ArchiveOperation::FinishArchive,
-            &source_path,
-            &archive_path,
-            error.to_string(),
-        )
-    })?;
-    encoder.finish().map_err(|error| {
+impl<W: std::io::Write> ArchiveSink for tar::Builder<W> {
+    fn append_dir(&mut self, relative: &Path, path: &Path) -> std::io::Result<()> {
+        tar::Builder::append_dir(self, relative, path)
+    }
+
+    fn append_file(&mut self, relative: &Path, path: &Path) -> std::io::Result<()> {
+        self.append_path_with_name(path, relative)
+    }
+}
+
+impl<W: std::io::Write + Seek> ArchiveSink for zip::ZipWriter<W> {
+    fn append_dir(&mut self, relative: &Path, _path: &Path) -> std::io::Result<()> {
+        self.add_directory(zip_entry_name(relative), zip_entry_options())
+            .map_err(std::io::Error::other)
+    }
+
+    fn append_file(&mut self, relative: &Path, path: &Path) -> std::io::Result<()> {
+        let mut source = File::open(path)?;
+        self.start_file(zip_entry_name(relative), zip_entry_options())
+            .map_err(std::io::Error::other)?;
+        std::io::copy(&mut source, self)?;
+        Ok(())
+    }
+}
+
+/// Zip entry names always use `/`, whatever the platform separator.
+fn zip_entry_name(relative: &Path) -> String {
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn zip_entry_options() -> zip::write::SimpleFileOptions {
+    zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Deflated)
+}
+
+fn append_directory_contents(
+    builder: &mut impl ArchiveSink,
+    root: &Path,
+    current: &Path,
+    archive_path: &Path,
+) -> Result<(), ArchiveError> {
+    for entry in fs::read_dir(current).map_err(|error| {
         ArchiveError::with_target(
-            ArchiveOperation::FinishArchive,
-            &source_path,
-            &archive_path,
+            ArchiveOperation::ReadSourceDirectory,
+            current,
+            archive_path,
             error.to_string(),
         )
-    })?;
+    })? {
+        let entry = entry.map_err(|error| {
+            ArchiveError::with_target(
+                ArchiveOperation::ReadSourceEntry,
+                current,
+                archive_path,
+                error.to_string(),
+            )
+        })?;
+        let path = entry.path();
+        let relative = path.strip_prefix(root).map_err(|error| {
+            ArchiveError::with_target(
+                ArchiveOperation::ResolveSourceEntry,
+                &path,
+                archive_path,
+                error.to_string(),
+            )
+        })?;
+
+        if entry
+            .file_type()
+            .map_err(|error| {
+                ArchiveError::with_target(
+                    ArchiveOperation::InspectSourceEntry,
+                    &path,
+                    archive_path,
+                    error.to_string(),
+                )
+            })?
+            .is_dir()
+        {
+            builder.append_dir(relative, &path).map_err(|error| {
+                ArchiveError::with_target(
+                    ArchiveOperation::AppendDirectory,
+                    &path,
+                    archive_path,
+                    error.to_string(),
+                )
+            })?;
+            append_directory_contents(builder, root, &path, archive_path)?;
+            continue;
+        }
+
+        builder.append_file(relative, &path).map_err(|error| {
+            ArchiveError::with_target(
+                ArchiveOperation::AppendFile,
+                &path,
+                archive_path,
+                error.to_string(),
+            )
+        })?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,145 @@
+//! Zip path of [`crate::extract_tar_bz2_matching`] and [`crate::scan_tar_bz2`].
+//!
+//! Zip archives keep a central directory instead of being one stream, so
+//! entries are read by index. Filtering, stripping, free-space checks and
+//! progress work as they do for tar; resuming relies on the size check alone
+//! because every entry can be reached without reading the ones before it.
+
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use crate::{
+    ArchiveOperation, ArchiveTotals, EntryFilter, ExtractProgress, ExtractSummary,
+    ensure_disk_space, safe_destination, strip_entry_path,
+};
+
+type ZipResult<T> = Result<T, (ArchiveOperation, String)>;
+
+fn open(file: File) -> ZipResult<zip::ZipArchive<BufReader<File>>> {
+    zip::ZipArchive::new(BufReader::new(file))
+        .map_err(|error| (ArchiveOperation::ReadEntries, error.to_string()))
+}
+
+/// Extracts the entries of the zip `file` matching `filter` under
+/// `target_dir`. Symlink entries are skipped; paths that would leave
+/// `target_dir` fail the extraction.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn extract_zip(
+    file: File,
+    target_dir: &Path,
+    filter: &EntryFilter,
+    resume: bool,
+    strip_components: u32,
+    bytes_total: Option<u64>,
+    cancel: &AtomicBool,
+    on_progress: &mut dyn FnMut(&ExtractProgress<'_>),
+) -> ZipResult<ExtractSummary> {
+    let compressed_total = file
+        .metadata()
+        .map_err(|error| (ArchiveOperation::OpenArchive, error.to_string()))?
+        .len();
+    let mut archive = open(file)?;
+    let mut summary = ExtractSummary::default();
+    let mut compressed_read = 0;
+    let mut bytes_done = 0;
+    let mut last_emit: Option<Instant> = None;
+
+    for index in 0..archive.len() {
+        if cancel.load(Ordering::Relaxed) {
+            return Err((ArchiveOperation::Cancelled, "cancelled".to_string()));
+        }
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| (ArchiveOperation::ReadEntry, error.to_string()))?;
+        let path = entry.name().to_string();
+        let size = entry.size();
+        let entry_compressed_read = compressed_read;
+        let entry_bytes_done = bytes_done;
+        compressed_read += entry.compressed_size();
+        bytes_done += size;
+
+        if !filter.is_empty() && !filter.matches(&path) {
+            summary.skipped += 1;
+            continue;
+        }
+        let Some(relative) = strip_entry_path(&path, strip_components) else {
+            summary.skipped += 1;
+            continue;
+        };
+        summary.matched += 1;
+
+        if last_emit.is_none_or(|last_emit| last_emit.elapsed().as_millis() > 100) {
+            on_progress(&ExtractProgress {
+                path: &path,
+                compressed_read: entry_compressed_read,
+                compressed_total,
+                bytes_done: entry_bytes_done,
+                bytes_total,
+            });
+            last_emit = Some(Instant::now());
+        }
+
+        let Some(destination) = safe_destination(target_dir, &relative)
+            .map_err(|reason| (ArchiveOperation::ExtractEntry, reason))?
+        else {
+            continue;
+        };
+        if entry.is_symlink() {
+            continue;
+        }
+        let extract_error =
+            |error: std::io::Error| (ArchiveOperation::ExtractEntry, error.to_string());
+        if entry.is_dir() {
+            fs::create_dir_all(&destination).map_err(extract_error)?;
+            continue;
+        }
+        if resume
+            && fs::symlink_metadata(&destination)
+                .is_ok_and(|metadata| metadata.is_file() && metadata.len() == size)
+        {
+            summary.resumed += 1;
+            continue;
+        }
+
+        ensure_disk_space(target_dir, size)
+            .map_err(|reason| (ArchiveOperation::CheckFreeSpace, reason))?;
+        // Replace rather than write through a link left at the destination.
+        if fs::symlink_metadata(&destination).is_ok_and(|metadata| metadata.is_symlink()) {
+            fs::remove_file(&destination).map_err(extract_error)?;
+        }
+        let mut output = BufWriter::new(File::create(&destination).map_err(extract_error)?);
+        // Reading to the end checks the entry's CRC.
+        std::io::copy(&mut entry, &mut output).map_err(extract_error)?;
+        output.flush().map_err(extract_error)?;
+        summary.files_extracted += 1;
+        summary.bytes_written += size;
+    }
+    Ok(summary)
+}
+
+/// Reads every entry of the zip `file` to its end, which checks each CRC, and
+/// adds up their sizes.
+pub(crate) fn scan_zip(file: File) -> ZipResult<ArchiveTotals> {
+    let mut archive = open(file)?;
+    let mut totals = ArchiveTotals::default();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| (ArchiveOperation::ReadEntry, error.to_string()))?;
+        let expected = entry.size();
+        let read = std::io::copy(&mut entry, &mut std::io::sink())
+            .map_err(|error| (ArchiveOperation::ReadEntryData, error.to_string()))?;
+        if read != expected {
+            return Err((
+                ArchiveOperation::ReadEntryData,
+                format!("entry ended after {read} of {expected} bytes"),
+            ));
+        }
+        totals.entries += 1;
+        totals.bytes += expected;
+    }
+    Ok(totals)
+}
@@ -1,4 +1,5 @@
 use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use sona_archive::ArchiveOperation;
 
@@ -32,6 +33,56 @@ fn creates_and_extracts_tar_bz2_archive() {
     );
 }
 
+#[test]
+fn creates_tar_gz_archive() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("logs")).unwrap();
+    fs::write(source.join("app-info.json"), "{}").unwrap();
+    fs::write(source.join("logs").join("sona.log"), "line").unwrap();
+    let archive_path = temp.path().join("bundle.tar.gz");
+
+    sona_archive::create_tar_gz(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+
+    let file = fs::File::open(&archive_path).unwrap();
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+    let mut names: Vec<String> = archive
+        .entries()
+        .unwrap()
+        .map(|entry| {
+            entry
+                .unwrap()
+                .path()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["app-info.json", "logs", "logs/sona.log"]);
+}
+
+#[test]
+fn creates_zip_archive() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("logs")).unwrap();
+    fs::write(source.join("app-info.json"), "{}").unwrap();
+    fs::write(source.join("logs").join("sona.log"), "line").unwrap();
+    let archive_path = temp.path().join("bundle.zip");
+
+    sona_archive::create_zip(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+
+    let mut archive = zip::ZipArchive::new(fs::File::open(&archive_path).unwrap()).unwrap();
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+    assert_eq!(names, vec!["app-info.json", "logs/", "logs/sona.log"]);
+    let mut log = String::new();
+    std::io::Read::read_to_string(&mut archive.by_name("logs/sona.log").unwrap(), &mut log)
+        .unwrap();
+    assert_eq!(log, "line");
+}
+
 #[test]
 fn rejects_missing_source_directory() {
     let temp = tempfile::tempdir().unwrap();
@@ -67,3 +118,755 @@ fn extraction_errors_preserve_archive_and_target_paths() {
     assert_eq!(error.source, archive_path);
     assert_eq!(error.target.as_deref(), Some(target_dir.as_path()));
 }
+
+#[test]
+fn extracts_only_entries_matching_prefixes_and_globs() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    for (dir, file) in [
+        ("whisper-tiny", "model.onnx"),
+        ("whisper-tiny", "tokens.txt"),
+        ("whisper-tiny-int8", "model.onnx"),
+        ("sense-voice", "tokens.txt"),
+    ] {
+        fs::create_dir_all(source.join(dir)).unwrap();
+        fs::write(source.join(dir).join(file), format!("{dir}/{file}")).unwrap();
+    }
+    let archive_path = temp.path().join("bundle.tar.bz2");
+    let extract_dir = temp.path().join("extract");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+
+    let summary = sona_archive::extract_tar_bz2_matching(
+        archive_path.to_str().unwrap(),
+        extract_dir.to_str().unwrap(),
+        &["whisper-tiny/".to_string(), "*/tokens.txt".to_string()],
+        false,
+        0,
+        None,
+        &AtomicBool::new(false),
+        |_| {},
+    )
+    .unwrap();
+
+    assert!(extract_dir.join("whisper-tiny").join("model.onnx").exists());
+    assert!(extract_dir.join("whisper-tiny").join("tokens.txt").exists());
+    assert!(extract_dir.join("sense-voice").join("tokens.txt").exists());
+    assert!(!extract_dir.join("whisper-tiny-int8").exists());
+    // whisper-tiny dir + 2 files, sense-voice/tokens.txt.
+    assert_eq!(summary.matched, 4);
+    // whisper-tiny-int8 dir + file, sense-voice dir.
+    assert_eq!(summary.skipped, 3);
+}
+
+#[test]
+fn extract_without_include_filter_matches_every_entry() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("nested")).unwrap();
+    fs::write(source.join("nested").join("child.txt"), "child").unwrap();
+    let archive_path = temp.path().join("archive.tar.bz2");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+
+    let summary = sona_archive::extract_tar_bz2_matching(
+        archive_path.to_str().unwrap(),
+        temp.path().join("extract").to_str().unwrap(),
+        &[],
+        false,
+        0,
+        None,
+        &AtomicBool::new(false),
+        |_| {},
+    )
+    .unwrap();
+
+    assert_eq!(
+        summary,
+        sona_archive::ExtractSummary {
+            matched: 2,
+            skipped: 0,
+            resumed: 0,
+            files_extracted: 1,
+            bytes_written: 5,
+        }
+    );
+}
+
+#[test]
+fn extraction_progress_counts_compressed_bytes_read() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("model.onnx"), vec![7_u8; 64 * 1024]).unwrap();
+    let archive_path = temp.path().join("archive.tar.bz2");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+    let archive_size = fs::metadata(&archive_path).unwrap().len();
+
+    let mut progress = Vec::new();
+    sona_archive::extract_tar_bz2_matching(
+        archive_path.to_str().unwrap(),
+        temp.path().join("extract").to_str().unwrap(),
+        &[],
+        false,
+        0,
+        None,
+        &AtomicBool::new(false),
+        |update| {
+            progress.push((
+                update.path.to_string(),
+                update.compressed_read,
+                update.compressed_total,
+            ))
+        },
+    )
+    .unwrap();
+
+    let (path, read, total) = &progress[0];
+    assert_eq!(path, "model.onnx");
+    assert_eq!(*total, archive_size);
+    assert!(*read > 0 && read <= total);
+}
+
+#[test]
+fn scanned_extraction_reports_a_percentage() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("a.onnx"), vec![1_u8; 3000]).unwrap();
+    fs::write(source.join("b.txt"), vec![2_u8; 1000]).unwrap();
+    let archive_path = temp.path().join("archive.tar.bz2");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+    let archive_path = archive_path.to_str().unwrap();
+
+    let totals = sona_archive::scan_tar_bz2(archive_path).unwrap();
+    assert_eq!(
+        totals,
+        sona_archive::ArchiveTotals {
+            entries: 2,
+            bytes: 4000
+        }
+    );
+
+    let mut progress = Vec::new();
+    sona_archive::extract_tar_bz2_matching(
+        archive_path,
+        temp.path().join("extract").to_str().unwrap(),
+        &[],
+        false,
+        0,
+        Some(totals.bytes),
+        &AtomicBool::new(false),
+        |update| progress.push((update.bytes_done, update.bytes_total, update.percent())),
+    )
+    .unwrap();
+    assert_eq!(progress[0], (0, Some(4000), Some(0.0)));
+
+    let unscanned = sona_archive::ExtractProgress {
+        path: "a.onnx",
+        compressed_read: 10,
+        compressed_total: 20,
+        bytes_done: 3000,
+        bytes_total: None,
+    };
+    assert_eq!(unscanned.percent(), None);
+    assert_eq!(
+        sona_archive::ExtractProgress {
+            bytes_total: Some(4000),
+            ..unscanned
+        }
+        .percent(),
+        Some(75.0)
+    );
+}
+
+#[test]
+fn rejects_invalid_include_patterns() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive_path = temp.path().join("archive.tar.bz2");
+
+    let error = sona_archive::extract_tar_bz2_matching(
+        archive_path.to_str().unwrap(),
+        temp.path().join("extract").to_str().unwrap(),
+        &["models/[".to_string()],
+        false,
+        0,
+        None,
+        &AtomicBool::new(false),
+        |_| {},
+    )
+    .unwrap_err();
+
+    assert_eq!(error.operation, ArchiveOperation::ParseIncludePattern);
+    assert!(error.reason.contains("Invalid include pattern"));
+}
+
+#[test]
+fn resumed_extraction_skips_files_already_unpacked() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("model")).unwrap();
+    fs::write(source.join("model").join("model.onnx"), "weights").unwrap();
+    fs::write(source.join("model").join("tokens.txt"), "tokens").unwrap();
+    let archive_path = temp.path().join("model.tar.bz2");
+    let extract_dir = temp.path().join("extract");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+    let extract = |resume| {
+        sona_archive::extract_tar_bz2_matching(
+            archive_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            &[],
+            resume,
+            0,
+            None,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .unwrap()
+    };
+
+    assert_eq!(extract(true).resumed, 0);
+    // Simulate a file cut short by an interrupted run.
+    let truncated = extract_dir.join("model").join("tokens.txt");
+    fs::write(&truncated, "tok").unwrap();
+
+    let summary = extract(true);
+
+    assert_eq!(summary.matched, 3);
+    assert_eq!(summary.resumed, 1);
+    assert_eq!(summary.files_extracted, 1);
+    assert_eq!(summary.bytes_written, "tokens".len() as u64);
+    assert_eq!(fs::read_to_string(&truncated).unwrap(), "tokens");
+    assert_eq!(extract(false).resumed, 0);
+}
+
+#[test]
+fn resumed_extraction_trusts_a_matching_checkpoint_and_removes_it() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("model")).unwrap();
+    fs::write(source.join("model").join("model.onnx"), "weights").unwrap();
+    fs::write(source.join("model").join("tokens.txt"), "tokens").unwrap();
+    let archive_path = temp.path().join("model.tar.bz2");
+    let archive = archive_path.to_str().unwrap();
+    let extract_dir = temp.path().join("extract");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive).unwrap();
+    let checkpoint_path = sona_archive::extraction_checkpoint_path(&archive_path);
+    let checkpoint = sona_archive::ExtractionCheckpoint {
+        target_dir: extract_dir.clone(),
+        include: Vec::new(),
+        strip_components: 0,
+        entries_done: 3,
+        bytes_done: ("weights".len() + "tokens".len()) as u64,
+        compressed_read: 0,
+        compressed_total: fs::metadata(&archive_path).unwrap().len(),
+    };
+    let extract = |resume, strip_components| {
+        sona_archive::extract_tar_bz2_matching(
+            archive,
+            extract_dir.to_str().unwrap(),
+            &[],
+            resume,
+            strip_components,
+            None,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .unwrap()
+    };
+
+    // A checkpoint for another strip count does not apply.
+    fs::write(
+        &checkpoint_path,
+        serde_json::to_vec(&sona_archive::ExtractionCheckpoint {
+            strip_components: 1,
+            ..checkpoint.clone()
+        })
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(extract(true, 0).files_extracted, 2);
+    assert!(!checkpoint_path.exists());
+
+    fs::remove_dir_all(&extract_dir).unwrap();
+    fs::write(&checkpoint_path, serde_json::to_vec(&checkpoint).unwrap()).unwrap();
+    assert_eq!(
+        sona_archive::read_extraction_checkpoint(archive),
+        Some(checkpoint)
+    );
+
+    let summary = extract(true, 0);
+
+    assert_eq!(summary.matched, 3);
+    assert_eq!(summary.resumed, 2);
+    assert_eq!(summary.files_extracted, 0);
+    assert!(!checkpoint_path.exists());
+    assert_eq!(sona_archive::read_extraction_checkpoint(archive), None);
+}
+
+#[test]
+fn strip_components_drops_the_leading_directory() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("whisper-tiny").join("nested")).unwrap();
+    fs::write(source.join("whisper-tiny").join("tokens.txt"), "tokens").unwrap();
+    fs::write(
+        source
+            .join("whisper-tiny")
+            .join("nested")
+            .join("model.onnx"),
+        "weights",
+    )
+    .unwrap();
+    let archive_path = temp.path().join("model.tar.bz2");
+    let extract_dir = temp.path().join("models").join("whisper-tiny");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+    let extract = |resume| {
+        sona_archive::extract_tar_bz2_matching(
+            archive_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            &["whisper-tiny/".to_string()],
+            resume,
+            1,
+            None,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .unwrap()
+    };
+
+    let summary = extract(false);
+
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("tokens.txt")).unwrap(),
+        "tokens"
+    );
+    assert!(extract_dir.join("nested").join("model.onnx").exists());
+    assert!(!extract_dir.join("whisper-tiny").exists());
+    // The top-level directory entry has nothing left once stripped.
+    assert_eq!(summary.matched, 3);
+    assert_eq!(summary.skipped, 1);
+    assert_eq!(extract(true).resumed, 2);
+}
+
+#[test]
+fn collapses_a_lone_top_level_directory() {
+    let temp = tempfile::tempdir().unwrap();
+    let model_dir = temp.path().join("sense-voice");
+    let nested = model_dir.join("sense-voice");
+    fs::create_dir_all(nested.join("sense-voice")).unwrap();
+    fs::write(nested.join("tokens.txt"), "tokens").unwrap();
+    let model_dir = model_dir.to_str().unwrap();
+
+    assert!(sona_archive::collapse_single_top_level_dir(model_dir).unwrap());
+
+    let model_dir = std::path::Path::new(model_dir);
+    assert_eq!(
+        fs::read_to_string(model_dir.join("tokens.txt")).unwrap(),
+        "tokens"
+    );
+    // A child named like its folder moves up too.
+    assert!(model_dir.join("sense-voice").is_dir());
+    assert_eq!(fs::read_dir(model_dir).unwrap().count(), 2);
+    assert!(!sona_archive::collapse_single_top_level_dir(model_dir.to_str().unwrap()).unwrap());
+}
+
+#[test]
+fn verification_reads_every_entry_and_rejects_truncated_archives() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    let weights = (0..200_000u32)
+        .flat_map(|value| value.wrapping_mul(2_654_435_761).to_le_bytes())
+        .collect::<Vec<_>>();
+    fs::write(source.join("model.onnx"), &weights).unwrap();
+    fs::write(source.join("tokens.txt"), "tokens").unwrap();
+    let archive_path = temp.path().join("model.tar.bz2");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+
+    assert_eq!(
+        sona_archive::verify_tar_bz2(archive_path.to_str().unwrap()).unwrap(),
+        2
+    );
+
+    let bytes = fs::read(&archive_path).unwrap();
+    let truncated_path = temp.path().join("truncated.tar.bz2");
+    fs::write(&truncated_path, &bytes[..bytes.len() / 2]).unwrap();
+    let error = sona_archive::verify_tar_bz2(truncated_path.to_str().unwrap()).unwrap_err();
+    assert_eq!(error.source, truncated_path);
+    assert_eq!(error.target, None);
+}
+
+fn write_plain_tar(source: &std::path::Path, archive_path: &std::path::Path) {
+    let mut builder = tar::Builder::new(fs::File::create(archive_path).unwrap());
+    builder.append_dir_all(".", source).unwrap();
+    builder.finish().unwrap();
+}
+
+#[test]
+fn extraction_refuses_entries_larger_than_the_free_space() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive_path = temp.path().join("huge.tar");
+    // A header announcing a petabyte; extraction must stop before its data.
+    let mut header = tar::Header::new_gnu();
+    header.set_path("model.onnx").unwrap();
+    header.set_size(1 << 50);
+    header.set_mode(0o644);
+    header.set_cksum();
+    fs::write(&archive_path, header.as_bytes()).unwrap();
+    let extract_dir = temp.path().join("extract");
+
+    let error = sona_archive::extract_tar_bz2(
+        archive_path.to_str().unwrap(),
+        extract_dir.to_str().unwrap(),
+        |_| {},
+    )
+    .unwrap_err();
+
+    assert_eq!(error.operation, ArchiveOperation::CheckFreeSpace);
+    assert!(
+        error.reason.starts_with(&format!(
+            "insufficient disk space: need {} bytes",
+            (1_u64 << 50) + sona_archive::DISK_SPACE_MARGIN
+        )),
+        "{error}"
+    );
+    assert!(!extract_dir.join("model.onnx").exists());
+}
+
+#[test]
+fn extraction_picks_the_decoder_from_magic_bytes_not_the_extension() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    fs::write(source.join("tokens.txt"), "tokens").unwrap();
+
+    let gzip_named_bz2 = temp.path().join("gzip.tar.bz2");
+    sona_archive::create_tar_gz(source.to_str().unwrap(), gzip_named_bz2.to_str().unwrap())
+        .unwrap();
+    let bzip2_named_gz = temp.path().join("bzip2.tar.gz");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), bzip2_named_gz.to_str().unwrap())
+        .unwrap();
+    let plain_named_bz2 = temp.path().join("plain.tar.bz2");
+    write_plain_tar(&source, &plain_named_bz2);
+
+    for archive_path in [gzip_named_bz2, bzip2_named_gz, plain_named_bz2] {
+        let extract_dir = temp.path().join(format!(
+            "extract-{}",
+            archive_path.file_name().unwrap().to_string_lossy()
+        ));
+        sona_archive::extract_tar_bz2(
+            archive_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            |_| {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(extract_dir.join("tokens.txt")).unwrap(),
+            "tokens",
+            "{}",
+            archive_path.display()
+        );
+    }
+}
+
+#[test]
+fn detects_archive_formats_from_magic_bytes() {
+    use sona_archive::{ArchiveFormat, detect_archive_format};
+    use std::path::Path;
+
+    let named = Path::new("model.tar.bz2");
+    assert_eq!(
+        detect_archive_format(b"BZh91AY&SY", named),
+        Ok(ArchiveFormat::Bzip2)
+    );
+    assert_eq!(
+        detect_archive_format(&[0x1f, 0x8b, 0x08, 0x00], named),
+        Ok(ArchiveFormat::Gzip)
+    );
+    assert_eq!(
+        detect_archive_format(&[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00], named),
+        Ok(ArchiveFormat::Xz)
+    );
+    assert_eq!(
+        detect_archive_format(b"PK\x03\x04", named),
+        Ok(ArchiveFormat::Zip)
+    );
+
+    let mut tar_header = vec![0; 512];
+    tar_header[257..262].copy_from_slice(b"ustar");
+    assert_eq!(
+        detect_archive_format(&tar_header, named),
+        Ok(ArchiveFormat::Tar)
+    );
+    assert_eq!(
+        detect_archive_format(&[0; 512], Path::new("legacy.tar")),
+        Ok(ArchiveFormat::Tar)
+    );
+    assert_eq!(
+        detect_archive_format(&[0; 512], named),
+        Err("unrecognized archive format (magic bytes: [00 00 00 00 00 00 00 00])".to_string())
+    );
+}
+
+#[test]
+fn rejects_corrupt_and_unrecognized_archives() {
+    let temp = tempfile::tempdir().unwrap();
+    let zip_named_bz2 = temp.path().join("zip.tar.bz2");
+    fs::write(&zip_named_bz2, b"PK\x03\x04rest of a zip file").unwrap();
+    let text_named_bz2 = temp.path().join("text.tar.bz2");
+    fs::write(&text_named_bz2, b"<html>not found</html>").unwrap();
+    let extract = |archive_path: &std::path::Path| {
+        sona_archive::extract_tar_bz2(
+            archive_path.to_str().unwrap(),
+            temp.path().join("extract").to_str().unwrap(),
+            |_| {},
+        )
+        .unwrap_err()
+    };
+
+    let error = extract(&text_named_bz2);
+    assert_eq!(error.operation, ArchiveOperation::DetectFormat);
+    assert_eq!(
+        error.reason,
+        "unrecognized archive format (magic bytes: [3c 68 74 6d 6c 3e 6e 6f])"
+    );
+    // Read as the zip its magic bytes say it is, whatever the name.
+    assert_eq!(
+        extract(&zip_named_bz2).operation,
+        ArchiveOperation::ReadEntries
+    );
+}
+
+#[test]
+fn extracts_tar_xz_archives() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(source.join("model")).unwrap();
+    fs::write(source.join("model").join("tokens.txt"), "xz tokens").unwrap();
+    let archive_path = temp.path().join("model.tar.xz");
+    let encoder = xz2::write::XzEncoder::new(fs::File::create(&archive_path).unwrap(), 6);
+    let mut builder = tar::Builder::new(encoder);
+    builder.append_dir_all(".", &source).unwrap();
+    builder.into_inner().unwrap().finish().unwrap();
+    let archive_path = archive_path.to_str().unwrap();
+    let extract_dir = temp.path().join("extract");
+
+    assert_eq!(sona_archive::verify_tar_bz2(archive_path).unwrap(), 3);
+    sona_archive::extract_tar_bz2(archive_path, extract_dir.to_str().unwrap(), |_| {}).unwrap();
+
+    assert_eq!(
+        fs::read_to_string(extract_dir.join("model").join("tokens.txt")).unwrap(),
+        "xz tokens"
+    );
+}
+
+fn write_zip(archive_path: &std::path::Path, entries: &[(&str, &[u8])]) {
+    use std::io::Write;
+
+    let mut writer = zip::ZipWriter::new(fs::File::create(archive_path).unwrap());
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, contents) in entries {
+        if name.ends_with('/') {
+            writer.add_directory(*name, options).unwrap();
+        } else {
+            writer.start_file(*name, options).unwrap();
+            writer.write_all(contents).unwrap();
+        }
+    }
+    writer.finish().unwrap();
+}
+
+#[test]
+fn extracts_zip_archives_with_filters_and_stripped_paths() {
+    let temp = tempfile::tempdir().unwrap();
+    let archive_path = temp.path().join("model.zip");
+    write_zip(
+        &archive_path,
+        &[
+            ("bundle/", b""),
+            ("bundle/model.onnx", b"zip weights"),
+            ("bundle/docs/README.md", b"readme"),
+        ],
+    );
+    let archive_path = archive_path.to_str().unwrap();
+    let extract_dir = temp.path().join("extract");
+
+    let totals = sona_archive::scan_tar_bz2(archive_path).unwrap();
+    assert_eq!(totals.entries, 3);
+    assert_eq!(totals.bytes, 11 + 6);
+
+    let mut progress = Vec::new();
+    let summary = sona_archive::extract_tar_bz2_matching(
+        archive_path,
+        extract_dir.to_str().unwrap(),
+        &["bundle/model.onnx".to_string()],
+        false,
+        1,
+        Some(totals.bytes),
+        &AtomicBool::new(false),
+        |update| progress.push(update.path.to_string()),
+    )
+    .unwrap();
+
+    assert_eq!(
+        fs::read(extract_dir.join("model.onnx")).unwrap(),
+        b"zip weights"
+    );
+    assert!(!extract_dir.join("docs").exists());
+    assert_eq!(summary.files_extracted, 1);
+    assert_eq!(summary.skipped, 2);
+    assert_eq!(progress[0], "bundle/model.onnx");
+
+    let resumed = sona_archive::extract_tar_bz2_matching(
+        archive_path,
+        extract_dir.to_str().unwrap(),
+        &[],
+        true,
+        1,
+        None,
+        &AtomicBool::new(false),
+        |_| {},
+    )
+    .unwrap();
+    assert_eq!(resumed.resumed, 1);
+    assert_eq!(resumed.files_extracted, 1);
+    assert_eq!(
+        fs::read(extract_dir.join("docs").join("README.md")).unwrap(),
+        b"readme"
+    );
+}
+
+/// A bz2 tar holding `entries` as given. `tar::Builder` refuses `..` in
+/// paths, so names are written into the header directly.
+fn write_crafted_tar_bz2(archive_path: &std::path::Path, entries: &[(&str, tar::EntryType, &str)]) {
+    let encoder = bzip2::write::BzEncoder::new(
+        fs::File::create(archive_path).unwrap(),
+        bzip2::Compression::fast(),
+    );
+    let mut builder = tar::Builder::new(encoder);
+    for (name, entry_type, contents) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+        header.set_entry_type(*entry_type);
+        header.set_mode(0o755);
+        if entry_type.is_symlink() {
+            header.set_link_name(contents).unwrap();
+            header.set_size(0);
+            header.set_cksum();
+            builder.append(&header, std::io::empty()).unwrap();
+        } else {
+            header.set_size(contents.len() as u64);
+            header.set_cksum();
+            builder.append(&header, contents.as_bytes()).unwrap();
+        }
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+#[test]
+fn entries_escaping_the_target_fail_the_extraction() {
+    let temp = tempfile::tempdir().unwrap();
+    let extract_dir = temp.path().join("nested").join("extract");
+    let outside = temp.path().join("outside");
+    fs::create_dir_all(&outside).unwrap();
+    let regular = tar::EntryType::Regular;
+
+    let dot_dot = temp.path().join("dot-dot.tar.bz2");
+    write_crafted_tar_bz2(
+        &dot_dot,
+        &[("ok.txt", regular, "fine"), ("../evil", regular, "evil")],
+    );
+    let through_symlink = temp.path().join("symlink.tar.bz2");
+    write_crafted_tar_bz2(
+        &through_symlink,
+        &[
+            ("link", tar::EntryType::Symlink, outside.to_str().unwrap()),
+            ("link/evil", regular, "evil"),
+        ],
+    );
+    let zip_path = temp.path().join("dot-dot.zip");
+    {
+        use std::io::Write;
+
+        // `ZipWriter` keeps names as given, `..` included.
+        let mut writer = zip::ZipWriter::new(fs::File::create(&zip_path).unwrap());
+        writer
+            .start_file("../evil", zip::write::SimpleFileOptions::default())
+            .unwrap();
+        writer.write_all(b"evil").unwrap();
+        writer.finish().unwrap();
+    }
+
+    for (archive_path, strip_components) in [(&dot_dot, 0), (&through_symlink, 0), (&zip_path, 0)] {
+        let error = sona_archive::extract_tar_bz2_matching(
+            archive_path.to_str().unwrap(),
+            extract_dir.to_str().unwrap(),
+            &[],
+            false,
+            strip_components,
+            None,
+            &AtomicBool::new(false),
+            |_| {},
+        )
+        .unwrap_err();
+
+        assert_eq!(error.operation, ArchiveOperation::ExtractEntry);
+        assert!(
+            error.reason.starts_with("unsafe path in archive"),
+            "{}: {}",
+            archive_path.display(),
+            error.reason
+        );
+        assert!(!temp.path().join("nested").join("evil").exists());
+        assert!(!outside.join("evil").exists());
+    }
+}
+
+#[test]
+fn cancelled_extraction_stops_between_entries_and_resumes() {
+    let temp = tempfile::tempdir().unwrap();
+    let source = temp.path().join("source");
+    fs::create_dir_all(&source).unwrap();
+    for name in ["a.txt", "b.txt", "c.txt"] {
+        fs::write(source.join(name), name).unwrap();
+    }
+    let archive_path = temp.path().join("model.tar.bz2");
+    sona_archive::create_tar_bz2(source.to_str().unwrap(), archive_path.to_str().unwrap()).unwrap();
+    let archive_path = archive_path.to_str().unwrap();
+    let extract_dir = temp.path().join("extract");
+    let extract = |resume, cancel: &AtomicBool| {
+        sona_archive::extract_tar_bz2_matching(
+            archive_path,
+            extract_dir.to_str().unwrap(),
+            &[],
+            resume,
+            0,
+            None,
+            cancel,
+            // Progress is reported for the first entry, before it is written.
+            |_| cancel.store(true, Ordering::Relaxed),
+        )
+    };
+
+    let error = extract(false, &AtomicBool::new(false)).unwrap_err();
+    assert!(error.is_cancelled());
+    let extracted = fs::read_dir(&extract_dir).unwrap().count();
+    assert_eq!(extracted, 1);
+    assert!(sona_archive::read_extraction_checkpoint(archive_path).is_some());
+
+    let summary = sona_archive::extract_tar_bz2_matching(
+        archive_path,
+        extract_dir.to_str().unwrap(),
+        &[],
+        true,
+        0,
+        None,
+        &AtomicBool::new(false),
+        |_| {},
+    )
+    .unwrap();
+    assert_eq!(summary.resumed, 1);
+    assert_eq!(summary.files_extracted, 2);
+    assert_eq!(fs::read_dir(&extract_dir).unwrap().count(), 3);
+}
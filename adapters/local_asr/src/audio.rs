@@ -1,7 +1,14 @@
 use hound::{SampleFormat, WavSpec, WavWriter};
 use sherpa_onnx::{SileroVadModelConfig, VadModelConfig, VoiceActivityDetector};
 use sona_core::ports::asr::{AsrPortError, AsrPortErrorKind, BatchSegmentationMode};
+use sona_core::runtime::capture::{
+    FfmpegStderrLevel, FfmpegStderrTail, RecordCodec, parse_ffmpeg_duration_line,
+    parse_ffmpeg_encoder_names, parse_ffmpeg_input_device_names, parse_ffmpeg_progress_line,
+    parse_ffmpeg_progress_seconds, silence_trim_filter, supported_record_codecs,
+};
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
 
 pub(crate) type VadConfig = VadModelConfig;
 pub(crate) type VadDetector = VoiceActivityDetector;
@@ -126,6 +133,14 @@ pub fn pcm_s16le_bytes_to_f32(bytes: &[u8]) -> Vec<f32> {
         .collect()
 }
 
+/// Same scaling as the WAV recorder, so piped and recorded audio match.
+pub fn pcm_f32_to_s16le_bytes(samples: &[f32]) -> Vec<u8> {
+    samples
+        .iter()
+        .flat_map(|&sample| f32_to_i16_sample(sample).to_le_bytes())
+        .collect()
+}
+
 fn mono_pcm16_wav_spec(sample_rate: u32) -> WavSpec {
     WavSpec {
         channels: 1,
@@ -174,6 +189,36 @@ impl LiveWavRecorder {
 
         Ok(self.filepath)
     }
+
+    /// Finalizes the WAV header, syncs the file to disk and reads the header
+    /// back, so the returned info describes what was actually written.
+    pub fn finalize_with_info(self) -> hound::Result<WavFileInfo> {
+        let filepath = self.finalize()?;
+        std::fs::File::open(&filepath)?.sync_all()?;
+        read_wav_file_info(&filepath)
+    }
+}
+
+/// What a finished WAV file holds, as read from its header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct WavFileInfo {
+    pub path: PathBuf,
+    pub duration_secs: f64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub bytes: u64,
+}
+
+pub fn read_wav_file_info(filepath: &Path) -> hound::Result<WavFileInfo> {
+    let reader = hound::WavReader::open(filepath)?;
+    let spec = reader.spec();
+    Ok(WavFileInfo {
+        path: filepath.to_path_buf(),
+        duration_secs: f64::from(reader.duration()) / f64::from(spec.sample_rate),
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        bytes: std::fs::metadata(filepath)?.len(),
+    })
 }
 
 pub fn save_wav_file(data: &[f32], sample_rate: u32, filepath: &Path) -> hound::Result<()> {
@@ -184,36 +229,121 @@ pub fn save_wav_file(data: &[f32], sample_rate: u32, filepath: &Path) -> hound::
     writer.finalize()
 }
 
-pub async fn extract_and_resample_audio(
-    filepath: &Path,
-    target_sample_rate: u32,
-) -> Result<Vec<f32>, AsrPortError> {
+fn ffmpeg_command() -> Result<tokio::process::Command, AsrPortError> {
+    ffmpeg_blocking_command().map(tokio::process::Command::from)
+}
+
+/// Runs FFmpeg to completion and returns its stdout.
+///
+/// Stderr is streamed through a bounded [`FfmpegStderrTail`] instead of being
+/// collected whole, so a decoder stuck repeating the same error neither grows
+/// memory nor floods the log. The retained tail is used for the error message.
+async fn run_ffmpeg(command: tokio::process::Command) -> Result<Vec<u8>, AsrPortError> {
+    run_ffmpeg_with_stderr(command, |_| false).await
+}
+
+/// [`run_ffmpeg`] with every stderr line offered to `on_stderr_line` first.
+/// Lines it returns `true` for are consumed and kept out of the log.
+async fn run_ffmpeg_with_stderr(
+    mut command: tokio::process::Command,
+    mut on_stderr_line: impl FnMut(&str) -> bool + Send,
+) -> Result<Vec<u8>, AsrPortError> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| {
+            AsrPortError::new(
+                AsrPortErrorKind::FileSystem,
+                format!("Failed to run ffmpeg command: {error}"),
+            )
+        })?;
+
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+
+    let read_stdout = async move {
+        let mut buffer = Vec::new();
+        if let Some(mut stdout) = stdout {
+            stdout.read_to_end(&mut buffer).await?;
+        }
+        Ok::<_, std::io::Error>(buffer)
+    };
+    let read_stderr = async move {
+        let mut tail = FfmpegStderrTail::default();
+        if let Some(stderr) = stderr {
+            let mut lines = BufReader::new(stderr).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if on_stderr_line(&line) {
+                    continue;
+                }
+                match tail.push(&line) {
+                    Some((FfmpegStderrLevel::Warn, line)) => log::warn!("[FFmpeg] {line}"),
+                    Some((FfmpegStderrLevel::Debug, line)) => log::debug!("[FFmpeg] {line}"),
+                    None => {}
+                }
+            }
+        }
+        if tail.suppressed() > 0 {
+            log::warn!(
+                "[FFmpeg] Suppressed {} further stderr line(s)",
+                tail.suppressed()
+            );
+        }
+        tail
+    };
+
+    let (stdout, tail) = tokio::join!(read_stdout, read_stderr);
+    let status = child.wait().await.map_err(|error| {
+        AsrPortError::new(
+            AsrPortErrorKind::FileSystem,
+            format!("Failed to wait for ffmpeg command: {error}"),
+        )
+    })?;
+
+    if !status.success() {
+        return Err(AsrPortError::runtime(format!(
+            "FFmpeg exited with {:?}: {}",
+            status,
+            tail.summary()
+        )));
+    }
+
+    stdout.map_err(|error| {
+        AsrPortError::new(
+            AsrPortErrorKind::FileSystem,
+            format!("Failed to read ffmpeg output: {error}"),
+        )
+    })
+}
+
+/// Blocking counterpart of [`ffmpeg_command`], which is built from it so both
+/// start the sidecar the same way.
+fn ffmpeg_blocking_command() -> Result<std::process::Command, AsrPortError> {
     let ffmpeg_path = resolve_ffmpeg_sidecar_path()?;
-    let mut command = tokio::process::Command::new(ffmpeg_path);
 
     #[cfg(target_os = "windows")]
     {
-        #[allow(unused_imports)]
         use std::os::windows::process::CommandExt;
+        let mut command = std::process::Command::new(ffmpeg_path);
         command.creation_flags(0x0800_0000);
+        Ok(command)
     }
 
-    let output = command
-        .arg("-loglevel")
-        .arg("error")
-        .arg("-i")
-        .arg(filepath)
-        .arg("-f")
-        .arg("s16le")
-        .arg("-acodec")
-        .arg("pcm_s16le")
-        .arg("-ar")
-        .arg(target_sample_rate.to_string())
-        .arg("-ac")
-        .arg("1")
-        .arg("-")
+    #[cfg(not(target_os = "windows"))]
+    {
+        Ok(std::process::Command::new(ffmpeg_path))
+    }
+}
+
+/// Runs the bundled FFmpeg sidecar with a listing option such as
+/// `-encoders` and returns its stdout.
+fn ffmpeg_listing(option: &str) -> Result<String, AsrPortError> {
+    let output = ffmpeg_blocking_command()?
+        .arg("-hide_banner")
+        .arg(option)
         .output()
-        .await
         .map_err(|error| {
             AsrPortError::new(
                 AsrPortErrorKind::FileSystem,
@@ -229,7 +359,202 @@ pub async fn extract_and_resample_audio(
         )));
     }
 
-    Ok(pcm_s16le_bytes_to_f32(&output.stdout))
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Lists the encoders compiled into the bundled FFmpeg sidecar.
+///
+/// Synchronous so capture start-up can validate a requested recording codec
+/// before any hardware stream is opened.
+pub fn probe_ffmpeg_encoders() -> Result<Vec<String>, AsrPortError> {
+    Ok(parse_ffmpeg_encoder_names(&ffmpeg_listing("-encoders")?))
+}
+
+/// Lists the input devices (capture backends such as `dshow` or `pulse`)
+/// compiled into the bundled FFmpeg sidecar.
+pub fn probe_ffmpeg_input_devices() -> Result<Vec<String>, AsrPortError> {
+    Ok(parse_ffmpeg_input_device_names(&ffmpeg_listing(
+        "-devices",
+    )?))
+}
+
+/// Starts the bundled FFmpeg generating `duration` of a 440 Hz sine from its
+/// `sine` source, paced in real time and written to stdout as interleaved
+/// f32le at `sample_rate` with `channels` identical channels. The tone is at
+/// [`FFMPEG_TEST_TONE_PEAK`]. Stderr is piped so a failure can be reported.
+pub fn spawn_ffmpeg_test_tone(
+    sample_rate: u32,
+    channels: u16,
+    duration: std::time::Duration,
+) -> Result<std::process::Child, AsrPortError> {
+    ffmpeg_blocking_command()?
+        .args(["-hide_banner", "-nostdin", "-loglevel", "error", "-re"])
+        .args(["-f", "lavfi", "-i"])
+        .arg(format!(
+            "sine=frequency=440:sample_rate={sample_rate}:duration={}",
+            duration.as_secs_f64()
+        ))
+        .args(["-ac", &channels.to_string(), "-f", "f32le", "pipe:1"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| {
+            AsrPortError::new(
+                AsrPortErrorKind::FileSystem,
+                format!("Failed to run ffmpeg command: {error}"),
+            )
+        })
+}
+
+/// Peak amplitude of [`spawn_ffmpeg_test_tone`]; FFmpeg's `sine` source
+/// plays at 1/8 of full scale.
+pub const FFMPEG_TEST_TONE_PEAK: f32 = 0.125;
+
+/// Record codecs the bundled FFmpeg can encode, so the UI can offer only
+/// those instead of failing when a recording starts.
+pub fn probe_supported_record_codecs() -> Result<Vec<RecordCodec>, AsrPortError> {
+    Ok(supported_record_codecs(&probe_ffmpeg_encoders()?))
+}
+
+pub fn ensure_ffmpeg_supports_record_codec(codec: RecordCodec) -> Result<(), AsrPortError> {
+    let Some(encoder) = codec.ffmpeg_encoder() else {
+        return Ok(());
+    };
+
+    if probe_ffmpeg_encoders()?
+        .iter()
+        .any(|available| available == encoder)
+    {
+        return Ok(());
+    }
+
+    Err(AsrPortError::runtime(format!(
+        "Bundled FFmpeg does not support the {} encoder required for {} recordings",
+        encoder,
+        codec.as_str()
+    )))
+}
+
+/// Re-encodes a finalized WAV recording with `codec` and removes the WAV on
+/// success. PCM recordings are returned unchanged.
+pub async fn encode_recording(
+    recording_path: &Path,
+    codec: RecordCodec,
+) -> Result<PathBuf, AsrPortError> {
+    let Some(encoder) = codec.ffmpeg_encoder() else {
+        return Ok(recording_path.to_path_buf());
+    };
+    let encoded_path = codec.encoded_output_path(recording_path);
+
+    let mut command = ffmpeg_command()?;
+    command
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-y")
+        .arg("-i")
+        .arg(recording_path)
+        .arg("-c:a")
+        .arg(encoder)
+        .arg(&encoded_path);
+    if let Err(error) = run_ffmpeg(command).await {
+        let _ = tokio::fs::remove_file(&encoded_path).await;
+        return Err(error);
+    }
+
+    tokio::fs::remove_file(recording_path)
+        .await
+        .map_err(|error| {
+            AsrPortError::new(
+                AsrPortErrorKind::FileSystem,
+                format!(
+                    "Failed to remove intermediate recording {}: {error}",
+                    recording_path.display()
+                ),
+            )
+        })?;
+
+    Ok(encoded_path)
+}
+
+/// Writes `input` to `output` with leading and trailing silence removed and
+/// returns the new duration in seconds.
+///
+/// `on_progress` receives the output position in seconds and, once FFmpeg has
+/// probed the input, the input duration. Both come from FFmpeg's stderr.
+pub async fn trim_silence(
+    input: &Path,
+    output: &Path,
+    threshold_db: Option<f64>,
+    min_silence_ms: Option<u32>,
+    mut on_progress: impl FnMut(f64, Option<f64>) + Send,
+) -> Result<f64, AsrPortError> {
+    let filter = silence_trim_filter(threshold_db, min_silence_ms)
+        .map_err(|error| AsrPortError::invalid_request(error.to_string()))?;
+    if input == output {
+        return Err(AsrPortError::invalid_request(
+            "Trimmed output must be written to a different file than the input",
+        ));
+    }
+
+    let mut command = ffmpeg_command()?;
+    command
+        .arg("-hide_banner")
+        .arg("-nostats")
+        .arg("-loglevel")
+        .arg("info")
+        .arg("-progress")
+        .arg("pipe:2")
+        .arg("-y")
+        .arg("-i")
+        .arg(input)
+        .arg("-af")
+        .arg(filter)
+        .arg(output);
+
+    let mut total = None;
+    let mut duration = 0.0;
+    let result = run_ffmpeg_with_stderr(command, |line| {
+        if total.is_none() {
+            total = parse_ffmpeg_duration_line(line);
+        }
+        if let Some(position) = parse_ffmpeg_progress_seconds(line) {
+            duration = position;
+            on_progress(position, total);
+        }
+        parse_ffmpeg_progress_line(line).is_some()
+    })
+    .await;
+    if let Err(error) = result {
+        let _ = tokio::fs::remove_file(output).await;
+        return Err(error);
+    }
+
+    Ok(duration)
+}
+
+pub async fn extract_and_resample_audio(
+    filepath: &Path,
+    target_sample_rate: u32,
+) -> Result<Vec<f32>, AsrPortError> {
+    let mut command = ffmpeg_command()?;
+    command
+        .arg("-loglevel")
+        .arg("error")
+        .arg("-i")
+        .arg(filepath)
+        .arg("-f")
+        .arg("s16le")
+        .arg("-acodec")
+        .arg("pcm_s16le")
+        .arg("-ar")
+        .arg(target_sample_rate.to_string())
+        .arg("-ac")
+        .arg("1")
+        .arg("-");
+    let stdout = run_ffmpeg(command).await?;
+
+    Ok(pcm_s16le_bytes_to_f32(&stdout))
 }
 
 pub fn fixed_chunk_audio(
@@ -447,7 +772,7 @@ mod tests {
         LiveWavRecorder, resolve_ffmpeg_sidecar_path_from_exe, resolve_model_onnx_path,
         segment_batch_audio,
     };
-    use crate::audio::{pcm_i16_to_f32, pcm_s16le_bytes_to_f32};
+    use crate::audio::{pcm_f32_to_s16le_bytes, pcm_i16_to_f32, pcm_s16le_bytes_to_f32};
     use sona_core::ports::asr::BatchSegmentationMode;
     use std::fs;
     use std::path::Path;
@@ -509,6 +834,33 @@ mod tests {
         fs::remove_file(filepath).unwrap();
     }
 
+    #[test]
+    fn live_wav_recorder_reports_finalized_file_info() {
+        let filepath =
+            std::env::temp_dir().join(format!("sona-live-recorder-{}.wav", uuid::Uuid::new_v4()));
+        let mut recorder = LiveWavRecorder::create(&filepath, 16000).unwrap();
+
+        recorder.write_samples(&vec![0.25; 24000]).unwrap();
+        let info = recorder.finalize_with_info().unwrap();
+
+        assert_eq!(info.path, filepath);
+        assert_eq!(info.duration_secs, 1.5);
+        assert_eq!(info.sample_rate, 16000);
+        assert_eq!(info.channels, 1);
+        assert_eq!(info.bytes, 44 + 24000 * 2);
+        assert_eq!(info.bytes, fs::metadata(&filepath).unwrap().len());
+
+        fs::remove_file(filepath).unwrap();
+    }
+
+    #[test]
+    fn f32_samples_convert_to_clamped_s16le_bytes() {
+        let bytes = pcm_f32_to_s16le_bytes(&[-2.0, 0.0, 0.5, 2.0]);
+
+        assert_eq!(bytes, [0x01, 0x80, 0x00, 0x00, 0xff, 0x3f, 0xff, 0x7f]);
+        assert_eq!(pcm_s16le_bytes_to_f32(&bytes).len(), 4);
+    }
+
     #[test]
     fn batch_whole_segmentation_uses_one_full_audio_segment() {
         let samples = vec![0.0; 16000 * 65];
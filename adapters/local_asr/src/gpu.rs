@@ -121,6 +121,30 @@ pub async fn check_gpu_availability() -> Result<bool, sona_core::ports::asr::Asr
     }
 }
 
+/// Lists CUDA devices reported by `nvidia-smi`; empty when it is missing.
+pub async fn list_gpus() -> Vec<sona_core::runtime::gpu::GpuDevice> {
+    #[cfg(target_os = "macos")]
+    {
+        Vec::new()
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        tokio::process::Command::new("nvidia-smi")
+            .args(["--query-gpu=index,name", "--format=csv,noheader"])
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| {
+                sona_core::runtime::gpu::parse_nvidia_smi_gpu_list(&String::from_utf8_lossy(
+                    &output.stdout,
+                ))
+            })
+            .unwrap_or_default()
+    }
+}
+
 pub async fn resolve_gpu_acceleration_plan(gpu_acceleration: Option<&str>) -> GpuAccelerationPlan {
     GpuAccelerationPlan::for_current_platform(gpu_acceleration).await
 }
@@ -1,13 +1,23 @@
-use reqwest::header::RANGE;
+use reqwest::header::{
+    ACCEPT, AUTHORIZATION, CONTENT_LENGTH, CONTENT_RANGE, COOKIE, ETAG, HeaderMap, HeaderName,
+    HeaderValue, IF_RANGE, LAST_MODIFIED, PROXY_AUTHORIZATION, RANGE, USER_AGENT,
+};
 use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 use thiserror::Error;
 use tokio::fs::OpenOptions;
 use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::Notify;
 
+use crate::ip_preference::{IpPreference, PreferringResolver};
+use crate::network_stats::TransferCounter;
+use crate::parallel_download::{RangedDownload, accepts_byte_ranges, download_ranges};
+use crate::robust_download::DownloadEvent;
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum DownloadFileOperation {
     CreateModelsDirectory,
@@ -15,10 +25,13 @@ pub enum DownloadFileOperation {
     RemoveInstallFile,
     RemoveInstallDirectory,
     HashFile,
+    SyncFile,
     Publish,
     OpenArchive,
     ExtractArchive,
     RemoveArchive,
+    ListPartialDownloads,
+    RemovePartialDownload,
 }
 
 impl std::fmt::Display for DownloadFileOperation {
@@ -29,10 +42,13 @@ impl std::fmt::Display for DownloadFileOperation {
             Self::RemoveInstallFile => "remove model file",
             Self::RemoveInstallDirectory => "remove model directory",
             Self::HashFile => "hash file",
+            Self::SyncFile => "sync file to disk",
             Self::Publish => "publish download",
             Self::OpenArchive => "open archive",
             Self::ExtractArchive => "extract archive",
             Self::RemoveArchive => "remove archive",
+            Self::ListPartialDownloads => "list partial downloads",
+            Self::RemovePartialDownload => "remove partial download",
         };
         formatter.write_str(value)
     }
@@ -41,27 +57,108 @@ impl std::fmt::Display for DownloadFileOperation {
 #[derive(Error, Debug)]
 pub enum DownloadError {
     #[error("Network error: {0}")]
-    Network(#[from] reqwest::Error),
+    Network(reqwest::Error),
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
     #[error("Download cancelled")]
     Cancelled,
     #[error("Range not satisfiable: server reset download")]
     RangeNotSatisfiable,
-    #[error("Download failed with status: {0}")]
-    HttpStatus(reqwest::StatusCode),
+    /// No body bytes arrived for [`RequestOptions::stall_timeout_ms`] while
+    /// the connection stayed open.
+    #[error("download stalled: no data for {}s", timeout.as_secs_f64())]
+    Stalled { timeout: Duration },
+    /// `message` is taken from the error body when the server sent one, such
+    /// as Hugging Face explaining that a gated model's license must be
+    /// accepted first.
+    #[error(
+        "Download failed with status: {status}{}",
+        message.as_deref().map(|message| format!(": {message}")).unwrap_or_default()
+    )]
+    HttpStatus {
+        status: reqwest::StatusCode,
+        message: Option<String>,
+    },
     #[error("Downloaded file hash mismatch for {path}: expected {expected}, got {actual}")]
     HashMismatch {
         path: PathBuf,
         expected: String,
         actual: String,
     },
+    /// The archive does not decompress to the end, so extracting it would
+    /// fail partway. Downloading it again is the fix.
+    #[error("downloaded archive is corrupt: {reason}")]
+    CorruptArchive { path: PathBuf, reason: String },
     #[error("Download already in progress by another process")]
     AlreadyInProgress,
     #[error("Failed to create HTTP client: {reason}")]
     HttpClient { reason: String },
+    #[error("Invalid network policy: {reason}")]
+    InvalidNetworkPolicy { reason: String },
+    #[error("Invalid download spec: {reason}")]
+    InvalidDownloadSpec { reason: String },
+    /// The URL, or a redirect it led to, is outside the client's
+    /// [`DownloadClient::with_allowed_hosts`] list.
+    #[error("host not allowed: {host}")]
+    HostNotAllowed { host: String },
+    /// A redirect loop, or a chain of [`MAX_REDIRECTS`] redirects. Not
+    /// retried, since the server would answer the same way again.
+    #[error("too many redirects: gave up after {limit}")]
+    TooManyRedirects { limit: usize },
+    /// The volume holding the partial file has less room than the response
+    /// plus [`DISK_SPACE_MARGIN`]. Checked before any byte is written.
+    #[error("insufficient disk space: need {needed} bytes, have {available} bytes")]
+    InsufficientDiskSpace {
+        path: PathBuf,
+        needed: u64,
+        available: u64,
+    },
+    #[error("Response is larger than the {limit} byte limit")]
+    ResponseTooLarge { limit: u64 },
+    #[error("Invalid model manifest: {reason}")]
+    InvalidModelManifest { reason: String },
+    #[error("Invalid temporary directory {}: {reason}", path.display())]
+    InvalidTempDir { path: PathBuf, reason: String },
     #[error(transparent)]
     FileSystem(DownloadFileSystemError),
+    /// Downloaded bytes could not be stored. Unlike [`Self::Network`],
+    /// retrying or resuming will not help until the disk problem is fixed.
+    #[error("Failed to write download to {}: {kind}: {source}", path.display())]
+    Write {
+        path: PathBuf,
+        kind: DownloadWriteErrorKind,
+        source: std::io::Error,
+    },
+}
+
+/// Cause of a [`DownloadError::Write`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DownloadWriteErrorKind {
+    DiskFull,
+    PermissionDenied,
+    Other,
+}
+
+impl DownloadWriteErrorKind {
+    pub fn from_io_error(error: &std::io::Error) -> Self {
+        use std::io::ErrorKind;
+
+        match error.kind() {
+            ErrorKind::StorageFull | ErrorKind::QuotaExceeded => Self::DiskFull,
+            ErrorKind::PermissionDenied | ErrorKind::ReadOnlyFilesystem => Self::PermissionDenied,
+            _ => Self::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for DownloadWriteErrorKind {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        formatter.write_str(match self {
+            Self::DiskFull => "disk is full",
+            Self::PermissionDenied => "permission denied",
+            Self::Other => "write failed",
+        })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -89,7 +186,47 @@ impl std::fmt::Display for DownloadFileSystemError {
 
 impl std::error::Error for DownloadFileSystemError {}
 
+/// Raised by the redirect policy so a redirect that leaves the allowlist can
+/// be told apart from other redirect failures.
+#[derive(Debug, Error)]
+#[error("host not allowed: {0}")]
+struct RedirectHostNotAllowed(String);
+
+/// Raised by the redirect policy once a chain reaches [`MAX_REDIRECTS`], or on
+/// a redirect back to a URL already visited.
+#[derive(Debug, Error)]
+#[error("too many redirects")]
+struct TooManyRedirects;
+
+impl From<reqwest::Error> for DownloadError {
+    fn from(error: reqwest::Error) -> Self {
+        let mut source = std::error::Error::source(&error);
+        while let Some(cause) = source {
+            if let Some(RedirectHostNotAllowed(host)) = cause.downcast_ref() {
+                return Self::HostNotAllowed { host: host.clone() };
+            }
+            if cause.is::<TooManyRedirects>() {
+                return Self::TooManyRedirects {
+                    limit: MAX_REDIRECTS,
+                };
+            }
+            source = cause.source();
+        }
+        Self::Network(error)
+    }
+}
+
 impl DownloadError {
+    /// A dropped connection or a 5xx answer, which a retry after a pause may
+    /// get past.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Self::Network(_) | Self::Stalled { .. } => true,
+            Self::HttpStatus { status, .. } => status.is_server_error(),
+            _ => false,
+        }
+    }
+
     pub fn file_system(
         operation: DownloadFileOperation,
         path: impl Into<PathBuf>,
@@ -103,6 +240,14 @@ impl DownloadError {
         })
     }
 
+    pub fn write(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Write {
+            path: path.into(),
+            kind: DownloadWriteErrorKind::from_io_error(&source),
+            source,
+        }
+    }
+
     pub fn file_system_with_target(
         operation: DownloadFileOperation,
         path: impl Into<PathBuf>,
@@ -119,15 +264,583 @@ impl DownloadError {
     }
 }
 
+/// Most bytes of an error response read for its message; a larger error page
+/// is cut off rather than buffered.
+const HTTP_ERROR_BODY_LIMIT: usize = 4 * 1024;
+/// Longest message kept from an error body.
+const HTTP_ERROR_MESSAGE_MAX_CHARS: usize = 300;
+
+/// Builds [`DownloadError::HttpStatus`] for a non-success `response`, with
+/// the message from the start of its body when one can be found.
+pub(crate) async fn http_status_error(mut response: reqwest::Response) -> DownloadError {
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    let mut body = Vec::new();
+    while body.len() < HTTP_ERROR_BODY_LIMIT {
+        match response.chunk().await {
+            Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+            Ok(None) | Err(_) => break,
+        }
+    }
+    body.truncate(HTTP_ERROR_BODY_LIMIT);
+    DownloadError::HttpStatus {
+        status,
+        message: http_error_message(&body, is_json),
+    }
+}
+
+/// Reads the message from an error body: the `error` or `message` field of a
+/// JSON object (Hugging Face uses `{"error": "..."}`), or the first line of
+/// plain text. HTML pages carry no usable message and are ignored.
+fn http_error_message(body: &[u8], is_json: bool) -> Option<String> {
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim();
+    let message = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(value) => {
+            ["error", "message", "detail"]
+                .iter()
+                .find_map(|key| match value.get(key)? {
+                    serde_json::Value::String(message) => Some(message.clone()),
+                    nested => nested.get("message")?.as_str().map(str::to_string),
+                })?
+        }
+        Err(_) if is_json || text.starts_with('<') => return None,
+        Err(_) => text.lines().next()?.to_string(),
+    };
+    let message = message.trim();
+    if message.is_empty() {
+        return None;
+    }
+    Some(message.chars().take(HTTP_ERROR_MESSAGE_MAX_CHARS).collect())
+}
+
+/// Suffix appended to a download target while its bytes are still arriving.
+pub const TEMPORARY_DOWNLOAD_SUFFIX: &str = ".download";
+
 pub fn temporary_download_path(path: &Path) -> PathBuf {
     let mut s = path.as_os_str().to_os_string();
-    s.push(".download");
+    s.push(TEMPORARY_DOWNLOAD_SUFFIX);
     PathBuf::from(s)
 }
 
+/// [`temporary_download_path`] placed in `temp_dir` when one is configured,
+/// otherwise next to `path`.
+pub fn temporary_download_path_in(path: &Path, temp_dir: Option<&Path>) -> PathBuf {
+    match (temp_dir, path.file_name()) {
+        (Some(dir), Some(name)) => temporary_download_path(&dir.join(name)),
+        _ => temporary_download_path(path),
+    }
+}
+
+/// Checks that `dir` is an existing directory this process can create files
+/// in, by writing and removing a probe file.
+pub fn validate_temp_dir(dir: &Path) -> Result<(), DownloadError> {
+    let invalid = |reason: String| DownloadError::InvalidTempDir {
+        path: dir.to_path_buf(),
+        reason,
+    };
+    let metadata = std::fs::metadata(dir).map_err(|error| invalid(error.to_string()))?;
+    if !metadata.is_dir() {
+        return Err(invalid("not a directory".to_string()));
+    }
+    let probe = dir.join(format!(".sona-write-probe-{}", std::process::id()));
+    std::fs::write(&probe, b"").map_err(|error| invalid(error.to_string()))?;
+    let _ = std::fs::remove_file(&probe);
+    Ok(())
+}
+
+/// Room left free on the target volume after a download, so finishing one
+/// never fills the disk completely.
+pub const DISK_SPACE_MARGIN: u64 = 64 * 1024 * 1024;
+
+/// Fails when the volume holding `path` cannot take `len` more bytes plus
+/// [`DISK_SPACE_MARGIN`]. When the free space cannot be read the download
+/// goes ahead and any shortage surfaces as a write error.
+pub fn ensure_disk_space(path: &Path, len: u64) -> Result<(), DownloadError> {
+    match fs3::available_space(path) {
+        Ok(available) => check_disk_space(path, len, available),
+        Err(_) => Ok(()),
+    }
+}
+
+fn check_disk_space(path: &Path, len: u64, available: u64) -> Result<(), DownloadError> {
+    let needed = len.saturating_add(DISK_SPACE_MARGIN);
+    if available < needed {
+        return Err(DownloadError::InsufficientDiskSpace {
+            path: path.to_path_buf(),
+            needed,
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// Suffix of the sidecar written next to a partial download so it can resume
+/// with the right validator after the app restarts.
+pub const DOWNLOAD_STATE_SUFFIX: &str = ".part.json";
+
+/// How often the sidecar is rewritten while bytes are streaming.
+const DOWNLOAD_STATE_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn download_state_path(temp_path: &Path) -> PathBuf {
+    let mut s = temp_path.as_os_str().to_os_string();
+    s.push(DOWNLOAD_STATE_SUFFIX);
+    PathBuf::from(s)
+}
+
+/// Contents of the `.part.json` sidecar.
+///
+/// `etag` holds whichever validator the server offered for `If-Range` (a
+/// strong ETag, else `Last-Modified`). `downloaded` is informational: resume
+/// always starts from the partial file's actual length.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadResumeState {
+    pub url: String,
+    pub etag: Option<String>,
+    pub total_size: u64,
+    pub downloaded: u64,
+}
+
+/// Reads the sidecar for `temp_path`. A missing or unreadable sidecar means
+/// the partial download cannot be resumed safely, so errors become `None`.
+pub fn read_download_state(temp_path: &Path) -> Option<DownloadResumeState> {
+    let bytes = std::fs::read(download_state_path(temp_path)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+async fn load_download_state(temp_path: &Path) -> Option<DownloadResumeState> {
+    let bytes = tokio::fs::read(download_state_path(temp_path)).await.ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Best effort: a sidecar that cannot be written only costs the ability to
+/// resume after a restart, so it never fails the download itself.
+pub(crate) async fn save_download_state(temp_path: &Path, state: &DownloadResumeState) {
+    if let Ok(bytes) = serde_json::to_vec(state) {
+        let _ = tokio::fs::write(download_state_path(temp_path), bytes).await;
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialDownloadInfo {
+    pub path: PathBuf,
+    pub size: u64,
+    pub age: Duration,
+    /// Saved resume state, when the partial file has a readable sidecar.
+    pub resume: Option<DownloadResumeState>,
+}
+
+/// Lists leftover temporary download files directly inside `dir`.
+///
+/// A missing directory has no partial downloads and is not an error.
+pub fn list_partial_downloads(dir: &Path) -> Result<Vec<PartialDownloadInfo>, DownloadError> {
+    let list_error = |error: std::io::Error| {
+        DownloadError::file_system(
+            DownloadFileOperation::ListPartialDownloads,
+            dir,
+            error.to_string(),
+        )
+    };
+
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(error) => return Err(list_error(error)),
+    };
+
+    let now = SystemTime::now();
+    let mut partials = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(list_error)?;
+        let path = entry.path();
+        let is_partial = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.ends_with(TEMPORARY_DOWNLOAD_SUFFIX));
+        if !is_partial {
+            continue;
+        }
+
+        let metadata = entry.metadata().map_err(list_error)?;
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        partials.push(PartialDownloadInfo {
+            resume: read_download_state(&path),
+            path,
+            size: metadata.len(),
+            age,
+        });
+    }
+
+    partials.sort_by(|left, right| left.path.cmp(&right.path));
+    Ok(partials)
+}
+
+/// Removes leftover temporary download files in `dir` and returns what was
+/// deleted.
+///
+/// Paths in `in_progress` are skipped, as is any file another process still
+/// holds the download lock on, so an active download is never pulled out from
+/// under its writer.
+pub fn clean_partial_downloads(
+    dir: &Path,
+    in_progress: &[PathBuf],
+) -> Result<Vec<PartialDownloadInfo>, DownloadError> {
+    use fs3::FileExt;
+
+    let mut removed = Vec::new();
+    for partial in list_partial_downloads(dir)? {
+        if in_progress.iter().any(|path| path == &partial.path) {
+            continue;
+        }
+
+        let is_locked = std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(&partial.path)
+            .map(|file| file.try_lock_exclusive().is_err())
+            .unwrap_or(false);
+        if is_locked {
+            continue;
+        }
+
+        match std::fs::remove_file(&partial.path) {
+            Ok(()) => {
+                let _ = std::fs::remove_file(download_state_path(&partial.path));
+                removed.push(partial);
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => {}
+            Err(error) => {
+                return Err(DownloadError::file_system(
+                    DownloadFileOperation::RemovePartialDownload,
+                    &partial.path,
+                    error.to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Default time budget for [`DownloadClient::check_connectivity`].
+pub const CONNECTIVITY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Why a connectivity probe got no HTTP response at all.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ConnectivityError {
+    #[error("Invalid URL {url}: {reason}")]
+    InvalidUrl { url: String, reason: String },
+    #[error("DNS lookup failed for {host}")]
+    Dns { host: String },
+    #[error("Connection refused by {host}")]
+    ConnectionRefused { host: String },
+    #[error("Timed out reaching {host}")]
+    Timeout { host: String },
+    #[error("Could not reach {host}: {reason}")]
+    Other { host: String, reason: String },
+}
+
+impl ConnectivityError {
+    fn from_request(host: String, error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            return Self::Timeout { host };
+        }
+
+        // `reqwest` puts the URL in its own message, so report the innermost
+        // cause instead.
+        let mut reason = "request failed".to_string();
+        let mut source = std::error::Error::source(error);
+        while let Some(cause) = source {
+            reason = cause.to_string();
+            if let Some(io_error) = cause.downcast_ref::<std::io::Error>() {
+                match io_error.kind() {
+                    std::io::ErrorKind::ConnectionRefused => {
+                        return Self::ConnectionRefused { host };
+                    }
+                    std::io::ErrorKind::TimedOut => return Self::Timeout { host },
+                    _ => {}
+                }
+            }
+            // The resolver error type is private to the HTTP stack, so DNS
+            // failures can only be recognised by their message.
+            let message = reason.to_ascii_lowercase();
+            if message.contains("dns error") || message.contains("failed to lookup address") {
+                return Self::Dns { host };
+            }
+            source = cause.source();
+        }
+
+        Self::Other { host, reason }
+    }
+}
+
+/// Outcome of [`DownloadClient::verify_remote`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RemoteVerificationStatus {
+    /// The local SHA-256 matches a digest header sent by the server.
+    DigestMatch,
+    /// Sizes agree, but the server sent no digest to compare against.
+    SizeMatch,
+    Mismatch,
+    /// The server reported neither a size nor a digest.
+    Unknown,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteVerification {
+    pub status: RemoteVerificationStatus,
+    pub local_size: u64,
+    pub remote_size: Option<u64>,
+    /// Lowercase hex SHA-256 taken from the response digest headers.
+    pub remote_sha256: Option<String>,
+    /// Where the request ended up after following redirects.
+    pub final_url: String,
+}
+
+/// Timeouts and retry behaviour shared by every request a [`DownloadClient`]
+/// makes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NetworkPolicy {
+    /// Time allowed to establish a connection.
+    pub connect_timeout: Duration,
+    /// A response body that stalls for longer than this fails the read.
+    pub read_idle_timeout: Duration,
+    /// Retries after a network error or 5xx answer before a download gives
+    /// up. Attempts that made progress reset the count.
+    pub max_retries: u32,
+    /// Delay before the first retry; each further retry doubles it.
+    pub backoff_base: Duration,
+    pub max_backoff: Duration,
+    /// Address family new connections try first.
+    pub ip_preference: IpPreference,
+    /// Connections a file of at least `parallel_min_size` bytes is split
+    /// across when the server accepts byte ranges; 1 keeps every download on
+    /// a single stream.
+    pub parallel_connections: u32,
+    pub parallel_min_size: u64,
+}
+
+impl Default for NetworkPolicy {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(30),
+            read_idle_timeout: Duration::from_secs(60),
+            max_retries: 3,
+            backoff_base: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            ip_preference: IpPreference::System,
+            parallel_connections: 4,
+            parallel_min_size: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl NetworkPolicy {
+    /// Upper bound on [`Self::max_retries`], so a bad setting cannot keep a
+    /// failing download retrying indefinitely.
+    pub const MAX_RETRIES_LIMIT: u32 = 10;
+    /// Upper bound on [`Self::parallel_connections`], to stay polite to the
+    /// servers hosting models.
+    pub const MAX_PARALLEL_CONNECTIONS: u32 = 16;
+
+    pub fn validate(&self) -> Result<(), DownloadError> {
+        let invalid = |reason: String| Err(DownloadError::InvalidNetworkPolicy { reason });
+        if self.connect_timeout.is_zero() {
+            return invalid("connect timeout must be greater than zero".to_string());
+        }
+        if self.read_idle_timeout.is_zero() {
+            return invalid("read idle timeout must be greater than zero".to_string());
+        }
+        if self.max_retries > Self::MAX_RETRIES_LIMIT {
+            return invalid(format!(
+                "max retries must be at most {}, got {}",
+                Self::MAX_RETRIES_LIMIT,
+                self.max_retries
+            ));
+        }
+        if self.max_backoff < self.backoff_base {
+            return invalid("max backoff must not be shorter than the backoff base".to_string());
+        }
+        if !(1..=Self::MAX_PARALLEL_CONNECTIONS).contains(&self.parallel_connections) {
+            return invalid(format!(
+                "parallel connections must be between 1 and {}, got {}",
+                Self::MAX_PARALLEL_CONNECTIONS,
+                self.parallel_connections
+            ));
+        }
+        Ok(())
+    }
+
+    /// Delay before retry number `attempt`, counting from 1.
+    pub fn retry_delay(&self, attempt: u32) -> Duration {
+        let factor = 1_u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.backoff_base
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
 #[derive(Clone)]
 pub struct DownloadClient {
     client: reqwest::Client,
+    policy: NetworkPolicy,
+    /// Where partial downloads and extraction staging go instead of the
+    /// target directory.
+    temp_dir: Option<PathBuf>,
+    /// Hosts downloads may use, normalized; empty allows every host.
+    allowed_hosts: Arc<[String]>,
+    user_agent: Arc<str>,
+    /// Proxy every request goes through, as set with [`Self::with_proxy`].
+    proxy: Option<Arc<str>>,
+}
+
+/// Hosts reached directly even when a proxy is set, so local servers keep
+/// working behind a corporate proxy.
+pub const PROXY_BYPASS_HOSTS: &str = "localhost,127.0.0.1,::1";
+
+/// Checks `proxy_url` is an `http://` or `https://` URL with a host. Errors
+/// never echo the URL, which may carry a password.
+fn parse_proxy_url(proxy_url: &str) -> Result<reqwest::Url, DownloadError> {
+    let invalid = |reason: &str| DownloadError::InvalidNetworkPolicy {
+        reason: format!("proxy URL {reason}"),
+    };
+    let url = reqwest::Url::parse(proxy_url)
+        .map_err(|error| invalid(&format!("is malformed: {error}")))?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(invalid("must start with http:// or https://"));
+    }
+    if url.host_str().is_none_or(str::is_empty) {
+        return Err(invalid("must name a host"));
+    }
+    Ok(url)
+}
+
+/// `User-Agent` of every request unless overridden.
+pub const DEFAULT_USER_AGENT: &str = "Sona/1.0";
+
+/// How long a body may go without bytes before the transfer counts as
+/// stalled, unless [`RequestOptions::stall_timeout_ms`] says otherwise.
+pub const DEFAULT_STALL_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Request tweaks for one download, for mirrors, CDNs and WAFs that answer
+/// some clients differently. Only HTTP/1.1 is built into the client, so
+/// there is no protocol version to pick.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestOptions {
+    /// Replaces the client's `User-Agent`.
+    #[serde(default)]
+    pub user_agent: Option<String>,
+    /// Sent as the `Accept` header.
+    #[serde(default)]
+    pub accept: Option<String>,
+    /// Fails the transfer with [`DownloadError::Stalled`] when no body bytes
+    /// arrive for this long, which is retried like a dropped connection.
+    /// Defaults to [`DEFAULT_STALL_TIMEOUT`].
+    #[serde(default)]
+    pub stall_timeout_ms: Option<u64>,
+    /// Extra request headers, e.g. `Authorization: Bearer <token>` for gated
+    /// models, so tokens stay out of the URL. `user_agent` and `accept` win
+    /// over entries here.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+}
+
+impl RequestOptions {
+    fn stall_timeout(&self) -> Result<Duration, DownloadError> {
+        match self.stall_timeout_ms {
+            Some(0) => Err(DownloadError::InvalidDownloadSpec {
+                reason: "stall timeout must be greater than zero".to_string(),
+            }),
+            Some(millis) => Ok(Duration::from_millis(millis)),
+            None => Ok(DEFAULT_STALL_TIMEOUT),
+        }
+    }
+
+    fn header_map(&self) -> Result<HeaderMap, DownloadError> {
+        let mut headers = custom_header_map(&self.headers)?;
+        for (name, value) in [(USER_AGENT, &self.user_agent), (ACCEPT, &self.accept)] {
+            if let Some(value) = value {
+                let value = HeaderValue::from_str(value).map_err(|error| {
+                    DownloadError::InvalidDownloadSpec {
+                        reason: format!("header {name}: {error}"),
+                    }
+                })?;
+                headers.insert(name, value);
+            }
+        }
+        Ok(headers)
+    }
+}
+
+/// Checks and converts caller-supplied request headers. `Range` and
+/// `If-Range` are refused because resuming sets them. Credential values are
+/// marked sensitive, and errors name the header but never quote its value.
+pub(crate) fn custom_header_map(
+    headers: &BTreeMap<String, String>,
+) -> Result<HeaderMap, DownloadError> {
+    let invalid = |reason: String| DownloadError::InvalidDownloadSpec { reason };
+    let mut map = HeaderMap::new();
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|error| invalid(format!("header name {name:?}: {error}")))?;
+        if name == RANGE || name == IF_RANGE {
+            return Err(invalid(format!(
+                "header {name} is managed by the downloader"
+            )));
+        }
+        let mut value = HeaderValue::from_str(value)
+            .map_err(|error| invalid(format!("header {name}: {error}")))?;
+        value.set_sensitive(name == AUTHORIZATION || name == PROXY_AUTHORIZATION || name == COOKIE);
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// A chain that reaches this many redirects is given up on.
+pub const MAX_REDIRECTS: usize = 10;
+
+/// Whether `host` is covered by `allowed_hosts`. An entry matches its exact
+/// host; a `*.` prefix matches any subdomain instead.
+fn host_is_allowed(allowed_hosts: &[String], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    allowed_hosts.is_empty()
+        || allowed_hosts
+            .iter()
+            .any(|allowed| match allowed.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|subdomain| subdomain.ends_with('.')),
+                None => *allowed == host,
+            })
+}
+
+fn normalize_allowed_host(host: &str) -> Result<String, DownloadError> {
+    let host = host.trim().trim_end_matches('.').to_ascii_lowercase();
+    let domain = host.strip_prefix("*.").unwrap_or(&host);
+    if domain.is_empty() || domain.contains(['/', ':', '*', ' ']) {
+        return Err(DownloadError::InvalidNetworkPolicy {
+            reason: format!("allowed host {host:?} must be a host name such as example.com"),
+        });
+    }
+    Ok(host)
 }
 
 impl Default for DownloadClient {
@@ -142,29 +855,391 @@ impl DownloadClient {
     }
 
     pub fn try_new() -> Result<Self, DownloadError> {
+        Self::with_policy(NetworkPolicy::default())
+    }
+
+    /// Builds a client whose requests follow `policy`. Timeouts are fixed when
+    /// the client is built, so changing the policy means building a new one.
+    pub fn with_policy(policy: NetworkPolicy) -> Result<Self, DownloadError> {
+        policy.validate()?;
         Ok(Self {
-            client: reqwest::Client::builder()
-                .user_agent("Sona/1.0")
-                .build()
-                .map_err(|error| DownloadError::HttpClient {
-                    reason: error.to_string(),
-                })?,
+            client: Self::build_http_client(&policy, Arc::from([]), DEFAULT_USER_AGENT, None)?,
+            policy,
+            temp_dir: None,
+            allowed_hosts: Arc::from([]),
+            user_agent: Arc::from(DEFAULT_USER_AGENT),
+            proxy: None,
+        })
+    }
+
+    fn build_http_client(
+        policy: &NetworkPolicy,
+        allowed_hosts: Arc<[String]>,
+        user_agent: &str,
+        proxy: Option<&str>,
+    ) -> Result<reqwest::Client, DownloadError> {
+        // Checked on every hop, so a redirect cannot leave the allowlist.
+        let redirect = reqwest::redirect::Policy::custom(move |attempt| {
+            let host = attempt.url().host_str().unwrap_or_default().to_string();
+            let is_loop = attempt.previous().contains(attempt.url());
+            if is_loop || attempt.previous().len() >= MAX_REDIRECTS {
+                attempt.error(TooManyRedirects)
+            } else if host_is_allowed(&allowed_hosts, &host) {
+                attempt.follow()
+            } else {
+                attempt.error(RedirectHostNotAllowed(host))
+            }
+        });
+        let mut builder = reqwest::Client::builder()
+            .user_agent(user_agent)
+            .connect_timeout(policy.connect_timeout)
+            .read_timeout(policy.read_idle_timeout)
+            .redirect(redirect);
+        if policy.ip_preference != IpPreference::System {
+            builder = builder.dns_resolver(PreferringResolver(policy.ip_preference));
+        }
+        if let Some(proxy) = proxy {
+            // Credentials in the URL are sent as proxy basic auth.
+            let proxy = reqwest::Proxy::all(parse_proxy_url(proxy)?)
+                .map_err(|error| DownloadError::InvalidNetworkPolicy {
+                    reason: format!("proxy URL is not usable: {error}"),
+                })?
+                .no_proxy(reqwest::NoProxy::from_string(PROXY_BYPASS_HOSTS));
+            builder = builder.proxy(proxy);
+        }
+        builder.build().map_err(|error| DownloadError::HttpClient {
+            reason: error.to_string(),
+        })
+    }
+
+    /// Restricts downloads to `hosts`, including every redirect they follow.
+    /// Entries are host names; `*.example.com` covers its subdomains. An empty
+    /// list allows every host.
+    pub fn with_allowed_hosts(mut self, hosts: Vec<String>) -> Result<Self, DownloadError> {
+        let hosts = hosts
+            .iter()
+            .map(|host| normalize_allowed_host(host))
+            .collect::<Result<Arc<[String]>, _>>()?;
+        self.client = Self::build_http_client(
+            &self.policy,
+            hosts.clone(),
+            &self.user_agent,
+            self.proxy.as_deref(),
+        )?;
+        self.allowed_hosts = hosts;
+        Ok(self)
+    }
+
+    /// Sends `user_agent` with every request instead of
+    /// [`DEFAULT_USER_AGENT`]; `None` restores the default.
+    pub fn with_user_agent(mut self, user_agent: Option<String>) -> Result<Self, DownloadError> {
+        let user_agent: Arc<str> = Arc::from(user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT));
+        if HeaderValue::from_str(&user_agent).is_err() {
+            return Err(DownloadError::InvalidNetworkPolicy {
+                reason: format!("user agent {user_agent:?} is not a valid header value"),
+            });
+        }
+        self.client = Self::build_http_client(
+            &self.policy,
+            self.allowed_hosts.clone(),
+            &user_agent,
+            self.proxy.as_deref(),
+        )?;
+        self.user_agent = user_agent;
+        Ok(self)
+    }
+
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// Sends every request through the HTTP or HTTPS proxy at `proxy_url`,
+    /// except to [`PROXY_BYPASS_HOSTS`]. `user:password@` in the URL
+    /// authenticates with the proxy. `None` goes back to the proxies named
+    /// by the environment, if any.
+    pub fn with_proxy(mut self, proxy_url: Option<String>) -> Result<Self, DownloadError> {
+        let proxy = proxy_url.map(Arc::<str>::from);
+        self.client = Self::build_http_client(
+            &self.policy,
+            self.allowed_hosts.clone(),
+            &self.user_agent,
+            proxy.as_deref(),
+        )?;
+        self.proxy = proxy;
+        Ok(self)
+    }
+
+    pub fn proxy_url(&self) -> Option<&str> {
+        self.proxy.as_deref()
+    }
+
+    /// The same client with a new connection pool, so the next requests
+    /// resolve and connect afresh, e.g. after switching networks or VPNs.
+    /// Requests already running keep the old connections until they finish.
+    pub fn reconnect(&self) -> Result<Self, DownloadError> {
+        Ok(Self {
+            client: Self::build_http_client(
+                &self.policy,
+                self.allowed_hosts.clone(),
+                &self.user_agent,
+                self.proxy.as_deref(),
+            )?,
+            ..self.clone()
+        })
+    }
+
+    pub fn allowed_hosts(&self) -> &[String] {
+        &self.allowed_hosts
+    }
+
+    /// Rejects `url` when its host is outside [`Self::with_allowed_hosts`].
+    pub fn check_host_allowed(&self, url: &str) -> Result<(), DownloadError> {
+        if self.allowed_hosts.is_empty() {
+            return Ok(());
+        }
+        let host = reqwest::Url::parse(url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_default();
+        if host_is_allowed(&self.allowed_hosts, &host) {
+            Ok(())
+        } else {
+            Err(DownloadError::HostNotAllowed { host })
+        }
+    }
+
+    /// Puts temporary files in `temp_dir`, after [`validate_temp_dir`];
+    /// `None` keeps them next to their target.
+    pub fn with_temp_dir(mut self, temp_dir: Option<PathBuf>) -> Result<Self, DownloadError> {
+        if let Some(dir) = &temp_dir {
+            validate_temp_dir(dir)?;
+        }
+        self.temp_dir = temp_dir;
+        Ok(self)
+    }
+
+    pub fn policy(&self) -> &NetworkPolicy {
+        &self.policy
+    }
+
+    pub fn temp_dir(&self) -> Option<&Path> {
+        self.temp_dir.as_deref()
+    }
+
+    /// Partial file for a download into `path`.
+    pub fn temporary_path(&self, path: &Path) -> PathBuf {
+        temporary_download_path_in(path, self.temp_dir())
+    }
+
+    /// Sends a `HEAD` request to `url` within `timeout`.
+    ///
+    /// Any HTTP response below 500 counts as reachable, since CDNs often
+    /// answer `HEAD` with 403 or 405. Server errors return `Ok(false)`, and a
+    /// request that never got a response says why in the error.
+    pub async fn check_connectivity(
+        &self,
+        url: &str,
+        timeout: Duration,
+    ) -> Result<bool, ConnectivityError> {
+        let parsed = reqwest::Url::parse(url).map_err(|error| ConnectivityError::InvalidUrl {
+            url: url.to_string(),
+            reason: error.to_string(),
+        })?;
+        let host = parsed.host_str().unwrap_or_default().to_string();
+
+        match self.client.head(parsed).timeout(timeout).send().await {
+            Ok(response) => Ok(!response.status().is_server_error()),
+            Err(error) => Err(ConnectivityError::from_request(host, &error)),
+        }
+    }
+
+    /// Compares `path` with the file at `url` using only a `HEAD` request.
+    ///
+    /// A differing `Content-Length` is a mismatch without hashing anything.
+    /// Otherwise the local file is hashed when the server sends a SHA-256
+    /// digest header (`x-amz-checksum-sha256`, `Repr-Digest` or `Digest`).
+    pub async fn verify_remote(
+        &self,
+        url: &str,
+        path: &Path,
+    ) -> Result<RemoteVerification, DownloadError> {
+        self.check_host_allowed(url)?;
+        let local_size = tokio::fs::metadata(path)
+            .await
+            .map_err(|error| {
+                DownloadError::file_system(
+                    DownloadFileOperation::InspectInstall,
+                    path,
+                    error.to_string(),
+                )
+            })?
+            .len();
+
+        let response = self
+            .client
+            .head(url)
+            // S3 only returns stored checksums when asked for them.
+            .header("x-amz-checksum-mode", "ENABLED")
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            return Err(http_status_error(response).await);
+        }
+        let final_url = response.url().to_string();
+        let remote_size = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse::<u64>().ok());
+        let remote_sha256 = sha256_from_digest_headers(response.headers());
+
+        let status = if remote_size.is_some_and(|size| size != local_size) {
+            RemoteVerificationStatus::Mismatch
+        } else if let Some(expected) = &remote_sha256 {
+            if sha256_file(path).await?.eq_ignore_ascii_case(expected) {
+                RemoteVerificationStatus::DigestMatch
+            } else {
+                RemoteVerificationStatus::Mismatch
+            }
+        } else if remote_size.is_some() {
+            RemoteVerificationStatus::SizeMatch
+        } else {
+            RemoteVerificationStatus::Unknown
+        };
+
+        Ok(RemoteVerification {
+            status,
+            local_size,
+            remote_size,
+            remote_sha256,
+            final_url,
         })
     }
 
+    /// See [`crate::stream_extract::download_and_extract`].
+    pub async fn download_and_extract(
+        &self,
+        url: &str,
+        target_dir: &Path,
+        notify: Arc<Notify>,
+        on_progress: impl FnMut(crate::StreamExtractProgress) + Send,
+    ) -> Result<crate::StreamExtractSummary, DownloadError> {
+        self.check_host_allowed(url)?;
+        crate::stream_extract::download_and_extract(
+            &self.client,
+            url,
+            target_dir,
+            self.temp_dir(),
+            notify,
+            on_progress,
+        )
+        .await
+    }
+
+    /// See [`crate::robust_download::robust_download`].
+    pub async fn robust_download(
+        &self,
+        spec: &crate::DownloadSpec,
+        notify: Arc<Notify>,
+        on_event: impl FnMut(DownloadEvent) + Send,
+    ) -> Result<(), DownloadError> {
+        for url in &spec.urls {
+            self.check_host_allowed(url)?;
+        }
+        crate::robust_download::robust_download(
+            &self.client,
+            &self.policy,
+            self.temp_dir(),
+            spec,
+            notify,
+            on_event,
+        )
+        .await
+    }
+
+    /// See [`download_to_memory`].
+    pub async fn download_to_memory(
+        &self,
+        url: &str,
+        max_bytes: u64,
+        notify: Arc<Notify>,
+    ) -> Result<Vec<u8>, DownloadError> {
+        self.check_host_allowed(url)?;
+        download_to_memory(&self.client, &self.policy, url, max_bytes, notify).await
+    }
+
+    /// See [`download_file`].
     pub async fn download_file(
         &self,
         url: &str,
         temp_path: &Path,
         notify: Arc<Notify>,
-        on_progress: Option<Box<dyn FnMut(u64, u64) + Send>>,
-    ) -> Result<(), DownloadError> {
-        download_file(&self.client, url, temp_path, notify, on_progress).await
+        on_progress: Option<Box<dyn FnMut(u64, u64) + Send>>,
+    ) -> Result<String, DownloadError> {
+        self.download_file_with_options(
+            url,
+            temp_path,
+            notify,
+            on_progress,
+            &RequestOptions::default(),
+        )
+        .await
+    }
+
+    /// [`Self::download_file`] with `options` applied to every request.
+    pub async fn download_file_with_options(
+        &self,
+        url: &str,
+        temp_path: &Path,
+        notify: Arc<Notify>,
+        mut on_progress: Option<Box<dyn FnMut(u64, u64) + Send>>,
+        options: &RequestOptions,
+    ) -> Result<String, DownloadError> {
+        self.download_file_with_events(url, temp_path, notify, options, |event| {
+            if let (DownloadEvent::Progress { downloaded, total }, Some(cb)) =
+                (event, on_progress.as_mut())
+            {
+                cb(downloaded, total);
+            }
+        })
+        .await
+    }
+
+    /// [`Self::download_file_with_options`] reporting every step, retries
+    /// included, as a [`DownloadEvent`]. `Complete` is never sent; the
+    /// caller publishes the file.
+    pub async fn download_file_with_events(
+        &self,
+        url: &str,
+        temp_path: &Path,
+        notify: Arc<Notify>,
+        options: &RequestOptions,
+        mut on_event: impl FnMut(DownloadEvent) + Send,
+    ) -> Result<String, DownloadError> {
+        self.check_host_allowed(url)?;
+        let resumable_urls = [url.to_string()];
+        let request = DownloadRequest {
+            url,
+            headers: &options.header_map()?,
+            max_retries: self.policy.max_retries,
+            resumable_urls: &resumable_urls,
+            stall_timeout: options.stall_timeout()?,
+        };
+        download_with_events(
+            &self.client,
+            &self.policy,
+            request,
+            temp_path,
+            notify,
+            &mut on_event,
+        )
+        .await
     }
 }
 
 pub async fn remove_download_file(temp_path: &Path) {
     let _ = tokio::fs::remove_file(temp_path).await;
+    let _ = tokio::fs::remove_file(download_state_path(temp_path)).await;
 }
 
 pub async fn complete_download_file(
@@ -177,7 +1252,30 @@ pub async fn complete_download_file(
         publish_download_file(temp_path, final_path).await
     }
     .await;
+    discard_download_file_on_error(temp_path, result).await
+}
+
+/// [`complete_download_file`] for a file whose SHA-256 was computed while it
+/// was written, as [`download_file`] returns it, so the file is not read
+/// again to verify it.
+pub async fn complete_hashed_download_file(
+    temp_path: &Path,
+    final_path: &Path,
+    expected_sha256: Option<&str>,
+    actual_sha256: &str,
+) -> Result<(), DownloadError> {
+    let result = async {
+        check_sha256(temp_path, expected_sha256, actual_sha256)?;
+        publish_download_file(temp_path, final_path).await
+    }
+    .await;
+    discard_download_file_on_error(temp_path, result).await
+}
 
+async fn discard_download_file_on_error(
+    temp_path: &Path,
+    result: Result<(), DownloadError>,
+) -> Result<(), DownloadError> {
     match result {
         Ok(()) => Ok(()),
         Err(error) => {
@@ -192,123 +1290,402 @@ pub async fn verify_download_file(
     expected_sha256: Option<&str>,
 ) -> Result<(), DownloadError> {
     if let Some(expected_hash) = expected_sha256 {
-        let actual_hash = sha256_file(temp_path).await?;
-        if !actual_hash.eq_ignore_ascii_case(expected_hash) {
-            return Err(DownloadError::HashMismatch {
-                path: temp_path.to_path_buf(),
-                expected: expected_hash.to_string(),
-                actual: actual_hash,
-            });
-        }
+        check_sha256(
+            temp_path,
+            Some(expected_hash),
+            &sha256_file(temp_path).await?,
+        )?;
     }
     Ok(())
 }
 
+fn check_sha256(
+    path: &Path,
+    expected_sha256: Option<&str>,
+    actual_sha256: &str,
+) -> Result<(), DownloadError> {
+    match expected_sha256 {
+        Some(expected) if !actual_sha256.eq_ignore_ascii_case(expected) => {
+            Err(DownloadError::HashMismatch {
+                path: path.to_path_buf(),
+                expected: expected.to_string(),
+                actual: actual_sha256.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
 pub async fn sha256_file(path: &Path) -> Result<String, DownloadError> {
-    let mut file = tokio::fs::File::open(path).await.map_err(|error| {
+    sha256_file_with_progress(path, |_, _| {}).await
+}
+
+/// Hashes `path`, reporting `(hashed_bytes, total_bytes)` after every chunk.
+pub async fn sha256_file_with_progress<F>(
+    path: &Path,
+    mut on_progress: F,
+) -> Result<String, DownloadError>
+where
+    F: FnMut(u64, u64),
+{
+    let hash_error = |error: std::io::Error| {
         DownloadError::file_system(DownloadFileOperation::HashFile, path, error.to_string())
-    })?;
+    };
+    let mut file = tokio::fs::File::open(path).await.map_err(hash_error)?;
+    let total = file.metadata().await.map_err(hash_error)?.len();
     let mut hasher = Sha256::new();
     let mut buffer = vec![0_u8; 16 * 1024];
+    let mut hashed = 0_u64;
 
     loop {
-        let read = file.read(&mut buffer).await.map_err(|error| {
-            DownloadError::file_system(DownloadFileOperation::HashFile, path, error.to_string())
-        })?;
+        let read = file.read(&mut buffer).await.map_err(hash_error)?;
         if read == 0 {
             break;
         }
         hasher.update(&buffer[..read]);
+        hashed += read as u64;
+        on_progress(hashed, total);
     }
 
     Ok(hex::encode(hasher.finalize()))
 }
 
+/// Forces `path` to stable storage, then re-hashes it against
+/// `expected_sha256`.
+///
+/// Meant for files that never went through download-time verification, such
+/// as installs from older versions or models copied in by hand.
+pub async fn flush_and_verify_file<F>(
+    path: &Path,
+    expected_sha256: &str,
+    on_progress: F,
+) -> Result<(), DownloadError>
+where
+    F: FnMut(u64, u64),
+{
+    let sync_error = |error: std::io::Error| {
+        DownloadError::file_system(DownloadFileOperation::SyncFile, path, error.to_string())
+    };
+    // Windows only flushes through a handle with write access; fall back to a
+    // read-only handle for files we are not allowed to open for writing.
+    let file = match tokio::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open(path)
+        .await
+    {
+        Ok(file) => file,
+        Err(error) if error.kind() == std::io::ErrorKind::PermissionDenied => {
+            tokio::fs::File::open(path).await.map_err(sync_error)?
+        }
+        Err(error) => return Err(sync_error(error)),
+    };
+    file.sync_all().await.map_err(sync_error)?;
+    drop(file);
+
+    let expected_sha256 = expected_sha256.trim();
+    let actual_hash = sha256_file_with_progress(path, on_progress).await?;
+    if !actual_hash.eq_ignore_ascii_case(expected_sha256) {
+        return Err(DownloadError::HashMismatch {
+            path: path.to_path_buf(),
+            expected: expected_sha256.to_string(),
+            actual: actual_hash,
+        });
+    }
+    Ok(())
+}
+
+/// Moves a finished download into place. The rename replaces an existing
+/// file in one step on every platform, so `final_path` always holds either
+/// the previous complete file or the new one, never nothing or a prefix.
 pub async fn publish_download_file(
     temp_path: &Path,
     final_path: &Path,
 ) -> Result<(), DownloadError> {
-    if tokio::fs::try_exists(final_path).await.map_err(|error| {
+    move_file(temp_path, final_path).await.map_err(|error| {
         DownloadError::file_system_with_target(
             DownloadFileOperation::Publish,
             temp_path,
             final_path,
             error.to_string(),
         )
-    })? {
-        tokio::fs::remove_file(final_path).await.map_err(|error| {
-            DownloadError::file_system_with_target(
-                DownloadFileOperation::Publish,
-                temp_path,
-                final_path,
-                error.to_string(),
-            )
-        })?;
-    }
-    tokio::fs::rename(temp_path, final_path)
-        .await
-        .map_err(|error| {
-            DownloadError::file_system_with_target(
-                DownloadFileOperation::Publish,
-                temp_path,
-                final_path,
-                error.to_string(),
-            )
-        })?;
+    })?;
+    let _ = tokio::fs::remove_file(download_state_path(temp_path)).await;
     Ok(())
 }
 
+/// Renames `from` to `to`. A temporary directory on another volume cannot be
+/// renamed across, so the file is copied next to `to` first and renamed from
+/// there, keeping the final step atomic.
+pub(crate) async fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    match tokio::fs::rename(from, to).await {
+        Err(error) if error.kind() == std::io::ErrorKind::CrossesDevices => {
+            let staged = temporary_download_path(to);
+            let copied = async {
+                tokio::fs::copy(from, &staged).await?;
+                tokio::fs::rename(&staged, to).await
+            }
+            .await;
+            if copied.is_err() {
+                let _ = tokio::fs::remove_file(&staged).await;
+            }
+            copied?;
+            tokio::fs::remove_file(from).await
+        }
+        result => result,
+    }
+}
+
+/// Downloads `url` to `temp_path`, resuming the partial file a previous run
+/// left there, and returns the SHA-256 of the finished file as lowercase hex.
+/// The digest is computed while the bytes are written; only a resumed prefix
+/// is read back from disk.
 pub async fn download_file(
     client: &reqwest::Client,
+    policy: &NetworkPolicy,
     url: &str,
     temp_path: &Path,
     notify: Arc<Notify>,
     mut on_progress: Option<Box<dyn FnMut(u64, u64) + Send>>,
-) -> Result<(), DownloadError> {
+) -> Result<String, DownloadError> {
+    let resumable_urls = [url.to_string()];
+    let request = DownloadRequest {
+        url,
+        headers: &HeaderMap::new(),
+        max_retries: policy.max_retries,
+        resumable_urls: &resumable_urls,
+        stall_timeout: DEFAULT_STALL_TIMEOUT,
+    };
+    download_with_events(client, policy, request, temp_path, notify, &mut |event| {
+        if let (DownloadEvent::Progress { downloaded, total }, Some(cb)) =
+            (event, on_progress.as_mut())
+        {
+            cb(downloaded, total);
+        }
+    })
+    .await
+}
+
+/// Cap used by [`download_to_memory`] callers that do not pick their own.
+pub const MEMORY_DOWNLOAD_DEFAULT_LIMIT: u64 = 16 * 1024 * 1024;
+/// Largest cap [`download_to_memory`] accepts; bigger files belong on disk.
+pub const MEMORY_DOWNLOAD_MAX_LIMIT: u64 = 256 * 1024 * 1024;
+
+/// Downloads a small file such as a manifest or license into memory.
+///
+/// Fails with [`DownloadError::ResponseTooLarge`] as soon as the advertised
+/// or received size passes `max_bytes`, so an unexpectedly large response is
+/// never buffered whole. Failed requests are retried per `policy`; without a
+/// partial file to resume, a retry starts over.
+pub async fn download_to_memory(
+    client: &reqwest::Client,
+    policy: &NetworkPolicy,
+    url: &str,
+    max_bytes: u64,
+    notify: Arc<Notify>,
+) -> Result<Vec<u8>, DownloadError> {
+    if max_bytes == 0 || max_bytes > MEMORY_DOWNLOAD_MAX_LIMIT {
+        return Err(DownloadError::InvalidDownloadSpec {
+            reason: format!(
+                "max size must be between 1 and {MEMORY_DOWNLOAD_MAX_LIMIT} bytes, got {max_bytes}"
+            ),
+        });
+    }
+    let too_large = || DownloadError::ResponseTooLarge { limit: max_bytes };
+
+    let mut attempt = 0;
+    loop {
+        let result = tokio::select! {
+            _ = notify.notified() => return Err(DownloadError::Cancelled),
+            result = async {
+                let response = client.get(url).send().await?;
+                if !response.status().is_success() {
+                    return Err(http_status_error(response).await);
+                }
+                if response.content_length().is_some_and(|length| length > max_bytes) {
+                    return Err(too_large());
+                }
+
+                use futures_util::StreamExt;
+                let mut body = Vec::new();
+                let mut stream = response.bytes_stream();
+                let transfer = TransferCounter::start();
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk?;
+                    transfer.add(chunk.len());
+                    if body.len() as u64 + chunk.len() as u64 > max_bytes {
+                        return Err(too_large());
+                    }
+                    body.extend_from_slice(&chunk);
+                }
+                Ok(body)
+            } => result,
+        };
+
+        match result {
+            Err(error) if error.is_transient() && attempt < policy.max_retries => {
+                attempt += 1;
+                retry_after(
+                    policy,
+                    url,
+                    attempt,
+                    policy.max_retries,
+                    &error,
+                    &notify,
+                    &mut |_| {},
+                )
+                .await?;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// One URL's worth of [`download_with_events`].
+pub(crate) struct DownloadRequest<'a> {
+    pub url: &'a str,
+    pub headers: &'a HeaderMap,
+    pub max_retries: u32,
+    /// URLs serving the same bytes as `url`. A partial file whose sidecar
+    /// names one of them is continued instead of restarted.
+    pub resumable_urls: &'a [String],
+    pub stall_timeout: Duration,
+}
+
+/// The resumable download loop behind [`download_file`], reporting each
+/// step as a [`DownloadEvent`]. `Complete` is left to the caller, which
+/// knows when the file has been verified and published. Returns the file's
+/// SHA-256, hashed as it is written.
+pub(crate) async fn download_with_events(
+    client: &reqwest::Client,
+    policy: &NetworkPolicy,
+    request: DownloadRequest<'_>,
+    temp_path: &Path,
+    notify: Arc<Notify>,
+    on_event: &mut (dyn FnMut(DownloadEvent) + Send),
+) -> Result<String, DownloadError> {
+    let DownloadRequest {
+        url,
+        headers,
+        max_retries,
+        resumable_urls,
+        stall_timeout,
+    } = request;
     // Acquire an exclusive lock on the download file BEFORE establishing any
     // network connection. This lets us fail fast with AlreadyInProgress
     // instead of wasting a TCP connection and downloading bytes we cannot use.
     let mut file = open_and_lock_download_file(temp_path).await?;
 
-    let max_retries = 3;
     let mut attempt = 0;
+    // Digest of the first `hashed` bytes of the file. Retries within this
+    // call keep appending to it; bytes from an earlier run are hashed from
+    // disk before the first resumed write.
+    let mut hasher = Sha256::new();
+    let mut hashed: u64 = 0;
+    // Validator captured from the most recent response so resumed requests
+    // only get a 206 when the server still has the same file version.
+    let mut resume_validator: Option<String> = None;
+    // Cleared once the multi-connection path has failed, so the rest of the
+    // file comes over a single stream.
+    let mut parallel = policy.parallel_connections > 1;
+
+    // A sidecar from an earlier run restores the validator so the partial
+    // bytes survive a restart. One written for a mirror keeps the bytes, but
+    // its validator belongs to the other server. Any other URL means the
+    // bytes belong to a different file and cannot be appended to.
+    match load_download_state(temp_path).await {
+        Some(saved) if saved.url == url => resume_validator = saved.etag,
+        Some(saved) if resumable_urls.contains(&saved.url) => {}
+        Some(_) => {
+            file.set_len(0).await?;
+            file.seek(SeekFrom::Start(0)).await?;
+        }
+        None => {}
+    }
 
     loop {
         // Read the current on-disk size from the already-open handle so we
         // know whether to request a byte range for resumption.
         let current_size = file.metadata().await.map(|m| m.len()).unwrap_or(0);
 
-        let mut request = client.get(url);
+        let mut request = client.get(url).headers(headers.clone());
         if current_size > 0 {
             request = request.header(RANGE, format!("bytes={}-", current_size));
+            if let Some(validator) = &resume_validator {
+                request = request.header(IF_RANGE, validator);
+            }
         }
 
         let res_result = request.send().await;
-        let res = match res_result {
+        let res = match res_result.map_err(DownloadError::from) {
             Ok(r) => r,
-            Err(e) => {
-                if attempt < max_retries {
-                    attempt += 1;
-                    tokio::time::sleep(std::time::Duration::from_secs(1 << (attempt - 1))).await;
-                    continue;
-                }
-                return Err(DownloadError::Network(e));
+            Err(e @ DownloadError::Network(_)) if attempt < max_retries => {
+                attempt += 1;
+                retry_after(policy, url, attempt, max_retries, &e, &notify, on_event).await?;
+                continue;
             }
+            Err(e) => return Err(e),
         };
 
         if res.status() == reqwest::StatusCode::RANGE_NOT_SATISFIABLE {
             // The server does not recognise our byte range; truncate the
-            // partial file in-place and restart from the beginning.
-            file.set_len(0).await?;
-            file.seek(SeekFrom::Start(0)).await?;
+            // partial file in-place and restart from the beginning. Without
+            // a range there is nothing to restart from.
+            if current_size == 0 {
+                return Err(DownloadError::RangeNotSatisfiable);
+            }
+            restart_from_scratch(
+                &mut file,
+                &mut attempt,
+                max_retries,
+                policy,
+                url,
+                &notify,
+                on_event,
+            )
+            .await?;
             continue;
         }
 
         if !res.status().is_success() {
-            return Err(DownloadError::HttpStatus(res.status()));
+            let error = http_status_error(res).await;
+            if error.is_transient() && attempt < max_retries {
+                attempt += 1;
+                retry_after(policy, url, attempt, max_retries, &error, &notify, on_event).await?;
+                continue;
+            }
+            return Err(error);
         }
 
         let is_partial = res.status() == reqwest::StatusCode::PARTIAL_CONTENT;
+        if is_partial && content_range_start(res.headers()) != Some(current_size) {
+            // A 206 for a different offset cannot be appended safely; drop the
+            // partial bytes and fetch the whole file instead. One answering a
+            // request for the whole file leaves no better request to make.
+            if current_size == 0 {
+                return Err(DownloadError::RangeNotSatisfiable);
+            }
+            resume_validator = None;
+            restart_from_scratch(
+                &mut file,
+                &mut attempt,
+                max_retries,
+                policy,
+                url,
+                &notify,
+                on_event,
+            )
+            .await?;
+            continue;
+        }
+        if let Some(validator) = resume_validator_from_headers(res.headers()) {
+            resume_validator = Some(validator);
+        }
+        // Only the bytes still to come need room; servers that do not send a
+        // length are not checked.
+        if let Some(content_length) = res.content_length() {
+            ensure_disk_space(temp_path, content_length)?;
+        }
         let content_length = res.content_length().unwrap_or(0);
         let total_size = if is_partial {
             current_size + content_length
@@ -316,21 +1693,84 @@ pub async fn download_file(
             content_length
         };
 
+        on_event(DownloadEvent::Started {
+            url: url.to_string(),
+            final_url: res.url().to_string(),
+            resumed_from: if is_partial { current_size } else { 0 },
+            total: total_size,
+        });
+
+        if parallel
+            && !is_partial
+            && total_size >= policy.parallel_min_size
+            && accepts_byte_ranges(res.headers())
+            && let Some(validator) = resume_validator.as_deref()
+        {
+            let download = RangedDownload {
+                client,
+                policy,
+                url,
+                headers,
+                max_retries,
+                validator,
+                total: total_size,
+                stall_timeout,
+            };
+            match download_ranges(download, res, &mut file, temp_path, &notify, on_event).await {
+                // The file now holds only the bytes written without a gap;
+                // the single stream resumes after them.
+                Err(error)
+                    if !matches!(
+                        error,
+                        DownloadError::Cancelled | DownloadError::Write { .. }
+                    ) =>
+                {
+                    parallel = false;
+                    continue;
+                }
+                result => return result,
+            }
+        }
+
         // Position the file cursor before streaming begins.
         if is_partial {
+            if hashed != current_size {
+                hasher = Sha256::new();
+                hash_file_prefix(&mut file, current_size, &mut hasher)
+                    .await
+                    .map_err(|error| {
+                        DownloadError::file_system(
+                            DownloadFileOperation::HashFile,
+                            temp_path,
+                            error.to_string(),
+                        )
+                    })?;
+                hashed = current_size;
+            }
             // Resume: append after the bytes already on disk.
             file.seek(SeekFrom::End(0)).await?;
         } else {
             // Full response: overwrite from the beginning.
             file.set_len(0).await?;
             file.seek(SeekFrom::Start(0)).await?;
+            hasher = Sha256::new();
+            hashed = 0;
         }
 
         let mut writer = tokio::io::BufWriter::new(&mut file);
-        use futures_util::StreamExt;
         let mut stream = res.bytes_stream();
+        let transfer = TransferCounter::start();
         let mut downloaded: u64 = if is_partial { current_size } else { 0 };
 
+        let mut resume_state = DownloadResumeState {
+            url: url.to_string(),
+            etag: resume_validator.clone(),
+            total_size,
+            downloaded,
+        };
+        save_download_state(temp_path, &resume_state).await;
+        let mut last_state_save = std::time::Instant::now();
+
         let mut stream_error = None;
         let mut cancelled = false;
 
@@ -339,15 +1779,24 @@ pub async fn download_file(
                 cancelled = true;
             }
             res = async {
-                while let Some(item) = stream.next().await {
+                while let Some(item) = next_body_chunk(&mut stream, stall_timeout).await? {
                     match item {
                         Ok(chunk) => {
+                            transfer.add(chunk.len());
                             if let Err(e) = writer.write_all(&chunk).await {
-                                return Err(DownloadError::Io(e));
+                                return Err(DownloadError::write(temp_path, e));
                             }
+                            hasher.update(&chunk);
+                            hashed += chunk.len() as u64;
                             downloaded += chunk.len() as u64;
-                            if let Some(cb) = on_progress.as_mut() {
-                                cb(downloaded, total_size);
+                            on_event(DownloadEvent::Progress {
+                                downloaded,
+                                total: total_size,
+                            });
+                            if last_state_save.elapsed() >= DOWNLOAD_STATE_SAVE_INTERVAL {
+                                resume_state.downloaded = downloaded;
+                                save_download_state(temp_path, &resume_state).await;
+                                last_state_save = std::time::Instant::now();
                             }
                         }
                         Err(e) => {
@@ -363,10 +1812,13 @@ pub async fn download_file(
             }
         };
 
-        writer.flush().await?;
+        let write_error = |error| DownloadError::write(temp_path, error);
+        writer.flush().await.map_err(write_error)?;
         // Drop the writer to release the &mut borrow before calling sync_all.
         drop(writer);
-        file.sync_all().await?;
+        file.sync_all().await.map_err(write_error)?;
+        resume_state.downloaded = downloaded;
+        save_download_state(temp_path, &resume_state).await;
 
         if cancelled {
             return Err(DownloadError::Cancelled);
@@ -376,16 +1828,175 @@ pub async fn download_file(
             if downloaded > current_size {
                 attempt = 0;
             }
-            if attempt < max_retries && matches!(e, DownloadError::Network(_)) {
+            if attempt < max_retries && e.is_transient() {
                 attempt += 1;
-                tokio::time::sleep(std::time::Duration::from_secs(1 << (attempt - 1))).await;
+                retry_after(policy, url, attempt, max_retries, &e, &notify, on_event).await?;
                 continue;
             }
             return Err(e);
         }
 
-        return Ok(());
+        return Ok(hex::encode(hasher.finalize()));
+    }
+}
+
+/// Waits for the next body chunk. The wait starts over with every chunk and
+/// ends in [`DownloadError::Stalled`] when `stall_timeout` runs out.
+pub(crate) async fn next_body_chunk<S>(
+    stream: &mut S,
+    stall_timeout: Duration,
+) -> Result<Option<S::Item>, DownloadError>
+where
+    S: futures_util::Stream + Unpin,
+{
+    use futures_util::StreamExt;
+    tokio::time::timeout(stall_timeout, stream.next())
+        .await
+        .map_err(|_| DownloadError::Stalled {
+            timeout: stall_timeout,
+        })
+}
+
+/// Feeds the first `len` bytes of `file` to `hasher`, leaving the cursor
+/// wherever the read stopped.
+pub(crate) async fn hash_file_prefix(
+    file: &mut tokio::fs::File,
+    len: u64,
+    hasher: &mut Sha256,
+) -> std::io::Result<()> {
+    file.seek(SeekFrom::Start(0)).await?;
+    let mut prefix = file.take(len);
+    let mut buffer = vec![0_u8; 16 * 1024];
+    loop {
+        let read = prefix.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// Empties the partial file after the server refused to continue it, then
+/// backs off like any other retry. Restarts count against `max_retries`, so
+/// a server that keeps refusing ends the download instead of looping.
+async fn restart_from_scratch(
+    file: &mut tokio::fs::File,
+    attempt: &mut u32,
+    max_retries: u32,
+    policy: &NetworkPolicy,
+    url: &str,
+    notify: &Notify,
+    on_event: &mut (dyn FnMut(DownloadEvent) + Send),
+) -> Result<(), DownloadError> {
+    file.set_len(0).await?;
+    file.seek(SeekFrom::Start(0)).await?;
+    if *attempt >= max_retries {
+        return Err(DownloadError::RangeNotSatisfiable);
+    }
+    *attempt += 1;
+    let reason = DownloadError::RangeNotSatisfiable;
+    retry_after(
+        policy,
+        url,
+        *attempt,
+        max_retries,
+        &reason,
+        notify,
+        on_event,
+    )
+    .await
+}
+
+/// Reports retry `attempt` and waits out its backoff. A cancellation during
+/// the wait ends it with [`DownloadError::Cancelled`].
+async fn retry_after(
+    policy: &NetworkPolicy,
+    url: &str,
+    attempt: u32,
+    max_retries: u32,
+    reason: &(dyn std::fmt::Display + Sync),
+    notify: &Notify,
+    on_event: &mut (dyn FnMut(DownloadEvent) + Send),
+) -> Result<(), DownloadError> {
+    let delay = policy.retry_delay(attempt);
+    on_event(DownloadEvent::Retry {
+        url: url.to_string(),
+        attempt,
+        max_retries,
+        delay_ms: delay.as_millis() as u64,
+        reason: reason.to_string(),
+    });
+    tokio::select! {
+        _ = notify.notified() => Err(DownloadError::Cancelled),
+        _ = tokio::time::sleep(delay) => Ok(()),
+    }
+}
+
+/// Extracts a SHA-256 digest as lowercase hex from the headers object stores
+/// and RFC 9530 / RFC 3230 servers use; all of them carry it base64 encoded.
+fn sha256_from_digest_headers(headers: &HeaderMap) -> Option<String> {
+    use base64::Engine;
+
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+    let from_list = |value: &str| {
+        value.split(',').find_map(|item| {
+            let (algorithm, digest) = item.trim().split_once('=')?;
+            algorithm
+                .trim()
+                .eq_ignore_ascii_case("sha-256")
+                .then(|| digest.trim().trim_matches(':').to_string())
+        })
+    };
+
+    let encoded = header("x-amz-checksum-sha256")
+        .map(str::to_string)
+        .or_else(|| header("repr-digest").and_then(from_list))
+        .or_else(|| header("digest").and_then(from_list))?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(encoded.trim())
+        .ok()?;
+    (bytes.len() == 32).then(|| hex::encode(bytes))
+}
+
+/// Picks the validator to send as `If-Range` when resuming.
+///
+/// `If-Range` only accepts strong validators, so a weak ETag (`W/"..."`) falls
+/// back to `Last-Modified`. Without either, a resumed response cannot be tied
+/// to the original file version.
+fn resume_validator_from_headers(headers: &HeaderMap) -> Option<String> {
+    let etag = headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if let Some(etag) = etag
+        && !etag.starts_with("W/")
+    {
+        return Some(etag.to_string());
     }
+
+    headers
+        .get(LAST_MODIFIED)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+/// Returns the first byte offset of a `Content-Range: bytes <start>-<end>/<len>`
+/// header.
+pub(crate) fn content_range_start(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .trim()
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
 }
 
 /// Opens `temp_path` for reading and writing (creating it if absent) and
@@ -394,8 +2005,11 @@ pub async fn download_file(
 /// Failing early here — before any network activity — means a competing
 /// download (e.g. the GUI) is detected without wasting a TCP connection.
 async fn open_and_lock_download_file(temp_path: &Path) -> Result<tokio::fs::File, DownloadError> {
+    let write_error = |error| DownloadError::write(temp_path, error);
     if let Some(parent) = temp_path.parent() {
-        tokio::fs::create_dir_all(parent).await?;
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(write_error)?;
     }
 
     // Open for both reading and writing so that a single handle supports
@@ -409,7 +2023,8 @@ async fn open_and_lock_download_file(temp_path: &Path) -> Result<tokio::fs::File
         .create(true)
         .truncate(false)
         .open(temp_path)
-        .await?;
+        .await
+        .map_err(write_error)?;
 
     use fs3::FileExt;
     let std_file = file.into_std().await;
@@ -424,6 +2039,99 @@ mod tests {
     use super::*;
     use std::path::Path;
 
+    #[test]
+    fn disk_space_check_keeps_a_margin_free() {
+        let path = Path::new("model.onnx.download");
+        assert!(check_disk_space(path, 100, 100 + DISK_SPACE_MARGIN).is_ok());
+        assert!(check_disk_space(path, 0, DISK_SPACE_MARGIN).is_ok());
+
+        let error = check_disk_space(path, 101, 100 + DISK_SPACE_MARGIN).unwrap_err();
+        assert!(matches!(
+            error,
+            DownloadError::InsufficientDiskSpace { needed, available, .. }
+                if needed == 101 + DISK_SPACE_MARGIN && available == 100 + DISK_SPACE_MARGIN
+        ));
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "insufficient disk space: need {} bytes, have {} bytes",
+                101 + DISK_SPACE_MARGIN,
+                100 + DISK_SPACE_MARGIN
+            )
+        );
+        assert!(check_disk_space(path, u64::MAX, u64::MAX - 1).is_err());
+    }
+
+    #[test]
+    fn sha256_digest_is_read_from_supported_headers() {
+        // SHA-256 of the empty string.
+        let hex_digest = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+        let base64_digest = "47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU=";
+
+        for (name, value) in [
+            ("x-amz-checksum-sha256", base64_digest.to_string()),
+            (
+                "repr-digest",
+                format!("sha-512=:AAAA:, sha-256=:{base64_digest}:"),
+            ),
+            ("digest", format!("SHA-256={base64_digest}")),
+        ] {
+            let mut headers = HeaderMap::new();
+            headers.insert(name, value.parse().unwrap());
+            assert_eq!(
+                sha256_from_digest_headers(&headers).as_deref(),
+                Some(hex_digest),
+                "{name}"
+            );
+        }
+
+        let mut headers = HeaderMap::new();
+        headers.insert("digest", "md5=AAAA".parse().unwrap());
+        assert_eq!(sha256_from_digest_headers(&headers), None);
+    }
+
+    #[test]
+    fn resume_validator_prefers_strong_etag_over_last_modified() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "\"v1\"".parse().unwrap());
+        headers.insert(
+            LAST_MODIFIED,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+
+        assert_eq!(
+            resume_validator_from_headers(&headers).as_deref(),
+            Some("\"v1\"")
+        );
+    }
+
+    #[test]
+    fn resume_validator_falls_back_to_last_modified_for_weak_etag() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, "W/\"v1\"".parse().unwrap());
+
+        assert_eq!(resume_validator_from_headers(&headers), None);
+
+        headers.insert(
+            LAST_MODIFIED,
+            "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap(),
+        );
+
+        assert_eq!(
+            resume_validator_from_headers(&headers).as_deref(),
+            Some("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+    }
+
+    #[test]
+    fn content_range_start_reads_first_byte_offset() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(content_range_start(&headers), None);
+
+        headers.insert(CONTENT_RANGE, "bytes 5-9/10".parse().unwrap());
+        assert_eq!(content_range_start(&headers), Some(5));
+    }
+
     #[test]
     fn temporary_download_path_uses_sibling_file() {
         let path = temporary_download_path(Path::new("C:/models/silero_vad.onnx"));
@@ -461,6 +2169,20 @@ mod tests {
         assert!(!temp_path.exists());
     }
 
+    #[tokio::test]
+    async fn failed_publish_keeps_the_previous_final_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let final_path = dir.path().join("silero_vad.onnx");
+        tokio::fs::write(&final_path, b"old-good").await.unwrap();
+
+        // The partial file is gone, e.g. removed by a concurrent cleanup.
+        let result =
+            publish_download_file(&dir.path().join("silero_vad.onnx.download"), &final_path).await;
+
+        assert!(result.is_err());
+        assert_eq!(tokio::fs::read(&final_path).await.unwrap(), b"old-good");
+    }
+
     #[tokio::test]
     async fn complete_download_file_keeps_final_and_removes_temp_when_hash_mismatches() {
         let dir = tempfile::tempdir().unwrap();
@@ -550,11 +2272,146 @@ mod tests {
         let notify = Arc::new(Notify::new());
 
         // This should fail with AlreadyInProgress
-        let result = download_file(&client, &url, &temp_path, notify, None).await;
+        let result = download_file(
+            &client,
+            &NetworkPolicy::default(),
+            &url,
+            &temp_path,
+            notify,
+            None,
+        )
+        .await;
 
         assert!(matches!(result, Err(DownloadError::AlreadyInProgress)));
     }
 
+    #[tokio::test]
+    async fn resume_with_changed_file_restarts_from_full_response() {
+        use axum::body::{Body, Bytes};
+        use axum::http::{HeaderMap as AxumHeaderMap, StatusCode, header};
+        use axum::response::{IntoResponse, Response};
+        use axum::{Router, routing::get};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::{Arc, Mutex};
+        use tokio::net::TcpListener;
+
+        let requests = Arc::new(AtomicUsize::new(0));
+        let seen_if_range = Arc::new(Mutex::new(None));
+        let requests_for_route = requests.clone();
+        let seen_if_range_for_route = seen_if_range.clone();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let url = format!("http://{addr}/model.onnx");
+        let app = Router::new().route(
+            "/model.onnx",
+            get(move |headers: AxumHeaderMap| {
+                let requests = requests_for_route.clone();
+                let seen_if_range = seen_if_range_for_route.clone();
+                async move {
+                    if requests.fetch_add(1, Ordering::SeqCst) == 0 {
+                        // First version: send a prefix, then break the stream.
+                        use futures_util::StreamExt;
+                        let prefix = futures_util::stream::iter([Ok::<_, std::io::Error>(
+                            Bytes::from_static(b"old-"),
+                        )]);
+                        let reset = futures_util::stream::once(async {
+                            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                            Err(std::io::Error::other("connection reset"))
+                        });
+                        return Response::builder()
+                            .header(header::ETAG, "\"v1\"")
+                            .body(Body::from_stream(prefix.chain(reset)))
+                            .unwrap();
+                    }
+
+                    *seen_if_range.lock().unwrap() = headers
+                        .get(header::IF_RANGE)
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string);
+                    // The file changed, so If-Range no longer matches and the
+                    // server answers with the complete new version.
+                    (
+                        StatusCode::OK,
+                        [(header::ETAG, "\"v2\"")],
+                        "new-model-bytes",
+                    )
+                        .into_response()
+                }
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.onnx.download");
+
+        download_file(
+            &reqwest::Client::new(),
+            &NetworkPolicy::default(),
+            &url,
+            &temp_path,
+            Arc::new(Notify::new()),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert_eq!(seen_if_range.lock().unwrap().as_deref(), Some("\"v1\""));
+        assert_eq!(
+            tokio::fs::read(&temp_path).await.unwrap(),
+            b"new-model-bytes"
+        );
+    }
+
+    #[tokio::test]
+    async fn partial_response_for_wrong_offset_restarts_download() {
+        use axum::http::{HeaderMap as AxumHeaderMap, StatusCode, header};
+        use axum::response::IntoResponse;
+        use axum::{Router, routing::get};
+        use tokio::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let url = format!("http://{addr}/model.onnx");
+        let app = Router::new().route(
+            "/model.onnx",
+            get(|headers: AxumHeaderMap| async move {
+                if headers.contains_key(header::RANGE) {
+                    return (
+                        StatusCode::PARTIAL_CONTENT,
+                        [(header::CONTENT_RANGE, "bytes 0-4/10")],
+                        "model",
+                    )
+                        .into_response();
+                }
+                (StatusCode::OK, "model-full").into_response()
+            }),
+        );
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let temp_path = dir.path().join("model.onnx.download");
+        tokio::fs::write(&temp_path, b"stale").await.unwrap();
+
+        download_file(
+            &reqwest::Client::new(),
+            &NetworkPolicy::default(),
+            &url,
+            &temp_path,
+            Arc::new(Notify::new()),
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(tokio::fs::read(&temp_path).await.unwrap(), b"model-full");
+    }
+
     #[tokio::test]
     async fn download_client_sets_sona_user_agent() {
         use axum::http::{HeaderMap, header::USER_AGENT};
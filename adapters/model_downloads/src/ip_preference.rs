@@ -0,0 +1,143 @@
+//! Address family choice for download connections, and a probe reporting
+//! which families can reach a host, for networks with broken IPv6 (or IPv4).
+
+use std::net::{IpAddr, SocketAddr};
+use std::time::Duration;
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+
+use crate::downloads::ConnectivityError;
+
+/// Addresses per family a connectivity probe tries before giving up on it.
+const MAX_PROBED_ADDRESSES: usize = 3;
+
+/// Which address family download connections try first. The connector races
+/// the other family only after its happy-eyeballs delay (300 ms), so a
+/// family that hangs on this network stops costing a stall per connection.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum IpPreference {
+    /// Keep the order the system resolver returns.
+    #[default]
+    System,
+    PreferIpv4,
+    PreferIpv6,
+}
+
+impl IpPreference {
+    fn sort(self, addresses: &mut [SocketAddr]) {
+        match self {
+            Self::System => {}
+            Self::PreferIpv4 => addresses.sort_by_key(|address| !address.is_ipv4()),
+            Self::PreferIpv6 => addresses.sort_by_key(|address| !address.is_ipv6()),
+        }
+    }
+}
+
+/// System resolver whose answers are reordered by an [`IpPreference`].
+pub(crate) struct PreferringResolver(pub(crate) IpPreference);
+
+impl Resolve for PreferringResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let preference = self.0;
+        Box::pin(async move {
+            let mut addresses = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .collect::<Vec<_>>();
+            preference.sort(&mut addresses);
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// How one address family fared in [`test_ip_connectivity`].
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpFamilyConnectivity {
+    /// Addresses of this family the host resolved to.
+    pub addresses: Vec<IpAddr>,
+    /// The address that accepted a connection.
+    pub connected: Option<IpAddr>,
+    /// Why no address of this family connected.
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IpConnectivity {
+    pub host: String,
+    pub ipv4: IpFamilyConnectivity,
+    pub ipv6: IpFamilyConnectivity,
+}
+
+/// Resolves `host` and opens a TCP connection to `port` over IPv4 and IPv6
+/// separately, each attempt limited to `timeout`. Only a failed lookup is an
+/// error; a family that cannot connect says why in its result.
+pub async fn test_ip_connectivity(
+    host: &str,
+    port: u16,
+    timeout: Duration,
+) -> Result<IpConnectivity, ConnectivityError> {
+    let host = host.trim().trim_start_matches('[').trim_end_matches(']');
+    if host.is_empty() {
+        return Err(ConnectivityError::InvalidUrl {
+            url: host.to_string(),
+            reason: "host name is empty".to_string(),
+        });
+    }
+    let dns_error = || ConnectivityError::Dns {
+        host: host.to_string(),
+    };
+    let addresses = tokio::time::timeout(timeout, tokio::net::lookup_host((host, port)))
+        .await
+        .map_err(|_| dns_error())?
+        .map_err(|_| dns_error())?
+        .collect::<Vec<_>>();
+
+    let ipv4_addresses = addresses.iter().filter(|address| address.is_ipv4());
+    let ipv6_addresses = addresses.iter().filter(|address| address.is_ipv6());
+    let (ipv4, ipv6) = tokio::join!(
+        probe_family(host, ipv4_addresses, "IPv4", timeout),
+        probe_family(host, ipv6_addresses, "IPv6", timeout),
+    );
+    Ok(IpConnectivity {
+        host: host.to_string(),
+        ipv4,
+        ipv6,
+    })
+}
+
+async fn probe_family(
+    host: &str,
+    addresses: impl Iterator<Item = &SocketAddr>,
+    family: &str,
+    timeout: Duration,
+) -> IpFamilyConnectivity {
+    let addresses = addresses.copied().collect::<Vec<_>>();
+    let mut result = IpFamilyConnectivity {
+        addresses: addresses.iter().map(SocketAddr::ip).collect(),
+        connected: None,
+        error: Some(format!("{host} has no {family} address")),
+    };
+    for address in addresses.into_iter().take(MAX_PROBED_ADDRESSES) {
+        let host = address.ip().to_string();
+        let error =
+            match tokio::time::timeout(timeout, tokio::net::TcpStream::connect(address)).await {
+                Ok(Ok(_)) => {
+                    result.connected = Some(address.ip());
+                    result.error = None;
+                    return result;
+                }
+                Ok(Err(error)) if error.kind() == std::io::ErrorKind::ConnectionRefused => {
+                    ConnectivityError::ConnectionRefused { host }
+                }
+                Ok(Err(error)) => ConnectivityError::Other {
+                    host,
+                    reason: error.to_string(),
+                },
+                Err(_) => ConnectivityError::Timeout { host },
+            };
+        result.error = Some(error.to_string());
+    }
+    result
+}
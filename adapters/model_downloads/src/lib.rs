@@ -1,9 +1,32 @@
 pub mod downloads;
+mod ip_preference;
+mod model_scan;
 mod models;
+mod network_stats;
+mod parallel_download;
+mod robust_download;
+mod stream_extract;
 
 pub use downloads::{
-    DownloadClient, DownloadError, DownloadFileOperation, DownloadFileSystemError,
-    complete_download_file, download_file, publish_download_file, remove_download_file,
-    sha256_file, temporary_download_path, verify_download_file,
+    CONNECTIVITY_TIMEOUT, ConnectivityError, DEFAULT_STALL_TIMEOUT, DEFAULT_USER_AGENT,
+    DISK_SPACE_MARGIN, DOWNLOAD_STATE_SUFFIX, DownloadClient, DownloadError, DownloadFileOperation,
+    DownloadFileSystemError, DownloadResumeState, DownloadWriteErrorKind, MAX_REDIRECTS,
+    MEMORY_DOWNLOAD_DEFAULT_LIMIT, MEMORY_DOWNLOAD_MAX_LIMIT, NetworkPolicy, PROXY_BYPASS_HOSTS,
+    PartialDownloadInfo, RemoteVerification, RemoteVerificationStatus, RequestOptions,
+    TEMPORARY_DOWNLOAD_SUFFIX, clean_partial_downloads, complete_download_file,
+    complete_hashed_download_file, download_file, download_state_path, download_to_memory,
+    ensure_disk_space, flush_and_verify_file, list_partial_downloads, publish_download_file,
+    read_download_state, remove_download_file, sha256_file, sha256_file_with_progress,
+    temporary_download_path, temporary_download_path_in, validate_temp_dir, verify_download_file,
+};
+pub use ip_preference::{IpConnectivity, IpFamilyConnectivity, IpPreference, test_ip_connectivity};
+pub use model_scan::{
+    ModelInfo, ModelManifest, ModelManifestFile, ModelScanProgress, ModelScanReport,
+    ModelScanResult, ModelScanStatus, export_model_info, scan_models,
 };
 pub use models::{download_model, installed_model_is_valid, remove_model_install_path};
+pub use network_stats::{NetworkStats, network_stats};
+pub use robust_download::{DownloadEvent, DownloadSpec, robust_download};
+pub use stream_extract::{
+    StreamExtractProgress, StreamExtractSummary, download_and_extract, stream_extract_staging_dir,
+};
@@ -0,0 +1,305 @@
+//! Integrity scan of installed models against manifests of their expected
+//! files, for catching bit-rot and interrupted installs, and the export of
+//! such a manifest for an installed model.
+
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use sha2::{Digest, Sha256};
+
+use crate::downloads::{DownloadError, DownloadFileOperation};
+
+const SCAN_READ_BUFFER_BYTES: usize = 1024 * 1024;
+
+/// Files a model consists of, relative to the scanned models directory.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelManifest {
+    pub model_id: String,
+    pub files: Vec<ModelManifestFile>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelManifestFile {
+    pub path: PathBuf,
+    pub size: u64,
+    /// Hashed only when given; size alone catches truncated installs.
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModelScanStatus {
+    Ok,
+    /// A file has the wrong hash, is larger than expected or cannot be read.
+    Corrupt,
+    /// A file is missing or shorter than expected.
+    Incomplete,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelScanResult {
+    pub model_id: String,
+    pub status: ModelScanStatus,
+    /// The first file that failed, and why.
+    pub problem: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelScanReport {
+    pub models: Vec<ModelScanResult>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelScanProgress {
+    pub model_id: String,
+    pub models_done: usize,
+    pub models_total: usize,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+/// Manifest paths must stay inside the models directory.
+fn validate_manifest_path(manifest: &ModelManifest, path: &Path) -> Result<(), DownloadError> {
+    if path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)))
+    {
+        return Ok(());
+    }
+    Err(DownloadError::InvalidModelManifest {
+        reason: format!(
+            "{}: file path {} must be relative to the models directory",
+            manifest.model_id,
+            path.display()
+        ),
+    })
+}
+
+/// Checks every file of every manifest under `dir`, hashing those with a
+/// `sha256`. Blocking; `cancel` is polled between reads and ends the scan with
+/// [`DownloadError::Cancelled`].
+pub fn scan_models(
+    dir: &Path,
+    manifests: &[ModelManifest],
+    cancel: &AtomicBool,
+    mut on_progress: impl FnMut(&ModelScanProgress),
+) -> Result<ModelScanReport, DownloadError> {
+    for manifest in manifests {
+        for file in &manifest.files {
+            validate_manifest_path(manifest, &file.path)?;
+        }
+    }
+
+    let hashed_size = |file: &ModelManifestFile| file.sha256.as_ref().map_or(0, |_| file.size);
+    let mut progress = ModelScanProgress {
+        model_id: String::new(),
+        models_done: 0,
+        models_total: manifests.len(),
+        bytes_done: 0,
+        bytes_total: manifests
+            .iter()
+            .flat_map(|manifest| &manifest.files)
+            .map(hashed_size)
+            .sum(),
+    };
+    let mut report = ModelScanReport::default();
+    for manifest in manifests {
+        progress.model_id = manifest.model_id.clone();
+        on_progress(&progress);
+
+        let mut result = ModelScanResult {
+            model_id: manifest.model_id.clone(),
+            status: ModelScanStatus::Ok,
+            problem: None,
+        };
+        let mut bytes_done = progress.bytes_done;
+        // Files after the first failure are skipped but still count as done.
+        let model_end = bytes_done + manifest.files.iter().map(hashed_size).sum::<u64>();
+        for file in &manifest.files {
+            let checked = check_manifest_file(&dir.join(&file.path), file, cancel, |read| {
+                progress.bytes_done = bytes_done + read;
+                on_progress(&progress);
+            })?;
+            bytes_done += hashed_size(file);
+            if let Err((status, problem)) = checked {
+                result.status = status;
+                result.problem = Some(format!("{}: {problem}", file.path.display()));
+                break;
+            }
+        }
+
+        progress.bytes_done = model_end;
+        progress.models_done += 1;
+        on_progress(&progress);
+        report.models.push(result);
+    }
+    Ok(report)
+}
+
+/// The outer error aborts the scan; the inner one is a finding about `path`.
+fn check_manifest_file(
+    path: &Path,
+    expected: &ModelManifestFile,
+    cancel: &AtomicBool,
+    on_read: impl FnMut(u64),
+) -> Result<Result<(), (ModelScanStatus, String)>, DownloadError> {
+    let size = match std::fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        Ok(_) => {
+            return Ok(Err((ModelScanStatus::Corrupt, "not a file".to_string())));
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => {
+            return Ok(Err((ModelScanStatus::Incomplete, "missing".to_string())));
+        }
+        Err(error) => return Ok(Err((ModelScanStatus::Corrupt, error.to_string()))),
+    };
+    if size != expected.size {
+        let status = if size < expected.size {
+            ModelScanStatus::Incomplete
+        } else {
+            ModelScanStatus::Corrupt
+        };
+        return Ok(Err((
+            status,
+            format!("expected {} bytes, found {size}", expected.size),
+        )));
+    }
+    let Some(expected_sha256) = &expected.sha256 else {
+        return Ok(Ok(()));
+    };
+
+    let actual = match hash_file(path, cancel, on_read)? {
+        Ok(actual) => actual,
+        Err(error) => return Ok(Err((ModelScanStatus::Corrupt, error.to_string()))),
+    };
+    if actual.eq_ignore_ascii_case(expected_sha256.trim()) {
+        Ok(Ok(()))
+    } else {
+        Ok(Err((
+            ModelScanStatus::Corrupt,
+            format!("hash mismatch: expected {expected_sha256}, got {actual}"),
+        )))
+    }
+}
+
+/// SHA-256 of `path` in hex. The outer error is cancellation; the inner one
+/// a failed read.
+fn hash_file(
+    path: &Path,
+    cancel: &AtomicBool,
+    mut on_read: impl FnMut(u64),
+) -> Result<std::io::Result<String>, DownloadError> {
+    let mut reader = match std::fs::File::open(path) {
+        Ok(file) => file,
+        Err(error) => return Ok(Err(error)),
+    };
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; SCAN_READ_BUFFER_BYTES];
+    let mut read_total = 0;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(DownloadError::Cancelled);
+        }
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Ok(Err(error)),
+        };
+        hasher.update(&buffer[..read]);
+        read_total += read as u64;
+        on_read(read_total);
+    }
+    Ok(Ok(hex::encode(hasher.finalize())))
+}
+
+/// Shareable description of an installed model: a manifest that
+/// [`scan_models`] accepts for the model's parent directory, plus where the
+/// model came from when it is a preset.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    #[serde(flatten)]
+    pub manifest: ModelManifest,
+    /// Download URL of the preset installed at this path. Finished downloads
+    /// drop their resume sidecar, so the preset catalog is the only record.
+    pub source_url: Option<String>,
+    pub total_size: u64,
+}
+
+/// Lists, sizes and hashes every file of the model installed at `path`, a
+/// model directory or a single-file model. Blocking. Symlinks are skipped.
+pub fn export_model_info(path: &Path) -> Result<ModelInfo, DownloadError> {
+    let inspect_error = |path: &Path, error: std::io::Error| {
+        DownloadError::file_system(
+            DownloadFileOperation::InspectInstall,
+            path,
+            error.to_string(),
+        )
+    };
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            DownloadError::file_system(
+                DownloadFileOperation::InspectInstall,
+                path,
+                "path has no file name",
+            )
+        })?
+        .to_string();
+
+    let mut pending = vec![(path.to_path_buf(), name.clone())];
+    let mut files = Vec::new();
+    while let Some((current, relative)) = pending.pop() {
+        let metadata =
+            std::fs::symlink_metadata(&current).map_err(|error| inspect_error(&current, error))?;
+        if metadata.is_dir() {
+            let mut entries = std::fs::read_dir(&current)
+                .and_then(|entries| entries.collect::<std::io::Result<Vec<_>>>())
+                .map_err(|error| inspect_error(&current, error))?;
+            // Popped in reverse, so files come out in name order.
+            entries.sort_by_key(|entry| std::cmp::Reverse(entry.file_name()));
+            for entry in entries {
+                let entry_name = entry.file_name().to_string_lossy().into_owned();
+                pending.push((entry.path(), format!("{relative}/{entry_name}")));
+            }
+        } else if metadata.is_file() {
+            let sha256 =
+                hash_file(&current, &AtomicBool::new(false), |_| {})?.map_err(|error| {
+                    DownloadError::file_system(
+                        DownloadFileOperation::HashFile,
+                        &current,
+                        error.to_string(),
+                    )
+                })?;
+            files.push(ModelManifestFile {
+                path: PathBuf::from(relative),
+                size: metadata.len(),
+                sha256: Some(sha256),
+            });
+        }
+    }
+
+    let source_url = path.parent().and_then(|models_dir| {
+        sona_core::models::preset_models::preset_models()
+            .iter()
+            .find(|model| model.resolve_install_path(models_dir) == path)
+            .map(|model| model.url.clone())
+    });
+    Ok(ModelInfo {
+        total_size: files.iter().map(|file| file.size).sum(),
+        manifest: ModelManifest {
+            model_id: name,
+            files,
+        },
+        source_url,
+    })
+}
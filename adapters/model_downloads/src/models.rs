@@ -1,8 +1,8 @@
 use std::path::{Path, PathBuf};
 
 use crate::downloads::{
-    DownloadClient, DownloadError, DownloadFileOperation, publish_download_file, sha256_file,
-    temporary_download_path,
+    DownloadClient, DownloadError, DownloadFileOperation, publish_download_file,
+    remove_download_file, sha256_file, temporary_download_path,
 };
 use sona_core::models::downloads::ResolvedModelDownload;
 
@@ -107,24 +107,24 @@ where
 
     ctrl_c_task.abort();
 
-    result?;
+    let actual_sha = result?;
+
+    if let Some(expected_sha) = &resolved.model.sha256
+        && !actual_sha.eq_ignore_ascii_case(expected_sha)
+    {
+        remove_download_file(&temp_download_path).await;
+        return Err(DownloadError::HashMismatch {
+            path: temp_download_path,
+            expected: expected_sha.clone(),
+            actual: actual_sha,
+        });
+    }
 
-    if let Some(expected_sha) = &resolved.model.sha256 {
-        let actual_sha = match sha256_file(&temp_download_path).await {
-            Ok(sha) => sha,
-            Err(error) => {
-                let _ = tokio::fs::remove_file(&temp_download_path).await;
-                return Err(error);
-            }
-        };
-        if !actual_sha.eq_ignore_ascii_case(expected_sha) {
-            let _ = tokio::fs::remove_file(&temp_download_path).await;
-            return Err(DownloadError::HashMismatch {
-                path: temp_download_path,
-                expected: expected_sha.clone(),
-                actual: actual_sha,
-            });
-        }
+    if resolved.model.is_archive()
+        && let Err(error) = verify_tar_bz2_archive(&temp_download_path).await
+    {
+        remove_download_file(&temp_download_path).await;
+        return Err(error);
     }
 
     publish_download_file(&temp_download_path, &resolved.download_path).await?;
@@ -145,6 +145,56 @@ where
     Ok(resolved.install_path.clone())
 }
 
+/// Decompresses the whole archive and reads every entry without writing
+/// anything, so a corrupt download is reported before extraction starts.
+async fn verify_tar_bz2_archive(archive_path: &Path) -> Result<(), DownloadError> {
+    let archive_path = archive_path.to_path_buf();
+    let join_archive_path = archive_path.clone();
+
+    tokio::task::spawn_blocking(move || {
+        let corrupt = |reason: String| DownloadError::CorruptArchive {
+            path: archive_path.clone(),
+            reason,
+        };
+        let file = std::fs::File::open(&archive_path).map_err(|error| {
+            DownloadError::file_system(
+                DownloadFileOperation::OpenArchive,
+                &archive_path,
+                error.to_string(),
+            )
+        })?;
+        let tar = bzip2::read::BzDecoder::new(std::io::BufReader::new(file));
+        let mut archive = tar::Archive::new(tar);
+        for entry in archive
+            .entries()
+            .map_err(|error| corrupt(error.to_string()))?
+        {
+            let mut entry = entry.map_err(|error| corrupt(error.to_string()))?;
+            let expected = entry.size();
+            let read = std::io::copy(&mut entry, &mut std::io::sink())
+                .map_err(|error| corrupt(error.to_string()))?;
+            // A stream that ends inside an entry reads short rather than failing.
+            if read != expected {
+                return Err(corrupt(format!(
+                    "entry ended after {read} of {expected} bytes"
+                )));
+            }
+        }
+        // Drain the rest so the decoder checks its trailer and checksum.
+        std::io::copy(&mut archive.into_inner(), &mut std::io::sink())
+            .map_err(|error| corrupt(error.to_string()))?;
+        Ok(())
+    })
+    .await
+    .map_err(|error| {
+        DownloadError::file_system(
+            DownloadFileOperation::ExtractArchive,
+            join_archive_path,
+            format!("Failed to join verification task: {error}"),
+        )
+    })?
+}
+
 async fn extract_tar_bz2_archive(
     archive_path: &Path,
     target_dir: &Path,
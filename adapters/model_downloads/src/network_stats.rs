@@ -0,0 +1,43 @@
+//! Session-wide transfer counters for diagnostics.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
+static ACTIVE_TRANSFERS: AtomicUsize = AtomicUsize::new(0);
+static BYTES_RECEIVED: AtomicU64 = AtomicU64::new(0);
+
+/// Transfers across every [`crate::DownloadClient`] since the process started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStats {
+    /// Response bodies being read right now, each holding one connection.
+    /// Idle pooled connections are not visible through reqwest.
+    pub active_transfers: usize,
+    pub bytes_received: u64,
+}
+
+pub fn network_stats() -> NetworkStats {
+    NetworkStats {
+        active_transfers: ACTIVE_TRANSFERS.load(Ordering::Relaxed),
+        bytes_received: BYTES_RECEIVED.load(Ordering::Relaxed),
+    }
+}
+
+/// Counts one response body while it is read; dropping it ends the transfer.
+pub(crate) struct TransferCounter(());
+
+impl TransferCounter {
+    pub(crate) fn start() -> Self {
+        ACTIVE_TRANSFERS.fetch_add(1, Ordering::Relaxed);
+        Self(())
+    }
+
+    pub(crate) fn add(&self, bytes: usize) {
+        BYTES_RECEIVED.fetch_add(bytes as u64, Ordering::Relaxed);
+    }
+}
+
+impl Drop for TransferCounter {
+    fn drop(&mut self) {
+        ACTIVE_TRANSFERS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,333 @@
+//! Multi-connection path of [`crate::downloads::download_with_events`] for
+//! large files on servers that accept byte ranges.
+//!
+//! The file is split into one range per connection. The first range reuses
+//! the full response that advertised range support; the others are fetched
+//! with `Range` and `If-Range`, so every range comes from the same version of
+//! the file. Workers hand their chunks to a single writer that seeks to each
+//! chunk's offset in the pre-allocated file, which keeps one handle (and its
+//! lock) for the whole download.
+
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::Path;
+
+use reqwest::header::{ACCEPT_RANGES, HeaderMap, IF_RANGE, RANGE};
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::{Notify, mpsc};
+
+use crate::downloads::{
+    DownloadError, DownloadResumeState, NetworkPolicy, content_range_start, hash_file_prefix,
+    http_status_error, next_body_chunk, save_download_state,
+};
+use crate::network_stats::TransferCounter;
+use crate::robust_download::DownloadEvent;
+
+/// Chunks each worker may have queued for the writer before it waits.
+const CHUNKS_QUEUED_PER_WORKER: usize = 4;
+
+/// Whether a response to a plain `GET` lets the rest of the file be fetched
+/// in ranges.
+pub(crate) fn accepts_byte_ranges(headers: &HeaderMap) -> bool {
+    headers
+        .get(ACCEPT_RANGES)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .split(',')
+                .any(|unit| unit.trim().eq_ignore_ascii_case("bytes"))
+        })
+}
+
+/// What the workers of one [`download_ranges`] share.
+pub(crate) struct RangedDownload<'a> {
+    pub client: &'a reqwest::Client,
+    pub policy: &'a NetworkPolicy,
+    pub url: &'a str,
+    pub headers: &'a HeaderMap,
+    pub max_retries: u32,
+    /// Sent as `If-Range`; a range from another version of the file fails the
+    /// download instead of being mixed in.
+    pub validator: &'a str,
+    pub total: u64,
+    pub stall_timeout: std::time::Duration,
+}
+
+enum SegmentMessage {
+    Chunk {
+        segment: usize,
+        offset: u64,
+        bytes: bytes::Bytes,
+    },
+    Retry(DownloadEvent),
+}
+
+/// Downloads the file `first` started over `policy.parallel_connections`
+/// connections into `file`, and returns its SHA-256, read back once every
+/// range is written.
+///
+/// On failure or cancellation the file is cut back to the bytes written
+/// contiguously from the start, so a single-stream download can resume from
+/// it. Ranges finished beyond that are downloaded again.
+pub(crate) async fn download_ranges(
+    download: RangedDownload<'_>,
+    first: reqwest::Response,
+    file: &mut tokio::fs::File,
+    temp_path: &Path,
+    notify: &Notify,
+    on_event: &mut (dyn FnMut(DownloadEvent) + Send),
+) -> Result<String, DownloadError> {
+    let total = download.total;
+    let segments = split_ranges(total, download.policy.parallel_connections);
+    let write_error = |error| DownloadError::write(temp_path, error);
+    file.set_len(total).await.map_err(write_error)?;
+
+    let mut resume_state = DownloadResumeState {
+        url: download.url.to_string(),
+        etag: Some(download.validator.to_string()),
+        total_size: total,
+        downloaded: 0,
+    };
+    save_download_state(temp_path, &resume_state).await;
+
+    let mut written = vec![0_u64; segments.len()];
+    let result = {
+        let (sender, mut receiver) = mpsc::channel(segments.len() * CHUNKS_QUEUED_PER_WORKER);
+        let mut first = Some(first);
+        let workers = futures_util::future::try_join_all(segments.iter().enumerate().map(
+            |(index, range)| {
+                fetch_segment(
+                    &download,
+                    index,
+                    range.clone(),
+                    first.take(),
+                    sender.clone(),
+                )
+            },
+        ));
+        drop(sender);
+
+        let written = &mut written;
+        let file = &mut *file;
+        let writer = async move {
+            let mut downloaded = 0;
+            while let Some(message) = receiver.recv().await {
+                match message {
+                    SegmentMessage::Chunk {
+                        segment,
+                        offset,
+                        bytes,
+                    } => {
+                        file.seek(SeekFrom::Start(offset))
+                            .await
+                            .map_err(write_error)?;
+                        file.write_all(&bytes).await.map_err(write_error)?;
+                        written[segment] += bytes.len() as u64;
+                        downloaded += bytes.len() as u64;
+                        on_event(DownloadEvent::Progress { downloaded, total });
+                    }
+                    SegmentMessage::Retry(event) => on_event(event),
+                }
+            }
+            file.flush().await.map_err(write_error)
+        };
+
+        tokio::select! {
+            _ = notify.notified() => Err(DownloadError::Cancelled),
+            result = async { tokio::try_join!(workers, writer).map(|_| ()) } => result,
+        }
+    };
+
+    if let Err(error) = result {
+        let prefix = contiguous_prefix(&segments, &written);
+        file.set_len(prefix).await.map_err(write_error)?;
+        file.sync_all().await.map_err(write_error)?;
+        resume_state.downloaded = prefix;
+        save_download_state(temp_path, &resume_state).await;
+        return Err(error);
+    }
+
+    file.sync_all().await.map_err(write_error)?;
+    resume_state.downloaded = total;
+    save_download_state(temp_path, &resume_state).await;
+    let mut hasher = Sha256::new();
+    hash_file_prefix(file, total, &mut hasher)
+        .await
+        .map_err(|error| {
+            DownloadError::file_system(
+                crate::DownloadFileOperation::HashFile,
+                temp_path,
+                error.to_string(),
+            )
+        })?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Streams one range to the writer, reconnecting from where it stopped after
+/// a transient failure. `first`, when given, is a response whose body starts
+/// at `range.start`; it is read only as far as `range.end`.
+async fn fetch_segment(
+    download: &RangedDownload<'_>,
+    index: usize,
+    range: Range<u64>,
+    mut first: Option<reqwest::Response>,
+    sender: mpsc::Sender<SegmentMessage>,
+) -> Result<(), DownloadError> {
+    let mut position = range.start;
+    let mut attempt = 0;
+    loop {
+        let response = match first.take() {
+            Some(response) => Ok(response),
+            None => request_range(download, position..range.end).await,
+        };
+        let started_at = position;
+        let failure = match response {
+            Ok(response) => {
+                let transfer = TransferCounter::start();
+                let mut stream = response.bytes_stream();
+                let mut failure = None;
+                while position < range.end {
+                    let item = match next_body_chunk(&mut stream, download.stall_timeout).await {
+                        Ok(item) => item,
+                        Err(error) => {
+                            failure = Some(error);
+                            break;
+                        }
+                    };
+                    match item {
+                        Some(Ok(mut chunk)) => {
+                            transfer.add(chunk.len());
+                            chunk.truncate((range.end - position).min(chunk.len() as u64) as usize);
+                            let len = chunk.len() as u64;
+                            let message = SegmentMessage::Chunk {
+                                segment: index,
+                                offset: position,
+                                bytes: chunk,
+                            };
+                            if sender.send(message).await.is_err() {
+                                // The writer stopped; its error is the one reported.
+                                return Ok(());
+                            }
+                            position += len;
+                        }
+                        Some(Err(error)) => {
+                            failure = Some(DownloadError::Network(error));
+                            break;
+                        }
+                        None => {
+                            failure = Some(DownloadError::Io(std::io::Error::new(
+                                std::io::ErrorKind::UnexpectedEof,
+                                format!("connection closed at byte {position} of range {index}"),
+                            )));
+                            break;
+                        }
+                    }
+                }
+                failure
+            }
+            Err(error) => Some(error),
+        };
+        let Some(error) = failure else {
+            return Ok(());
+        };
+        if position > started_at {
+            attempt = 0;
+        }
+        let retryable = error.is_transient() || matches!(error, DownloadError::Io(_));
+        if !retryable || attempt >= download.max_retries {
+            return Err(error);
+        }
+        attempt += 1;
+        let delay = download.policy.retry_delay(attempt);
+        let event = DownloadEvent::Retry {
+            url: download.url.to_string(),
+            attempt,
+            max_retries: download.max_retries,
+            delay_ms: delay.as_millis() as u64,
+            reason: error.to_string(),
+        };
+        if sender.send(SegmentMessage::Retry(event)).await.is_err() {
+            return Ok(());
+        }
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Requests `range` and checks the server answered with exactly it. A full
+/// response means the file changed since the first request.
+async fn request_range(
+    download: &RangedDownload<'_>,
+    range: Range<u64>,
+) -> Result<reqwest::Response, DownloadError> {
+    let response = download
+        .client
+        .get(download.url)
+        .headers(download.headers.clone())
+        .header(RANGE, format!("bytes={}-{}", range.start, range.end - 1))
+        .header(IF_RANGE, download.validator)
+        .send()
+        .await?;
+    if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        if content_range_start(response.headers()) == Some(range.start) {
+            return Ok(response);
+        }
+        return Err(DownloadError::RangeNotSatisfiable);
+    }
+    if response.status().is_success() {
+        return Err(DownloadError::RangeNotSatisfiable);
+    }
+    Err(http_status_error(response).await)
+}
+
+/// Splits `0..total` into at most `connections` ranges of nearly equal size.
+fn split_ranges(total: u64, connections: u32) -> Vec<Range<u64>> {
+    let count = u64::from(connections.max(1)).min(total.max(1));
+    let len = total.div_ceil(count);
+    (0..count)
+        .map(|index| index * len..((index + 1) * len).min(total))
+        .filter(|range| !range.is_empty())
+        .collect()
+}
+
+/// Bytes written without a gap from the start of the file.
+fn contiguous_prefix(segments: &[Range<u64>], written: &[u64]) -> u64 {
+    let mut prefix = 0;
+    for (range, &done) in segments.iter().zip(written) {
+        prefix += done;
+        if range.start + done < range.end {
+            break;
+        }
+    }
+    prefix
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_cover_the_file_without_overlap() {
+        assert_eq!(split_ranges(10, 3), vec![0..4, 4..8, 8..10]);
+        assert_eq!(split_ranges(2, 4), vec![0..1, 1..2]);
+        assert_eq!(split_ranges(9, 1), vec![0..9]);
+    }
+
+    #[test]
+    fn prefix_stops_at_the_first_unfinished_range() {
+        let segments = split_ranges(12, 3);
+        assert_eq!(contiguous_prefix(&segments, &[4, 2, 4]), 6);
+        assert_eq!(contiguous_prefix(&segments, &[3, 4, 4]), 3);
+        assert_eq!(contiguous_prefix(&segments, &[4, 4, 4]), 12);
+    }
+
+    #[test]
+    fn byte_ranges_must_be_advertised() {
+        let mut headers = HeaderMap::new();
+        assert!(!accepts_byte_ranges(&headers));
+        headers.insert(ACCEPT_RANGES, "none".parse().unwrap());
+        assert!(!accepts_byte_ranges(&headers));
+        headers.insert(ACCEPT_RANGES, "Bytes".parse().unwrap());
+        assert!(accepts_byte_ranges(&headers));
+    }
+}
@@ -0,0 +1,202 @@
+//! Single entry point that combines resume, mirrors, custom headers and
+//! checksum verification for one file.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tokio::sync::Notify;
+
+use crate::downloads::{
+    DEFAULT_STALL_TIMEOUT, DownloadError, DownloadRequest, NetworkPolicy,
+    complete_hashed_download_file, custom_header_map, download_with_events,
+    temporary_download_path_in,
+};
+
+/// What to download and how. `urls` are mirrors of the same file, tried in
+/// order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadSpec {
+    pub urls: Vec<String>,
+    pub output_path: PathBuf,
+    #[serde(default)]
+    pub sha256: Option<String>,
+    /// Extra request headers, e.g. `Authorization` for gated models.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Retries per mirror; the client's [`NetworkPolicy`] applies when unset.
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    /// Connections a large file is split across, overriding
+    /// [`NetworkPolicy::parallel_connections`] for this download.
+    #[serde(default)]
+    pub connections: Option<u32>,
+}
+
+/// Steps of a [`robust_download`], in the order they can occur.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum DownloadEvent {
+    /// A server answered; `resumed_from` is non-zero when partial bytes are
+    /// being continued. `final_url` is where `url` redirected to, if anywhere.
+    Started {
+        url: String,
+        final_url: String,
+        resumed_from: u64,
+        total: u64,
+    },
+    Progress {
+        downloaded: u64,
+        total: u64,
+    },
+    /// Retry `attempt` of at most `max_retries` starts after `delay_ms`.
+    Retry {
+        url: String,
+        attempt: u32,
+        max_retries: u32,
+        delay_ms: u64,
+        reason: String,
+    },
+    /// `from` failed for good and the next mirror is being tried.
+    Mirror {
+        from: String,
+        to: String,
+        reason: String,
+    },
+    Complete {
+        url: String,
+        bytes: u64,
+    },
+}
+
+/// Errors that another mirror might not have. Local failures and
+/// cancellation end the download instead.
+fn mirror_can_recover(error: &DownloadError) -> bool {
+    matches!(
+        error,
+        DownloadError::Network(_)
+            | DownloadError::HttpStatus { .. }
+            | DownloadError::HashMismatch { .. }
+            | DownloadError::RangeNotSatisfiable
+            | DownloadError::TooManyRedirects { .. }
+    )
+}
+
+/// Downloads `spec` into its output path, trying each mirror in turn.
+///
+/// Each mirror gets the retry budget and connection count from the spec or
+/// `policy`. Partial bytes left by an earlier run are continued from their
+/// sidecar; bytes from a different mirror are only kept when `sha256` will
+/// verify them. The file is checked against `sha256` before it is published,
+/// and a mismatch moves on to the next mirror with a fresh download. Partial
+/// bytes live in `temp_dir` when given, otherwise next to the output path.
+pub async fn robust_download(
+    client: &reqwest::Client,
+    policy: &NetworkPolicy,
+    temp_dir: Option<&Path>,
+    spec: &DownloadSpec,
+    notify: Arc<Notify>,
+    mut on_event: impl FnMut(DownloadEvent) + Send,
+) -> Result<(), DownloadError> {
+    let invalid = |reason: String| DownloadError::InvalidDownloadSpec { reason };
+    if spec.urls.is_empty() {
+        return Err(invalid("at least one URL is required".to_string()));
+    }
+    let max_retries = spec.max_retries.unwrap_or(policy.max_retries);
+    if max_retries > NetworkPolicy::MAX_RETRIES_LIMIT {
+        return Err(invalid(format!(
+            "max retries must be at most {}, got {max_retries}",
+            NetworkPolicy::MAX_RETRIES_LIMIT
+        )));
+    }
+    let connections = spec.connections.unwrap_or(policy.parallel_connections);
+    if !(1..=NetworkPolicy::MAX_PARALLEL_CONNECTIONS).contains(&connections) {
+        return Err(invalid(format!(
+            "connections must be between 1 and {}, got {connections}",
+            NetworkPolicy::MAX_PARALLEL_CONNECTIONS
+        )));
+    }
+    let policy = &NetworkPolicy {
+        parallel_connections: connections,
+        ..*policy
+    };
+    let headers = custom_header_map(&spec.headers)?;
+    let temp_path = temporary_download_path_in(&spec.output_path, temp_dir);
+
+    let mut failed: Option<(&str, DownloadError)> = None;
+    for url in &spec.urls {
+        if let Some((from, error)) = &failed {
+            on_event(DownloadEvent::Mirror {
+                from: from.to_string(),
+                to: url.clone(),
+                reason: error.to_string(),
+            });
+        }
+
+        let single_url = [url.clone()];
+        let request = DownloadRequest {
+            url,
+            headers: &headers,
+            max_retries,
+            resumable_urls: if spec.sha256.is_some() {
+                &spec.urls
+            } else {
+                &single_url
+            },
+            stall_timeout: DEFAULT_STALL_TIMEOUT,
+        };
+        // What the file holds so far, reported as `Complete` once it is
+        // published.
+        let mut bytes = 0;
+        let mut track_bytes = |event: DownloadEvent| {
+            match event {
+                DownloadEvent::Started { resumed_from, .. } => bytes = resumed_from,
+                DownloadEvent::Progress { downloaded, .. } => bytes = downloaded,
+                _ => {}
+            }
+            on_event(event);
+        };
+        let result = match download_with_events(
+            client,
+            policy,
+            request,
+            &temp_path,
+            notify.clone(),
+            &mut track_bytes,
+        )
+        .await
+        {
+            Ok(sha256) => {
+                complete_hashed_download_file(
+                    &temp_path,
+                    &spec.output_path,
+                    spec.sha256.as_deref(),
+                    &sha256,
+                )
+                .await
+            }
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(()) => {
+                on_event(DownloadEvent::Complete {
+                    url: url.clone(),
+                    bytes,
+                });
+                return Ok(());
+            }
+            Err(error) if mirror_can_recover(&error) => failed = Some((url, error)),
+            Err(error) => return Err(error),
+        }
+    }
+
+    Err(failed
+        .map(|(_, error)| error)
+        .expect("at least one mirror was tried"))
+}
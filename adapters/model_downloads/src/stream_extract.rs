@@ -0,0 +1,258 @@
+//! Streams a `.tar.bz2` download straight into the tar unpacker so the
+//! compressed archive is never written to disk.
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use futures_util::StreamExt;
+use tokio::sync::{Notify, mpsc};
+
+use crate::downloads::{DownloadError, DownloadFileOperation, http_status_error};
+use crate::network_stats::TransferCounter;
+
+/// Downloaded chunks buffered between the network and the decoder thread.
+const STREAM_EXTRACT_CHANNEL_CHUNKS: usize = 16;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamExtractProgress {
+    pub downloaded: u64,
+    /// `Content-Length` of the archive, or 0 when the server did not send it.
+    pub total: u64,
+    pub files_extracted: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamExtractSummary {
+    pub bytes_downloaded: u64,
+    pub files_extracted: u64,
+}
+
+/// Directory the archive is unpacked into before its entries are moved into
+/// the target directory, so a failed or cancelled run leaves nothing
+/// half-extracted. `parent` is the temporary directory when one is
+/// configured, otherwise the target directory itself.
+pub fn stream_extract_staging_dir(parent: &Path, url: &str) -> PathBuf {
+    let name = url
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("archive");
+    parent.join(format!(".{name}.extracting"))
+}
+
+/// Blocking reader over the chunks sent by the download task. A closed
+/// channel reads as end of file.
+struct ChunkReader<T> {
+    rx: mpsc::Receiver<T>,
+    chunk: Option<T>,
+    offset: usize,
+}
+
+impl<T: AsRef<[u8]>> Read for ChunkReader<T> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if let Some(chunk) = &self.chunk {
+                let remaining = &chunk.as_ref()[self.offset..];
+                if !remaining.is_empty() {
+                    let len = remaining.len().min(buffer.len());
+                    buffer[..len].copy_from_slice(&remaining[..len]);
+                    self.offset += len;
+                    return Ok(len);
+                }
+            }
+            match self.rx.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = Some(chunk);
+                    self.offset = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+    }
+}
+
+/// Downloads `url` and unpacks it as a `.tar.bz2` into `target_dir` while the
+/// bytes arrive.
+///
+/// Entries are unpacked into [`stream_extract_staging_dir`] and moved into
+/// `target_dir` only once the whole archive has been read; existing entries
+/// with the same names are replaced. Cancellation, network and archive errors
+/// remove the staging directory. Unlike [`crate::download_file`] there are no
+/// retries, because a decoder cannot resume from the middle of a stream.
+pub async fn download_and_extract(
+    client: &reqwest::Client,
+    url: &str,
+    target_dir: &Path,
+    temp_dir: Option<&Path>,
+    notify: Arc<Notify>,
+    mut on_progress: impl FnMut(StreamExtractProgress) + Send,
+) -> Result<StreamExtractSummary, DownloadError> {
+    let staging_dir = stream_extract_staging_dir(temp_dir.unwrap_or(target_dir), url);
+    let extract_error = |reason: String| {
+        DownloadError::file_system_with_target(
+            DownloadFileOperation::ExtractArchive,
+            &staging_dir,
+            target_dir,
+            reason,
+        )
+    };
+    remove_staging_dir(&staging_dir).await;
+    tokio::fs::create_dir_all(&staging_dir)
+        .await
+        .map_err(|error| extract_error(error.to_string()))?;
+
+    let result = async {
+        let response = client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(http_status_error(response).await);
+        }
+        let total = response.content_length().unwrap_or(0);
+
+        let (tx, rx) = mpsc::channel(STREAM_EXTRACT_CHANNEL_CHUNKS);
+        let files_extracted = Arc::new(AtomicU64::new(0));
+        let extractor = {
+            let staging_dir = staging_dir.clone();
+            let files_extracted = files_extracted.clone();
+            tokio::task::spawn_blocking(move || -> std::io::Result<()> {
+                let reader = ChunkReader {
+                    rx,
+                    chunk: None,
+                    offset: 0,
+                };
+                let mut archive = tar::Archive::new(bzip2::read::BzDecoder::new(reader));
+                archive.set_preserve_permissions(false);
+                archive.set_unpack_xattrs(false);
+                for entry in archive.entries()? {
+                    let mut entry = entry?;
+                    entry.unpack_in(&staging_dir)?;
+                    if entry.header().entry_type().is_file() {
+                        files_extracted.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                Ok(())
+            })
+        };
+
+        let mut downloaded = 0_u64;
+        let mut stream = response.bytes_stream();
+        let transfer = TransferCounter::start();
+        let streamed = tokio::select! {
+            _ = notify.notified() => Err(DownloadError::Cancelled),
+            result = async {
+                while let Some(chunk) = stream.next().await {
+                    let chunk = chunk?;
+                    transfer.add(chunk.len());
+                    downloaded += chunk.len() as u64;
+                    // A closed channel means the extractor already failed;
+                    // its error is reported below.
+                    if tx.send(chunk).await.is_err() {
+                        break;
+                    }
+                    on_progress(StreamExtractProgress {
+                        downloaded,
+                        total,
+                        files_extracted: files_extracted.load(Ordering::Relaxed),
+                    });
+                }
+                Ok(())
+            } => result,
+        };
+        // Closing the channel lets the extractor see end of file and finish.
+        drop(tx);
+        let extracted = extractor
+            .await
+            .map_err(|error| extract_error(format!("Failed to join extraction task: {error}")))?;
+        streamed?;
+        extracted.map_err(|error| extract_error(error.to_string()))?;
+
+        let files_extracted = files_extracted.load(Ordering::Relaxed);
+        on_progress(StreamExtractProgress {
+            downloaded,
+            total,
+            files_extracted,
+        });
+        Ok(StreamExtractSummary {
+            bytes_downloaded: downloaded,
+            files_extracted,
+        })
+    }
+    .await;
+
+    let result = match result {
+        Ok(summary) => publish_staged_entries(&staging_dir, target_dir)
+            .await
+            .map(|()| summary),
+        Err(error) => Err(error),
+    };
+    remove_staging_dir(&staging_dir).await;
+    result
+}
+
+async fn publish_staged_entries(
+    staging_dir: &Path,
+    target_dir: &Path,
+) -> Result<(), DownloadError> {
+    let publish_error = |path: &Path, error: std::io::Error| {
+        DownloadError::file_system_with_target(
+            DownloadFileOperation::Publish,
+            path,
+            target_dir,
+            error.to_string(),
+        )
+    };
+    let mut entries = tokio::fs::read_dir(staging_dir)
+        .await
+        .map_err(|error| publish_error(staging_dir, error))?;
+    while let Some(entry) = entries
+        .next_entry()
+        .await
+        .map_err(|error| publish_error(staging_dir, error))?
+    {
+        let destination = target_dir.join(entry.file_name());
+        crate::remove_model_install_path(&destination)?;
+        let source = entry.path();
+        match tokio::fs::rename(&source, &destination).await {
+            // Staging in a temporary directory on another volume.
+            Err(error) if error.kind() == std::io::ErrorKind::CrossesDevices => {
+                let (from, to) = (source.clone(), destination.clone());
+                tokio::task::spawn_blocking(move || copy_tree(&from, &to))
+                    .await
+                    .map_err(std::io::Error::other)
+                    .and_then(|result| result)
+                    .map_err(|error| publish_error(&source, error))?;
+            }
+            result => result.map_err(|error| publish_error(&source, error))?,
+        }
+    }
+    Ok(())
+}
+
+/// Copies a file or directory tree; symlinks are recreated, not followed.
+fn copy_tree(from: &Path, to: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(from)?;
+    if metadata.is_dir() {
+        std::fs::create_dir_all(to)?;
+        for entry in std::fs::read_dir(from)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &to.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    if metadata.file_type().is_symlink() {
+        let link = std::fs::read_link(from)?;
+        #[cfg(unix)]
+        return std::os::unix::fs::symlink(link, to);
+        #[cfg(windows)]
+        return std::os::windows::fs::symlink_file(link, to);
+    }
+    std::fs::copy(from, to).map(|_| ())
+}
+
+async fn remove_staging_dir(staging_dir: &Path) {
+    let _ = tokio::fs::remove_dir_all(staging_dir).await;
+}
@@ -250,6 +250,359 @@ pub fn path_exists(path: &Path) -> Result<bool, FileSystemError> {
         .map(|metadata| metadata.is_some())
 }
 
+/// Files walked between [`directory_size`] progress callbacks.
+pub const DIRECTORY_SIZE_PROGRESS_INTERVAL: u64 = 1_000;
+
+/// Sums the sizes of regular files under `path`.
+///
+/// Symlinks are counted as links and never followed, so link cycles cannot
+/// loop the walk. Entries that cannot be read are skipped instead of failing
+/// the whole scan. `on_progress(files, bytes)` fires every
+/// [`DIRECTORY_SIZE_PROGRESS_INTERVAL`] files.
+pub fn directory_size<F>(path: &Path, mut on_progress: F) -> Result<u64, FileSystemError>
+where
+    F: FnMut(u64, u64),
+{
+    let metadata = std::fs::metadata(path).map_err(|error| {
+        FileSystemError::new(FileSystemOperation::Metadata, path, error.to_string())
+    })?;
+    if !metadata.is_dir() {
+        return Err(FileSystemError::new(
+            FileSystemOperation::ReadDirectory,
+            path,
+            "Path is not a directory",
+        ));
+    }
+
+    let mut files = 0_u64;
+    let mut bytes = 0_u64;
+    for entry in walkdir::WalkDir::new(path)
+        .follow_links(false)
+        .into_iter()
+        .filter_map(Result::ok)
+    {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        files += 1;
+        bytes = bytes.saturating_add(metadata.len());
+        if files.is_multiple_of(DIRECTORY_SIZE_PROGRESS_INTERVAL) {
+            on_progress(files, bytes);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Per-file outcome of [`validate_model_dir`], each list in request order.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelDirValidationReport {
+    pub ok: Vec<String>,
+    pub missing: Vec<String>,
+    pub empty: Vec<String>,
+}
+
+impl ModelDirValidationReport {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.empty.is_empty()
+    }
+}
+
+/// Checks that every `required_files` entry exists under `dir` as a non-empty
+/// regular file, so a broken extraction is caught before the engine loads it.
+///
+/// Entries must be relative paths that stay inside `dir`.
+pub fn validate_model_dir(
+    dir: &Path,
+    required_files: &[String],
+) -> Result<ModelDirValidationReport, FileSystemError> {
+    let mut report = ModelDirValidationReport::default();
+    for required in required_files {
+        let relative = Path::new(required);
+        if !relative
+            .components()
+            .all(|component| matches!(component, std::path::Component::Normal(_)))
+        {
+            return Err(FileSystemError::new(
+                FileSystemOperation::Metadata,
+                relative,
+                "Required model file must be a relative path inside the model directory",
+            ));
+        }
+
+        match fs::metadata(dir.join(relative)) {
+            Ok(metadata) if metadata.is_file() && metadata.len() > 0 => {
+                report.ok.push(required.clone())
+            }
+            Ok(metadata) if metadata.is_file() => report.empty.push(required.clone()),
+            Ok(_) => report.missing.push(required.clone()),
+            Err(error) if error.kind() == ErrorKind::NotFound => {
+                report.missing.push(required.clone())
+            }
+            Err(error) => {
+                return Err(FileSystemError::new(
+                    FileSystemOperation::Metadata,
+                    dir.join(relative),
+                    error.to_string(),
+                ));
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// Bytes written and read back by [`benchmark_disk`].
+pub const DISK_BENCHMARK_BYTES: usize = 8 * 1024 * 1024;
+const DISK_BENCHMARK_CHUNK_BYTES: usize = 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskBenchmark {
+    pub bytes: u64,
+    pub write_mb_per_sec: f64,
+    pub read_mb_per_sec: f64,
+}
+
+/// Times a synced sequential write and a read-back of `size_bytes` in `dir`.
+///
+/// The read usually comes from the OS page cache, so it reports an upper
+/// bound; the write figure includes `fsync` and is the useful one for
+/// diagnosing disk-bound downloads. The probe file is always removed.
+pub fn benchmark_disk(dir: &Path, size_bytes: usize) -> Result<DiskBenchmark, FileSystemError> {
+    use std::io::{Read, Write};
+    use std::time::Instant;
+
+    let probe_path = dir.join(format!(".sona-disk-benchmark-{}.tmp", uuid::Uuid::new_v4()));
+    let write_error = |error: std::io::Error| {
+        FileSystemError::new(
+            FileSystemOperation::WriteFile,
+            &probe_path,
+            error.to_string(),
+        )
+    };
+    let read_error = |error: std::io::Error| {
+        FileSystemError::new(
+            FileSystemOperation::ReadFile,
+            &probe_path,
+            error.to_string(),
+        )
+    };
+
+    let result = (|| {
+        let chunk = vec![0_u8; DISK_BENCHMARK_CHUNK_BYTES.min(size_bytes.max(1))];
+
+        let started = Instant::now();
+        let mut file = fs::File::create(&probe_path).map_err(write_error)?;
+        let mut written = 0;
+        while written < size_bytes {
+            let len = chunk.len().min(size_bytes - written);
+            file.write_all(&chunk[..len]).map_err(write_error)?;
+            written += len;
+        }
+        file.sync_all().map_err(write_error)?;
+        drop(file);
+        let write_elapsed = started.elapsed();
+
+        let started = Instant::now();
+        let mut file = fs::File::open(&probe_path).map_err(read_error)?;
+        let mut buffer = vec![0_u8; chunk.len()];
+        let mut read_total = 0;
+        loop {
+            let read = file.read(&mut buffer).map_err(read_error)?;
+            if read == 0 {
+                break;
+            }
+            read_total += read;
+        }
+        let read_elapsed = started.elapsed();
+
+        Ok(DiskBenchmark {
+            bytes: written as u64,
+            write_mb_per_sec: megabytes_per_second(written, write_elapsed),
+            read_mb_per_sec: megabytes_per_second(read_total, read_elapsed),
+        })
+    })();
+
+    let _ = fs::remove_file(&probe_path);
+    result
+}
+
+fn megabytes_per_second(bytes: usize, elapsed: Duration) -> f64 {
+    let seconds = elapsed.as_secs_f64().max(f64::EPSILON);
+    bytes as f64 / (1024.0 * 1024.0) / seconds
+}
+
+const DIRECTORY_COPY_CHUNK_BYTES: usize = 8 * 1024 * 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryCopyProgress {
+    pub files_done: u64,
+    pub files_total: u64,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryCopySummary {
+    pub files: u64,
+    pub bytes: u64,
+    /// Files already present at the destination with the right size, left
+    /// behind by an interrupted earlier run.
+    pub files_reused: u64,
+}
+
+/// Copies every regular file under `from` into `to` and checks each copy's
+/// size. `from` is left untouched so the caller can switch over to `to`
+/// before removing it.
+///
+/// Destination files whose size already matches the source are kept, so a
+/// run that was interrupted can be repeated and only copies what is left.
+/// Symlinks are not followed or copied. `on_progress` fires after each copied
+/// chunk and each reused file.
+pub fn copy_directory_verified<F>(
+    from: &Path,
+    to: &Path,
+    mut on_progress: F,
+) -> Result<DirectoryCopySummary, FileSystemError>
+where
+    F: FnMut(DirectoryCopyProgress),
+{
+    use std::io::{Read, Write};
+
+    let source_root = fs::canonicalize(from).map_err(|error| {
+        FileSystemError::new(FileSystemOperation::Metadata, from, error.to_string())
+    })?;
+    if !source_root.is_dir() {
+        return Err(FileSystemError::new(
+            FileSystemOperation::ReadDirectory,
+            from,
+            "Path is not a directory",
+        ));
+    }
+    let target_existed = to.exists();
+    fs::create_dir_all(to).map_err(|error| {
+        FileSystemError::new(FileSystemOperation::CreateDirectory, to, error.to_string())
+    })?;
+    let target_root = fs::canonicalize(to).map_err(|error| {
+        FileSystemError::new(FileSystemOperation::Metadata, to, error.to_string())
+    })?;
+    if target_root.starts_with(&source_root) || source_root.starts_with(&target_root) {
+        if !target_existed {
+            let _ = fs::remove_dir(&target_root);
+        }
+        return Err(FileSystemError::new(
+            FileSystemOperation::Copy,
+            to,
+            "Destination must not overlap the source directory",
+        ));
+    }
+
+    let mut files = Vec::new();
+    for entry in walkdir::WalkDir::new(&source_root).follow_links(false) {
+        let entry = entry.map_err(|error| {
+            FileSystemError::new(
+                FileSystemOperation::ReadDirectory,
+                error.path().unwrap_or(&source_root),
+                error.to_string(),
+            )
+        })?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let size = entry
+            .metadata()
+            .map_err(|error| {
+                FileSystemError::new(
+                    FileSystemOperation::Metadata,
+                    entry.path(),
+                    error.to_string(),
+                )
+            })?
+            .len();
+        let relative = entry
+            .path()
+            .strip_prefix(&source_root)
+            .unwrap_or(entry.path())
+            .to_path_buf();
+        files.push((relative, size));
+    }
+
+    let mut progress = DirectoryCopyProgress {
+        files_total: files.len() as u64,
+        bytes_total: files.iter().map(|(_, size)| size).sum(),
+        ..DirectoryCopyProgress::default()
+    };
+    let mut summary = DirectoryCopySummary::default();
+    let mut buffer = vec![0_u8; DIRECTORY_COPY_CHUNK_BYTES];
+
+    for (relative, size) in &files {
+        let source = source_root.join(relative);
+        let target = target_root.join(relative);
+        if fs::metadata(&target).is_ok_and(|metadata| metadata.is_file() && metadata.len() == *size)
+        {
+            summary.files_reused += 1;
+            progress.files_done += 1;
+            progress.bytes_done += size;
+            on_progress(progress);
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).map_err(|error| {
+                FileSystemError::new(
+                    FileSystemOperation::CreateDirectory,
+                    parent,
+                    error.to_string(),
+                )
+            })?;
+        }
+        let copy_error = |error: std::io::Error| {
+            FileSystemError::new(FileSystemOperation::Copy, &source, error.to_string())
+        };
+        let mut reader = fs::File::open(&source).map_err(copy_error)?;
+        let mut writer = fs::File::create(&target).map_err(copy_error)?;
+        loop {
+            let read = reader.read(&mut buffer).map_err(copy_error)?;
+            if read == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..read]).map_err(copy_error)?;
+            progress.bytes_done += read as u64;
+            on_progress(progress);
+        }
+        writer.sync_all().map_err(copy_error)?;
+        progress.files_done += 1;
+        on_progress(progress);
+    }
+
+    for (relative, size) in &files {
+        let target = target_root.join(relative);
+        let copied = fs::metadata(&target)
+            .map_err(|error| {
+                FileSystemError::new(FileSystemOperation::Metadata, &target, error.to_string())
+            })?
+            .len();
+        if copied != *size {
+            return Err(FileSystemError::new(
+                FileSystemOperation::Copy,
+                &target,
+                format!("Copied {copied} bytes but the source has {size}"),
+            ));
+        }
+        summary.files += 1;
+        summary.bytes += size;
+    }
+
+    Ok(summary)
+}
+
 pub fn write_transcript_output_file(path: &Path, output: &str) -> Result<(), FileSystemError> {
     RealFileSystem.write_file(path, output.as_bytes())
 }
@@ -865,11 +1218,7 @@ fn installed_model_ids_for_models_dir(models_dir: &Path) -> HashSet<String> {
 
 fn is_preset_model_install_path_complete(model: &PresetModel, install_path: &Path) -> bool {
     match install_path.metadata() {
-        Ok(metadata) => model.install_path_is_complete(
-            true,
-            metadata.is_file(),
-            metadata.len(),
-        ),
+        Ok(metadata) => model.install_path_is_complete(true, metadata.is_file(), metadata.len()),
         Err(_) => model.install_path_is_complete(false, false, 0),
     }
 }
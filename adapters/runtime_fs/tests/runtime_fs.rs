@@ -23,14 +23,14 @@ use sona_core::transcription::runtime::{
 use sona_runtime_fs::{
     FsDiagnosticsEnrichmentRepository, FsSourcePathStatusProvider, NativeAutomationFileSystem,
     RealFileSystem, RuntimeBatchTranscribePlanResolver, RuntimeFsError,
-    RuntimeModelCatalogProvider, SystemClock, UuidGenerator, build_diagnostics_snapshot,
-    collect_automation_runtime_candidate_paths, ensure_directory_exists,
-    is_preset_model_installed_at, load_legacy_settings_app_config, load_transcribe_config_file,
-    load_transcribe_live_config_file, path_exists, plan_batch_output_files, remove_path_if_exists,
-    resolve_batch_input_source, resolve_live_transcribe_plan_with_runtime_paths,
-    resolve_runtime_path_status, select_desktop_models_dir_from_app_roots,
-    validate_native_automation_rule_activation, write_cli_config_template_file,
-    write_json_pretty_atomic, write_transcript_output_file,
+    RuntimeModelCatalogProvider, SystemClock, UuidGenerator, benchmark_disk,
+    build_diagnostics_snapshot, collect_automation_runtime_candidate_paths,
+    copy_directory_verified, directory_size, ensure_directory_exists, is_preset_model_installed_at,
+    load_legacy_settings_app_config, load_transcribe_config_file, load_transcribe_live_config_file,
+    path_exists, plan_batch_output_files, remove_path_if_exists, resolve_batch_input_source,
+    resolve_live_transcribe_plan_with_runtime_paths, resolve_runtime_path_status,
+    select_desktop_models_dir_from_app_roots, validate_native_automation_rule_activation,
+    write_cli_config_template_file, write_json_pretty_atomic, write_transcript_output_file,
 };
 use uuid::{Uuid, Version};
 
@@ -791,3 +791,147 @@ fn real_file_system_remove_path_if_exists_handles_files_and_missing_paths() {
 
     remove_path_if_exists(&path).unwrap();
 }
+
+#[test]
+fn directory_size_sums_nested_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("model-a").join("onnx");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(dir.path().join("tokens.txt"), vec![0_u8; 10]).unwrap();
+    std::fs::write(nested.join("model.onnx"), vec![0_u8; 1_500]).unwrap();
+
+    let mut progress_calls = 0;
+    let bytes = directory_size(dir.path(), |_, _| progress_calls += 1).unwrap();
+
+    assert_eq!(bytes, 1_510);
+    assert_eq!(progress_calls, 0);
+}
+
+#[cfg(unix)]
+#[test]
+fn directory_size_does_not_follow_symlink_loops() {
+    let dir = tempfile::tempdir().unwrap();
+    let nested = dir.path().join("nested");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(nested.join("model.onnx"), vec![0_u8; 64]).unwrap();
+    std::os::unix::fs::symlink(dir.path(), nested.join("loop")).unwrap();
+
+    assert_eq!(directory_size(dir.path(), |_, _| {}).unwrap(), 64);
+}
+
+#[test]
+fn directory_size_rejects_missing_and_non_directory_paths() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing");
+    let file = dir.path().join("file.bin");
+    std::fs::write(&file, b"x").unwrap();
+
+    let error = directory_size(&missing, |_, _| {}).unwrap_err();
+    assert_eq!(error.operation, FileSystemOperation::Metadata);
+    assert_eq!(error.path, missing);
+
+    let error = directory_size(&file, |_, _| {}).unwrap_err();
+    assert_eq!(error.operation, FileSystemOperation::ReadDirectory);
+    assert_eq!(error.path, file);
+}
+
+#[test]
+fn benchmark_disk_reports_throughput_and_removes_probe_file() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let result = benchmark_disk(dir.path(), 256 * 1024).unwrap();
+
+    assert_eq!(result.bytes, 256 * 1024);
+    assert!(result.write_mb_per_sec > 0.0);
+    assert!(result.read_mb_per_sec > 0.0);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn benchmark_disk_reports_write_context_for_missing_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("missing");
+
+    let error = benchmark_disk(&missing, 1024).unwrap_err();
+
+    assert_eq!(error.operation, FileSystemOperation::WriteFile);
+    assert!(error.path.starts_with(&missing));
+}
+
+#[test]
+fn validate_model_dir_reports_ok_missing_and_empty_files() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir_all(dir.path().join("nested")).unwrap();
+    std::fs::create_dir_all(dir.path().join("tokens-dir")).unwrap();
+    std::fs::write(dir.path().join("model.onnx"), b"weights").unwrap();
+    std::fs::write(dir.path().join("nested").join("config.json"), b"").unwrap();
+
+    let required = [
+        "model.onnx",
+        "nested/config.json",
+        "tokens.txt",
+        "tokens-dir",
+    ]
+    .map(str::to_string);
+    let report = sona_runtime_fs::validate_model_dir(dir.path(), &required).unwrap();
+
+    assert_eq!(report.ok, vec!["model.onnx"]);
+    assert_eq!(report.empty, vec!["nested/config.json"]);
+    assert_eq!(report.missing, vec!["tokens.txt", "tokens-dir"]);
+    assert!(!report.is_complete());
+}
+
+#[test]
+fn validate_model_dir_rejects_paths_outside_the_directory() {
+    let dir = tempfile::tempdir().unwrap();
+
+    let error = sona_runtime_fs::validate_model_dir(dir.path(), &["../model.onnx".to_string()])
+        .unwrap_err();
+
+    assert_eq!(error.operation, FileSystemOperation::Metadata);
+    assert!(error.reason.contains("inside the model directory"));
+}
+
+#[test]
+fn copy_directory_verified_copies_files_and_reuses_completed_copies() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("models");
+    let to = dir.path().join("other-drive").join("models");
+    std::fs::create_dir_all(from.join("whisper")).unwrap();
+    std::fs::write(from.join("silero_vad.onnx"), b"vad").unwrap();
+    std::fs::write(from.join("whisper").join("model.onnx"), b"encoder").unwrap();
+    // Left behind by an interrupted run: one complete copy, one truncated.
+    std::fs::create_dir_all(to.join("whisper")).unwrap();
+    std::fs::write(to.join("silero_vad.onnx"), b"vad").unwrap();
+    std::fs::write(to.join("whisper").join("model.onnx"), b"enc").unwrap();
+    let mut last_progress = None;
+
+    let summary =
+        copy_directory_verified(&from, &to, |progress| last_progress = Some(progress)).unwrap();
+
+    assert_eq!(summary.files, 2);
+    assert_eq!(summary.bytes, 10);
+    assert_eq!(summary.files_reused, 1);
+    let last_progress = last_progress.unwrap();
+    assert_eq!(last_progress.files_done, 2);
+    assert_eq!(last_progress.bytes_done, last_progress.bytes_total);
+    assert_eq!(
+        std::fs::read(to.join("whisper").join("model.onnx")).unwrap(),
+        b"encoder"
+    );
+    assert!(from.join("whisper").join("model.onnx").exists());
+}
+
+#[test]
+fn copy_directory_verified_refuses_nested_destination() {
+    let dir = tempfile::tempdir().unwrap();
+    let from = dir.path().join("models");
+    std::fs::create_dir_all(&from).unwrap();
+    std::fs::write(from.join("model.onnx"), b"model").unwrap();
+
+    let error = copy_directory_verified(&from, &from.join("moved"), |_| {}).unwrap_err();
+
+    assert_eq!(error.operation, FileSystemOperation::Copy);
+    assert!(from.join("model.onnx").exists());
+    assert!(!from.join("moved").exists());
+}
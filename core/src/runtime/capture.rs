@@ -0,0 +1,855 @@
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+
+use super::error::RuntimeValidationError;
+
+pub const DEFAULT_RECORD_CODEC: &str = "pcm";
+pub const RECORD_CODEC_VALUES: &[&str] = &["pcm", "opus", "aac"];
+
+/// Container/codec used for the recording file written alongside a live
+/// capture. The live PCM stream fed to transcription is unaffected.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RecordCodec {
+    #[default]
+    Pcm,
+    Opus,
+    Aac,
+}
+
+impl RecordCodec {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pcm => "pcm",
+            Self::Opus => "opus",
+            Self::Aac => "aac",
+        }
+    }
+
+    /// FFmpeg encoder required to produce this codec, or `None` when the
+    /// recording stays as the uncompressed WAV written by the capture worker.
+    pub fn ffmpeg_encoder(self) -> Option<&'static str> {
+        match self {
+            Self::Pcm => None,
+            Self::Opus => Some("libopus"),
+            Self::Aac => Some("aac"),
+        }
+    }
+
+    pub fn file_extension(self) -> &'static str {
+        match self {
+            Self::Pcm => "wav",
+            Self::Opus => "ogg",
+            Self::Aac => "m4a",
+        }
+    }
+
+    pub fn encoded_output_path(self, recording_path: &Path) -> PathBuf {
+        recording_path.with_extension(self.file_extension())
+    }
+}
+
+pub fn resolve_record_codec(value: Option<String>) -> Result<RecordCodec, RuntimeValidationError> {
+    let value = value.unwrap_or_else(|| DEFAULT_RECORD_CODEC.to_string());
+
+    match value.trim().to_ascii_lowercase().as_str() {
+        "pcm" | "wav" => Ok(RecordCodec::Pcm),
+        "opus" => Ok(RecordCodec::Opus),
+        "aac" => Ok(RecordCodec::Aac),
+        _ => Err(RuntimeValidationError::new(
+            "record_codec",
+            format!(
+                "record_codec must be one of {}.",
+                RECORD_CODEC_VALUES.join(", ")
+            ),
+        )),
+    }
+}
+
+pub const DEFAULT_CAPTURE_PIPE_FORMAT: &str = "pcm";
+pub const CAPTURE_PIPE_FORMAT_VALUES: &[&str] = &["pcm", "wav"];
+
+/// What a capture pipe carries: bare 16-bit little-endian samples, or the
+/// same samples behind a WAV header so readers need no out-of-band format.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CapturePipeFormat {
+    #[default]
+    RawPcm,
+    Wav,
+}
+
+impl CapturePipeFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::RawPcm => "pcm",
+            Self::Wav => "wav",
+        }
+    }
+
+    /// Bytes each new reader receives before any samples.
+    pub fn stream_header(self, config: CaptureAudioConfig) -> Vec<u8> {
+        match self {
+            Self::RawPcm => Vec::new(),
+            Self::Wav => streaming_wav_header(config).to_vec(),
+        }
+    }
+}
+
+pub fn resolve_capture_pipe_format(
+    value: Option<String>,
+) -> Result<CapturePipeFormat, RuntimeValidationError> {
+    let value = value.unwrap_or_else(|| DEFAULT_CAPTURE_PIPE_FORMAT.to_string());
+
+    match value.trim().to_ascii_lowercase().as_str() {
+        "pcm" | "s16le" => Ok(CapturePipeFormat::RawPcm),
+        "wav" => Ok(CapturePipeFormat::Wav),
+        _ => Err(RuntimeValidationError::new(
+            "pipe_format",
+            format!(
+                "pipe_format must be one of {}.",
+                CAPTURE_PIPE_FORMAT_VALUES.join(", ")
+            ),
+        )),
+    }
+}
+
+pub const CAPTURE_SAMPLE_FORMAT_VALUES: &[&str] = &["s16le", "f32le"];
+/// Rates a capture pipe can be resampled to.
+pub const CAPTURE_PIPE_SAMPLE_RATES: &[u32] =
+    &[8_000, 16_000, 22_050, 24_000, 32_000, 44_100, 48_000];
+pub const MAX_CAPTURE_PIPE_CHANNELS: u16 = 2;
+
+/// Little-endian sample encoding of a capture pipe.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaptureSampleFormat {
+    #[default]
+    S16Le,
+    F32Le,
+}
+
+impl CaptureSampleFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::S16Le => "s16le",
+            Self::F32Le => "f32le",
+        }
+    }
+
+    pub fn bytes_per_sample(self) -> u16 {
+        match self {
+            Self::S16Le => 2,
+            Self::F32Le => 4,
+        }
+    }
+
+    /// `wFormatTag` of the WAV `fmt ` chunk: integer PCM or IEEE float.
+    fn wav_format_tag(self) -> u16 {
+        match self {
+            Self::S16Le => 1,
+            Self::F32Le => 3,
+        }
+    }
+}
+
+/// Layout of the audio a capture pipe delivers. Captures run at 16 kHz mono
+/// internally, the format speech models take; other layouts are resampled
+/// and upmixed from that stream, so they carry nothing above 8 kHz.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CaptureAudioConfig {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub sample_format: CaptureSampleFormat,
+}
+
+impl Default for CaptureAudioConfig {
+    fn default() -> Self {
+        Self {
+            sample_rate: 16_000,
+            channels: 1,
+            sample_format: CaptureSampleFormat::S16Le,
+        }
+    }
+}
+
+impl CaptureAudioConfig {
+    pub fn bytes_per_frame(self) -> u16 {
+        self.channels * self.sample_format.bytes_per_sample()
+    }
+}
+
+/// Validates the requested pipe layout; each value left out keeps the
+/// 16 kHz mono s16le default.
+pub fn resolve_capture_audio_config(
+    sample_rate: Option<u32>,
+    channels: Option<u16>,
+    sample_format: Option<String>,
+) -> Result<CaptureAudioConfig, RuntimeValidationError> {
+    let default = CaptureAudioConfig::default();
+
+    let sample_rate = sample_rate.unwrap_or(default.sample_rate);
+    if !CAPTURE_PIPE_SAMPLE_RATES.contains(&sample_rate) {
+        let rates: Vec<String> = CAPTURE_PIPE_SAMPLE_RATES
+            .iter()
+            .map(u32::to_string)
+            .collect();
+        return Err(RuntimeValidationError::new(
+            "pipe_sample_rate",
+            format!(
+                "pipe_sample_rate must be one of {}, got {sample_rate}.",
+                rates.join(", ")
+            ),
+        ));
+    }
+
+    let channels = channels.unwrap_or(default.channels);
+    if !(1..=MAX_CAPTURE_PIPE_CHANNELS).contains(&channels) {
+        return Err(RuntimeValidationError::new(
+            "pipe_channels",
+            format!(
+                "pipe_channels must be between 1 and {MAX_CAPTURE_PIPE_CHANNELS}, got {channels}."
+            ),
+        ));
+    }
+
+    let sample_format = match sample_format
+        .as_deref()
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("s16le") => CaptureSampleFormat::S16Le,
+        Some("f32le") => CaptureSampleFormat::F32Le,
+        Some(_) => {
+            return Err(RuntimeValidationError::new(
+                "pipe_sample_format",
+                format!(
+                    "pipe_sample_format must be one of {}.",
+                    CAPTURE_SAMPLE_FORMAT_VALUES.join(", ")
+                ),
+            ));
+        }
+    };
+
+    Ok(CaptureAudioConfig {
+        sample_rate,
+        channels,
+        sample_format,
+    })
+}
+
+/// Header of a WAV stream in `config` whose length is not known up front.
+/// The RIFF and data sizes are left at `u32::MAX`, as FFmpeg does for
+/// unseekable output, so readers take everything after it as samples.
+pub fn streaming_wav_header(config: CaptureAudioConfig) -> [u8; 44] {
+    let bits_per_sample = config.sample_format.bytes_per_sample() * 8;
+    let block_align = config.bytes_per_frame();
+    let byte_rate = config.sample_rate * u32::from(block_align);
+
+    let mut header = [0; 44];
+    header[0..4].copy_from_slice(b"RIFF");
+    header[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+    header[8..12].copy_from_slice(b"WAVE");
+    header[12..16].copy_from_slice(b"fmt ");
+    header[16..20].copy_from_slice(&16_u32.to_le_bytes());
+    header[20..22].copy_from_slice(&config.sample_format.wav_format_tag().to_le_bytes());
+    header[22..24].copy_from_slice(&config.channels.to_le_bytes());
+    header[24..28].copy_from_slice(&config.sample_rate.to_le_bytes());
+    header[28..32].copy_from_slice(&byte_rate.to_le_bytes());
+    header[32..34].copy_from_slice(&block_align.to_le_bytes());
+    header[34..36].copy_from_slice(&bits_per_sample.to_le_bytes());
+    header[36..40].copy_from_slice(b"data");
+    header[40..44].copy_from_slice(&u32::MAX.to_le_bytes());
+    header
+}
+
+/// Default number of 16 kHz mono frames delivered per capture chunk (64 ms).
+pub const DEFAULT_CAPTURE_CHUNK_FRAMES: usize = 1024;
+pub const MIN_CAPTURE_CHUNK_FRAMES: usize = 128;
+pub const MAX_CAPTURE_CHUNK_FRAMES: usize = 16_000;
+
+/// Resolves the capture chunk size, in output frames.
+///
+/// The chunk size sets how often captured audio is handed to transcription
+/// and level meters: smaller chunks lower latency (128 frames is 8 ms) at the
+/// cost of more wakeups and events per second, larger chunks cut that
+/// overhead but delay each delivery by up to one second at the upper bound.
+pub fn resolve_capture_chunk_frames(value: Option<u32>) -> Result<usize, RuntimeValidationError> {
+    let Some(value) = value else {
+        return Ok(DEFAULT_CAPTURE_CHUNK_FRAMES);
+    };
+    let frames = value as usize;
+    if !(MIN_CAPTURE_CHUNK_FRAMES..=MAX_CAPTURE_CHUNK_FRAMES).contains(&frames) {
+        return Err(RuntimeValidationError::new(
+            "chunk_frames",
+            format!(
+                "chunk_frames must be between {MIN_CAPTURE_CHUNK_FRAMES} and {MAX_CAPTURE_CHUNK_FRAMES}."
+            ),
+        ));
+    }
+    Ok(frames)
+}
+
+/// Validates a requested 1-based input channel (as labelled on audio
+/// interfaces) against the device's channel count and returns its 0-based
+/// index. `None` keeps the default downmix of every channel to mono.
+pub fn resolve_capture_input_channel(
+    channel: Option<u16>,
+    device_channels: u16,
+) -> Result<Option<usize>, RuntimeValidationError> {
+    let Some(channel) = channel else {
+        return Ok(None);
+    };
+    if channel == 0 || channel > device_channels {
+        return Err(RuntimeValidationError::new(
+            "input_channel",
+            format!(
+                "input_channel must be between 1 and {device_channels} for this device, got {channel}."
+            ),
+        ));
+    }
+    Ok(Some(usize::from(channel - 1)))
+}
+
+pub const DEFAULT_AGC_TARGET_DBFS: f32 = -20.0;
+pub const MIN_AGC_TARGET_DBFS: f32 = -40.0;
+pub const MAX_AGC_TARGET_DBFS: f32 = -3.0;
+
+/// Returns the AGC target level when automatic gain control is enabled.
+/// `target_dbfs` is the desired RMS level and is ignored while AGC is off.
+pub fn resolve_capture_agc(
+    enabled: Option<bool>,
+    target_dbfs: Option<f32>,
+) -> Result<Option<f32>, RuntimeValidationError> {
+    if !enabled.unwrap_or(false) {
+        return Ok(None);
+    }
+    let target_dbfs = target_dbfs.unwrap_or(DEFAULT_AGC_TARGET_DBFS);
+    if !(MIN_AGC_TARGET_DBFS..=MAX_AGC_TARGET_DBFS).contains(&target_dbfs) {
+        return Err(RuntimeValidationError::new(
+            "agc_target_dbfs",
+            format!(
+                "agc_target_dbfs must be between {MIN_AGC_TARGET_DBFS} and {MAX_AGC_TARGET_DBFS}, got {target_dbfs}."
+            ),
+        ));
+    }
+    Ok(Some(target_dbfs))
+}
+
+/// Blocks quieter than this RMS are treated as silence and leave the gain
+/// unchanged, so pauses in speech do not get amplified into hiss.
+const AGC_SILENCE_RMS: f32 = 1e-3;
+const AGC_MIN_GAIN: f32 = 0.1;
+const AGC_MAX_GAIN: f32 = 10.0;
+/// Fraction of the way the gain moves toward its target per block. Gain drops
+/// quickly on loud input to avoid clipping and recovers slowly afterwards.
+const AGC_ATTACK: f32 = 0.5;
+const AGC_RELEASE: f32 = 0.05;
+
+/// Block-based automatic gain control that steers mono chunks toward a
+/// target RMS level.
+#[derive(Clone, Debug)]
+pub struct AutomaticGainControl {
+    target_rms: f32,
+    gain: f32,
+}
+
+impl AutomaticGainControl {
+    pub fn new(target_dbfs: f32) -> Self {
+        Self {
+            target_rms: 10_f32.powf(target_dbfs / 20.0),
+            gain: 1.0,
+        }
+    }
+
+    pub fn gain(&self) -> f32 {
+        self.gain
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() {
+            return;
+        }
+        let rms = (samples.iter().map(|sample| sample * sample).sum::<f32>()
+            / samples.len() as f32)
+            .sqrt();
+        if rms > AGC_SILENCE_RMS {
+            let desired = (self.target_rms / rms).clamp(AGC_MIN_GAIN, AGC_MAX_GAIN);
+            let rate = if desired < self.gain {
+                AGC_ATTACK
+            } else {
+                AGC_RELEASE
+            };
+            self.gain += (desired - self.gain) * rate;
+        }
+        for sample in samples {
+            *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// Sample rate of the mono stream kept in a [`CaptureRing`].
+pub const CAPTURE_RING_SAMPLE_RATE: u32 = 16_000;
+pub const MAX_CAPTURE_RING_SECONDS: u32 = 600;
+
+/// Returns the capacity, in samples, of the rolling "last N seconds" buffer.
+/// `None` or zero leaves the buffer disabled.
+pub fn resolve_capture_ring_seconds(
+    value: Option<u32>,
+) -> Result<Option<usize>, RuntimeValidationError> {
+    let Some(seconds) = value.filter(|seconds| *seconds > 0) else {
+        return Ok(None);
+    };
+    if seconds > MAX_CAPTURE_RING_SECONDS {
+        return Err(RuntimeValidationError::new(
+            "capture_ring_seconds",
+            format!(
+                "capture_ring_seconds must be at most {MAX_CAPTURE_RING_SECONDS}, got {seconds}."
+            ),
+        ));
+    }
+    Ok(Some(seconds as usize * CAPTURE_RING_SAMPLE_RATE as usize))
+}
+
+/// Fixed-capacity buffer that keeps only the most recently captured samples,
+/// so the last few seconds can be saved without recording to disk.
+#[derive(Clone, Debug)]
+pub struct CaptureRing {
+    samples: VecDeque<f32>,
+    capacity: usize,
+}
+
+impl CaptureRing {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn push(&mut self, chunk: &[f32]) {
+        let chunk = &chunk[chunk.len().saturating_sub(self.capacity)..];
+        let overflow = (self.samples.len() + chunk.len()).saturating_sub(self.capacity);
+        self.samples.drain(..overflow);
+        self.samples.extend(chunk);
+    }
+
+    /// Copies the buffered samples out, oldest first.
+    pub fn to_vec(&self) -> Vec<f32> {
+        self.samples.iter().copied().collect()
+    }
+}
+
+/// Length of the windows [`detect_tone_onset`] compares against the noise floor.
+const TONE_ONSET_WINDOW_MS: u32 = 5;
+/// How far above the noise floor a window's RMS must rise to count as the tone.
+const TONE_ONSET_NOISE_RATIO: f32 = 4.0;
+/// Quietest RMS that counts as the tone, so a silent floor does not make
+/// every click an onset.
+const TONE_ONSET_MIN_RMS: f32 = 0.005;
+
+fn rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    (samples.iter().map(|sample| sample * sample).sum::<f32>() / samples.len() as f32).sqrt()
+}
+
+/// Finds where a calibration tone starts in mono `samples`. The first
+/// `noise_frames` samples were captured before the tone played and set the
+/// noise floor; the onset is the first sample past them, inside the first
+/// window loud enough above that floor, that crosses the threshold.
+pub fn detect_tone_onset(samples: &[f32], sample_rate: u32, noise_frames: usize) -> Option<usize> {
+    let noise_frames = noise_frames.min(samples.len());
+    let threshold =
+        (rms(&samples[..noise_frames]) * TONE_ONSET_NOISE_RATIO).max(TONE_ONSET_MIN_RMS);
+    let window = (sample_rate * TONE_ONSET_WINDOW_MS / 1000).max(1) as usize;
+    samples[noise_frames..]
+        .chunks(window)
+        .enumerate()
+        .find(|(_, chunk)| rms(chunk) >= threshold)
+        .map(|(index, chunk)| {
+            let offset = chunk
+                .iter()
+                .position(|sample| sample.abs() >= threshold)
+                .unwrap_or(0);
+            noise_frames + index * window + offset
+        })
+}
+
+/// Parses the encoder table printed by `ffmpeg -hide_banner -encoders`.
+///
+/// Rows follow a ` ------` separator and start with a six-character flag
+/// column followed by the encoder name.
+pub fn parse_ffmpeg_encoder_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("------"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let _flags = columns.next()?;
+            columns.next().map(str::to_string)
+        })
+        .collect()
+}
+
+/// Record codecs the FFmpeg build can produce, given its encoder names.
+/// PCM is always available since it never goes through FFmpeg.
+pub fn supported_record_codecs(encoders: &[String]) -> Vec<RecordCodec> {
+    [RecordCodec::Pcm, RecordCodec::Opus, RecordCodec::Aac]
+        .into_iter()
+        .filter(|codec| {
+            codec
+                .ffmpeg_encoder()
+                .is_none_or(|encoder| encoders.iter().any(|available| available == encoder))
+        })
+        .collect()
+}
+
+/// Stderr lines kept for the error message of a failed FFmpeg run.
+pub const FFMPEG_STDERR_TAIL_LINES: usize = 20;
+/// Stderr lines forwarded to the log per FFmpeg run before the rest are only
+/// counted.
+pub const FFMPEG_STDERR_LOG_LINE_LIMIT: usize = 100;
+/// Longer stderr lines are truncated before being buffered.
+pub const FFMPEG_STDERR_MAX_LINE_CHARS: usize = 512;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FfmpegStderrLevel {
+    Warn,
+    Debug,
+}
+
+/// Routine FFmpeg chatter (stream info, configuration) is demoted to debug;
+/// only lines that look like problems surface as warnings.
+pub fn classify_ffmpeg_stderr_line(line: &str) -> FfmpegStderrLevel {
+    let lowered = line.to_ascii_lowercase();
+    if [
+        "error",
+        "warning",
+        "invalid",
+        "failed",
+        "could not",
+        "unable",
+    ]
+    .iter()
+    .any(|marker| lowered.contains(marker))
+    {
+        FfmpegStderrLevel::Warn
+    } else {
+        FfmpegStderrLevel::Debug
+    }
+}
+
+/// Bounded view of one FFmpeg process's stderr: a fixed-size ring of the most
+/// recent lines for error reporting plus a per-run logging budget, so a
+/// looping error cannot grow memory or flood the log.
+#[derive(Debug)]
+pub struct FfmpegStderrTail {
+    lines: VecDeque<String>,
+    capacity: usize,
+    log_limit: usize,
+    logged: usize,
+    suppressed: usize,
+}
+
+impl Default for FfmpegStderrTail {
+    fn default() -> Self {
+        Self::new(FFMPEG_STDERR_TAIL_LINES, FFMPEG_STDERR_LOG_LINE_LIMIT)
+    }
+}
+
+impl FfmpegStderrTail {
+    pub fn new(capacity: usize, log_limit: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(capacity),
+            capacity,
+            log_limit,
+            logged: 0,
+            suppressed: 0,
+        }
+    }
+
+    /// Buffers `line` and returns the level it should be logged at, or `None`
+    /// once the logging budget is spent. Blank lines are ignored.
+    pub fn push(&mut self, line: &str) -> Option<(FfmpegStderrLevel, String)> {
+        let line = line.trim_end();
+        if line.trim().is_empty() {
+            return None;
+        }
+        let line = match line.char_indices().nth(FFMPEG_STDERR_MAX_LINE_CHARS) {
+            Some((end, _)) => format!("{}…", &line[..end]),
+            None => line.to_string(),
+        };
+
+        if self.capacity > 0 {
+            if self.lines.len() == self.capacity {
+                self.lines.pop_front();
+            }
+            self.lines.push_back(line.clone());
+        }
+
+        if self.logged >= self.log_limit {
+            self.suppressed += 1;
+            return None;
+        }
+        self.logged += 1;
+        Some((classify_ffmpeg_stderr_line(&line), line))
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.lines.iter().map(String::as_str)
+    }
+
+    /// Number of lines buffered but not forwarded to the log.
+    pub fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    /// Most recent lines joined for an error message.
+    pub fn summary(&self) -> String {
+        self.lines
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+pub const DEFAULT_SILENCE_THRESHOLD_DB: f64 = -50.0;
+pub const MIN_SILENCE_THRESHOLD_DB: f64 = -90.0;
+pub const DEFAULT_MIN_SILENCE_MS: u32 = 500;
+pub const MAX_MIN_SILENCE_MS: u32 = 60_000;
+
+/// Builds the FFmpeg filter that trims leading and trailing silence quieter
+/// than `threshold_db` and at least `min_silence_ms` long. Trailing silence
+/// is trimmed on the reversed stream so pauses in the middle are kept.
+pub fn silence_trim_filter(
+    threshold_db: Option<f64>,
+    min_silence_ms: Option<u32>,
+) -> Result<String, RuntimeValidationError> {
+    let threshold_db = threshold_db.unwrap_or(DEFAULT_SILENCE_THRESHOLD_DB);
+    if !(MIN_SILENCE_THRESHOLD_DB..=0.0).contains(&threshold_db) {
+        return Err(RuntimeValidationError::new(
+            "threshold_db",
+            format!(
+                "threshold_db must be between {MIN_SILENCE_THRESHOLD_DB} and 0, got {threshold_db}."
+            ),
+        ));
+    }
+    let min_silence_ms = min_silence_ms.unwrap_or(DEFAULT_MIN_SILENCE_MS);
+    if min_silence_ms == 0 || min_silence_ms > MAX_MIN_SILENCE_MS {
+        return Err(RuntimeValidationError::new(
+            "min_silence_ms",
+            format!(
+                "min_silence_ms must be between 1 and {MAX_MIN_SILENCE_MS}, got {min_silence_ms}."
+            ),
+        ));
+    }
+
+    let trim_start = format!(
+        "silenceremove=start_periods=1:start_duration={}:start_threshold={threshold_db}dB",
+        f64::from(min_silence_ms) / 1000.0
+    );
+    Ok(format!("{trim_start},areverse,{trim_start},areverse"))
+}
+
+/// Parses an FFmpeg `HH:MM:SS.ss` timestamp into seconds.
+pub fn parse_ffmpeg_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.trim().splitn(3, ':');
+    let hours = parts.next()?.parse::<f64>().ok()?;
+    let minutes = parts.next()?.parse::<f64>().ok()?;
+    let seconds = parts.next()?.parse::<f64>().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Reads the input length from the `Duration: 00:01:02.50, start: ...` line
+/// FFmpeg prints while probing an input.
+pub fn parse_ffmpeg_duration_line(line: &str) -> Option<f64> {
+    let (_, rest) = line.split_once("Duration: ")?;
+    parse_ffmpeg_timestamp(rest.split(',').next()?)
+}
+
+/// Splits a `key=value` line written by `ffmpeg -progress`. Regular log
+/// lines contain spaces or brackets and are rejected.
+pub fn parse_ffmpeg_progress_line(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.trim().split_once('=')?;
+    let is_key = !key.is_empty()
+        && key
+            .chars()
+            .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_');
+    (is_key && !value.contains(char::is_whitespace)).then_some((key, value))
+}
+
+/// Output position in seconds from an `out_time_us=` progress line.
+pub fn parse_ffmpeg_progress_seconds(line: &str) -> Option<f64> {
+    match parse_ffmpeg_progress_line(line)? {
+        ("out_time_us", value) => Some(value.parse::<u64>().ok()? as f64 / 1_000_000.0),
+        _ => None,
+    }
+}
+
+/// Lowercased fragments of the errors audio backends report when the OS
+/// refuses access to a capture device: EACCES/EPERM from ALSA and PulseAudio,
+/// `E_ACCESSDENIED` from WASAPI and CoreAudio's permission status.
+const CAPTURE_PERMISSION_DENIED_MARKERS: &[&str] = &[
+    "permission denied",
+    "access is denied",
+    "access denied",
+    "0x80070005",
+    "operation not permitted",
+    "not authorized",
+    "kaudiodevicepermissionserror",
+];
+
+/// Whether a capture error means the OS denied access to the device, as
+/// opposed to a missing or misconfigured device.
+pub fn is_capture_permission_denied(message: &str) -> bool {
+    let lowered = message.to_ascii_lowercase();
+    CAPTURE_PERMISSION_DENIED_MARKERS
+        .iter()
+        .any(|marker| lowered.contains(marker))
+}
+
+/// Deep link to the privacy settings pane that grants capture access on `os`
+/// (as in `std::env::consts::OS`). macOS gates system audio behind screen
+/// recording rather than the microphone permission.
+pub fn capture_permission_settings_url(os: &str, system_audio: bool) -> Option<&'static str> {
+    match (os, system_audio) {
+        ("macos", false) => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_Microphone")
+        }
+        ("macos", true) => {
+            Some("x-apple.systempreferences:com.apple.preference.security?Privacy_ScreenCapture")
+        }
+        ("windows", false) => Some("ms-settings:privacy-microphone"),
+        _ => None,
+    }
+}
+
+/// Parses the device table printed by `ffmpeg -hide_banner -devices` and
+/// returns the devices FFmpeg can read from.
+///
+/// Rows follow a ` ---` separator and start with a `D`/`E` flag column
+/// (demuxing/muxing) followed by the device name; output-only rows are
+/// skipped.
+pub fn parse_ffmpeg_input_device_names(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip_while(|line| !line.trim_start().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let flags = columns.next()?;
+            let name = columns.next()?;
+            flags.contains('D').then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Sound server, or bare driver, in charge of audio on a Linux desktop.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinuxAudioServer {
+    PipeWire,
+    PulseAudio,
+    /// No sound server; applications open ALSA devices directly.
+    Alsa,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinuxAudioBackend {
+    pub server: LinuxAudioServer,
+    /// PulseAudio clients, such as FFmpeg's `pulse` input, can connect.
+    /// PipeWire only serves them through pipewire-pulse.
+    pub pulse_compatible: bool,
+}
+
+/// Reads the sound server from `pactl info` output. PipeWire's PulseAudio
+/// replacement reports itself as `PulseAudio (on PipeWire x.y.z)`.
+pub fn parse_pactl_server(output: &str) -> Option<LinuxAudioServer> {
+    let name = output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Server Name:"))?;
+    if name.contains("PipeWire") {
+        Some(LinuxAudioServer::PipeWire)
+    } else if name.contains("PulseAudio") {
+        Some(LinuxAudioServer::PulseAudio)
+    } else {
+        None
+    }
+}
+
+/// An audio input backend the app can capture from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureBackend {
+    /// Backend name as used by the OS audio stack or FFmpeg's `-f` option.
+    pub id: &'static str,
+    /// Captured through the bundled FFmpeg rather than the native audio
+    /// stream, so it is only usable when FFmpeg was built with it.
+    pub ffmpeg: bool,
+    /// Can record what the system plays, not only input devices.
+    pub loopback: bool,
+}
+
+impl CaptureBackend {
+    const fn native(id: &'static str, loopback: bool) -> Self {
+        Self {
+            id,
+            ffmpeg: false,
+            loopback,
+        }
+    }
+
+    const fn ffmpeg(id: &'static str, loopback: bool) -> Self {
+        Self {
+            id,
+            ffmpeg: true,
+            loopback,
+        }
+    }
+}
+
+/// Capture backends available on `os` (as in `std::env::consts::OS`), given
+/// the input devices FFmpeg reports. Native backends are always listed;
+/// FFmpeg ones only when `ffmpeg_input_devices` contains them. Linux system
+/// audio comes from PulseAudio monitor sources, since ALSA has no loopback.
+pub fn capture_backends(os: &str, ffmpeg_input_devices: &[String]) -> Vec<CaptureBackend> {
+    let candidates: &[CaptureBackend] = match os {
+        "windows" => &[
+            CaptureBackend::native("wasapi", true),
+            CaptureBackend::ffmpeg("dshow", false),
+        ],
+        "macos" => &[
+            CaptureBackend::native("coreaudio", true),
+            CaptureBackend::ffmpeg("avfoundation", false),
+        ],
+        "linux" => &[
+            CaptureBackend::native("alsa", false),
+            CaptureBackend::ffmpeg("pulse", true),
+        ],
+        _ => &[],
+    };
+    candidates
+        .iter()
+        .copied()
+        .filter(|backend| {
+            !backend.ffmpeg
+                || ffmpeg_input_devices
+                    .iter()
+                    .any(|device| device == backend.id)
+        })
+        .collect()
+}
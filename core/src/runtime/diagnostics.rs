@@ -261,3 +261,45 @@ pub fn build_diagnostics_core_snapshot_at(
 fn default_microphone_id() -> String {
     "default".to_string()
 }
+
+const REDACTED: &str = "[REDACTED]";
+
+/// Secrets that can end up in log lines: auth headers, `key=value` or JSON
+/// fields whose name marks them as credentials (including URL query
+/// parameters), and provider API keys printed on their own.
+fn log_secret_patterns() -> &'static [(regex::Regex, &'static str)] {
+    static PATTERNS: std::sync::OnceLock<Vec<(regex::Regex, &'static str)>> =
+        std::sync::OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        [
+            (
+                r"(?i)(authorization\s*[:=]\s*(?:bearer\s+|basic\s+)?)[^\s,;]+",
+                "${1}[REDACTED]",
+            ),
+            (r"(?i)(\bbearer\s+)[A-Za-z0-9._~+/=-]+", "${1}[REDACTED]"),
+            (
+                r#"(?i)("?[a-z0-9_-]*(?:api[_-]?key|token|secret|password|credential)[a-z0-9_-]*"?\s*[:=]\s*"?)[^\s"'&,;}]+"#,
+                "${1}[REDACTED]",
+            ),
+            (r"\bsk-[A-Za-z0-9_-]{16,}", REDACTED),
+        ]
+        .into_iter()
+        .map(|(pattern, replacement)| {
+            (
+                regex::Regex::new(pattern).expect("log redaction pattern is valid"),
+                replacement,
+            )
+        })
+        .collect()
+    })
+}
+
+/// Masks credentials in log text before it leaves the machine, e.g. in a
+/// diagnostics bundle attached to a bug report.
+pub fn redact_log_secrets(text: &str) -> String {
+    log_secret_patterns()
+        .iter()
+        .fold(text.to_string(), |text, (pattern, replacement)| {
+            pattern.replace_all(&text, *replacement).into_owned()
+        })
+}
@@ -19,4 +19,52 @@ pub fn resolve_gpu_acceleration(
         ))
     }
 }
+use serde::Serialize;
+
 use super::error::RuntimeValidationError;
+
+/// A CUDA device as enumerated by `nvidia-smi`; `index` is the value
+/// `CUDA_VISIBLE_DEVICES` expects.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct GpuDevice {
+    pub index: u32,
+    pub name: String,
+}
+
+/// Parses `nvidia-smi --query-gpu=index,name --format=csv,noheader` output.
+/// Lines that do not start with a numeric index are ignored.
+pub fn parse_nvidia_smi_gpu_list(output: &str) -> Vec<GpuDevice> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (index, name) = line.split_once(',')?;
+            Some(GpuDevice {
+                index: index.trim().parse().ok()?,
+                name: name.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+pub fn resolve_preferred_gpu(
+    index: u32,
+    gpus: &[GpuDevice],
+) -> Result<&GpuDevice, RuntimeValidationError> {
+    gpus.iter().find(|gpu| gpu.index == index).ok_or_else(|| {
+        let available = gpus
+            .iter()
+            .map(|gpu| gpu.index.to_string())
+            .collect::<Vec<_>>();
+        RuntimeValidationError::new(
+            "preferred_gpu",
+            if available.is_empty() {
+                format!("preferred_gpu {index} is unavailable: no CUDA GPUs were detected.")
+            } else {
+                format!(
+                    "preferred_gpu must be one of {}, got {index}.",
+                    available.join(", ")
+                )
+            },
+        )
+    })
+}
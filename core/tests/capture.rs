@@ -0,0 +1,482 @@
+use sona_core::runtime::capture::{
+    AutomaticGainControl, CaptureAudioConfig, CaptureBackend, CapturePipeFormat, CaptureRing,
+    CaptureSampleFormat, DEFAULT_AGC_TARGET_DBFS, DEFAULT_CAPTURE_CHUNK_FRAMES,
+    DEFAULT_RECORD_CODEC, FFMPEG_STDERR_MAX_LINE_CHARS, FfmpegStderrLevel, FfmpegStderrTail,
+    LinuxAudioServer, MAX_CAPTURE_CHUNK_FRAMES, MAX_CAPTURE_RING_SECONDS, MIN_CAPTURE_CHUNK_FRAMES,
+    RECORD_CODEC_VALUES, RecordCodec, capture_backends, capture_permission_settings_url,
+    classify_ffmpeg_stderr_line, detect_tone_onset, is_capture_permission_denied,
+    parse_ffmpeg_duration_line, parse_ffmpeg_encoder_names, parse_ffmpeg_input_device_names,
+    parse_ffmpeg_progress_line, parse_ffmpeg_progress_seconds, parse_pactl_server,
+    resolve_capture_agc, resolve_capture_audio_config, resolve_capture_chunk_frames,
+    resolve_capture_input_channel, resolve_capture_pipe_format, resolve_capture_ring_seconds,
+    resolve_record_codec, silence_trim_filter, streaming_wav_header, supported_record_codecs,
+};
+use std::path::Path;
+
+#[test]
+fn record_codec_defaults_to_pcm_and_normalizes_values() {
+    assert_eq!(DEFAULT_RECORD_CODEC, "pcm");
+    assert!(RECORD_CODEC_VALUES.contains(&"opus"));
+    assert_eq!(resolve_record_codec(None).unwrap(), RecordCodec::Pcm);
+    assert_eq!(
+        resolve_record_codec(Some(" OPUS ".to_string())).unwrap(),
+        RecordCodec::Opus
+    );
+    assert_eq!(
+        resolve_record_codec(Some("aac".to_string())).unwrap(),
+        RecordCodec::Aac
+    );
+}
+
+#[test]
+fn record_codec_rejects_unknown_values() {
+    let error = resolve_record_codec(Some("flac".to_string())).unwrap_err();
+
+    assert_eq!(error.subject, "record_codec");
+    assert!(error.message.contains("record_codec must be one of"));
+}
+
+#[test]
+fn capture_pipe_format_defaults_to_raw_pcm() {
+    assert_eq!(
+        resolve_capture_pipe_format(None).unwrap(),
+        CapturePipeFormat::RawPcm
+    );
+    assert_eq!(
+        resolve_capture_pipe_format(Some(" WAV ".to_string())).unwrap(),
+        CapturePipeFormat::Wav
+    );
+    let error = resolve_capture_pipe_format(Some("flac".to_string())).unwrap_err();
+    assert_eq!(error.subject, "pipe_format");
+    assert!(
+        CapturePipeFormat::RawPcm
+            .stream_header(CaptureAudioConfig::default())
+            .is_empty()
+    );
+}
+
+#[test]
+fn capture_audio_config_defaults_to_16_khz_mono_s16le() {
+    let config = resolve_capture_audio_config(None, None, None).unwrap();
+
+    assert_eq!(config, CaptureAudioConfig::default());
+    assert_eq!(config.sample_rate, 16_000);
+    assert_eq!(config.channels, 1);
+    assert_eq!(config.sample_format, CaptureSampleFormat::S16Le);
+    assert_eq!(
+        resolve_capture_audio_config(Some(44_100), Some(2), Some(" F32LE ".to_string())).unwrap(),
+        CaptureAudioConfig {
+            sample_rate: 44_100,
+            channels: 2,
+            sample_format: CaptureSampleFormat::F32Le,
+        }
+    );
+}
+
+#[test]
+fn capture_audio_config_rejects_values_outside_the_allowlist() {
+    let rate = resolve_capture_audio_config(Some(44_000), None, None).unwrap_err();
+    let channels = resolve_capture_audio_config(None, Some(6), None).unwrap_err();
+    let zero_channels = resolve_capture_audio_config(None, Some(0), None).unwrap_err();
+    let format = resolve_capture_audio_config(None, None, Some("u8".to_string())).unwrap_err();
+
+    assert_eq!(rate.subject, "pipe_sample_rate");
+    assert!(rate.message.contains("44100"));
+    assert_eq!(channels.subject, "pipe_channels");
+    assert_eq!(zero_channels.subject, "pipe_channels");
+    assert_eq!(format.subject, "pipe_sample_format");
+}
+
+#[test]
+fn streaming_wav_header_describes_open_ended_16_bit_pcm() {
+    let header = streaming_wav_header(CaptureAudioConfig::default());
+    let u16_at = |at: usize| u16::from_le_bytes([header[at], header[at + 1]]);
+    let u32_at = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+
+    assert_eq!(&header[0..4], b"RIFF");
+    assert_eq!(u32_at(4), u32::MAX);
+    assert_eq!(&header[8..16], b"WAVEfmt ");
+    assert_eq!((u16_at(20), u16_at(22)), (1, 1));
+    assert_eq!(u32_at(24), 16_000);
+    assert_eq!(u32_at(28), 32_000);
+    assert_eq!((u16_at(32), u16_at(34)), (2, 16));
+    assert_eq!(&header[36..40], b"data");
+    assert_eq!(
+        CapturePipeFormat::Wav.stream_header(CaptureAudioConfig::default()),
+        header.to_vec()
+    );
+}
+
+#[test]
+fn streaming_wav_header_marks_float_samples() {
+    let header = streaming_wav_header(CaptureAudioConfig {
+        sample_rate: 48_000,
+        channels: 2,
+        sample_format: CaptureSampleFormat::F32Le,
+    });
+    let u16_at = |at: usize| u16::from_le_bytes([header[at], header[at + 1]]);
+    let u32_at = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+
+    assert_eq!((u16_at(20), u16_at(22)), (3, 2));
+    assert_eq!(u32_at(24), 48_000);
+    assert_eq!(u32_at(28), 384_000);
+    assert_eq!((u16_at(32), u16_at(34)), (8, 32));
+}
+
+#[test]
+fn record_codec_maps_to_ffmpeg_encoder_and_container() {
+    assert_eq!(RecordCodec::Pcm.ffmpeg_encoder(), None);
+    assert_eq!(RecordCodec::Opus.ffmpeg_encoder(), Some("libopus"));
+    assert_eq!(RecordCodec::Aac.ffmpeg_encoder(), Some("aac"));
+    assert_eq!(
+        RecordCodec::Opus.encoded_output_path(Path::new("C:/recordings/take.wav")),
+        Path::new("C:/recordings/take.ogg")
+    );
+    assert_eq!(
+        RecordCodec::Aac.encoded_output_path(Path::new("C:/recordings/take.wav")),
+        Path::new("C:/recordings/take.m4a")
+    );
+}
+
+#[test]
+fn parses_encoder_names_from_ffmpeg_encoder_table() {
+    let output = "Encoders:\n V..... = Video\n A..... = Audio\n ------\n V....D a64multi             Multicolor charset for Commodore 64\n A....D aac                  AAC (Advanced Audio Coding)\n A..... libopus              libopus Opus (codec opus)\n";
+
+    assert_eq!(
+        parse_ffmpeg_encoder_names(output),
+        vec!["a64multi", "aac", "libopus"]
+    );
+    assert!(parse_ffmpeg_encoder_names("").is_empty());
+}
+
+#[test]
+fn parses_input_device_names_from_ffmpeg_device_table() {
+    let output = "Devices:\n D. = Demuxing supported\n .E = Muxing supported\n ---\n DE alsa            ALSA audio output\n  E opengl          OpenGL output\n D  pulse           Pulse audio input\n";
+
+    assert_eq!(
+        parse_ffmpeg_input_device_names(output),
+        vec!["alsa", "pulse"]
+    );
+    assert!(parse_ffmpeg_input_device_names("").is_empty());
+}
+
+#[test]
+fn capture_backends_need_ffmpeg_support_for_ffmpeg_backends() {
+    let ids = |backends: Vec<CaptureBackend>| {
+        backends
+            .into_iter()
+            .map(|backend| (backend.id, backend.loopback))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        ids(capture_backends("windows", &[])),
+        vec![("wasapi", true)]
+    );
+    assert_eq!(
+        ids(capture_backends("windows", &["dshow".to_string()])),
+        vec![("wasapi", true), ("dshow", false)]
+    );
+    assert_eq!(
+        ids(capture_backends("macos", &["avfoundation".to_string()])),
+        vec![("coreaudio", true), ("avfoundation", false)]
+    );
+    assert_eq!(
+        ids(capture_backends("linux", &["pulse".to_string()])),
+        vec![("alsa", false), ("pulse", true)]
+    );
+    assert!(capture_backends("freebsd", &["pulse".to_string()]).is_empty());
+}
+
+#[test]
+fn pactl_info_tells_pipewire_from_pulseaudio() {
+    let info = |server: &str| {
+        format!(
+            "Server String: /run/user/1000/pulse/native\nServer Name: {server}\nServer Version: 16.1\n"
+        )
+    };
+
+    assert_eq!(
+        parse_pactl_server(&info("PulseAudio (on PipeWire 1.0.5)")),
+        Some(LinuxAudioServer::PipeWire)
+    );
+    assert_eq!(parse_pactl_server(&info("pulseaudio")), None);
+    assert_eq!(
+        parse_pactl_server(&info("PulseAudio")),
+        Some(LinuxAudioServer::PulseAudio)
+    );
+    assert_eq!(
+        parse_pactl_server("Connection failure: Connection refused"),
+        None
+    );
+}
+
+#[test]
+fn supported_record_codecs_follow_available_encoders() {
+    assert_eq!(supported_record_codecs(&[]), vec![RecordCodec::Pcm]);
+    assert_eq!(
+        supported_record_codecs(&["pcm_s16le".to_string(), "aac".to_string()]),
+        vec![RecordCodec::Pcm, RecordCodec::Aac]
+    );
+    assert_eq!(
+        supported_record_codecs(&["libopus".to_string(), "aac".to_string()]),
+        vec![RecordCodec::Pcm, RecordCodec::Opus, RecordCodec::Aac]
+    );
+}
+
+#[test]
+fn ffmpeg_stderr_lines_are_demoted_unless_they_look_like_problems() {
+    assert_eq!(
+        classify_ffmpeg_stderr_line("  Stream #0:0: Audio: pcm_s16le, 16000 Hz, mono"),
+        FfmpegStderrLevel::Debug
+    );
+    assert_eq!(
+        classify_ffmpeg_stderr_line("[mp3 @ 0x1] Error while decoding stream #0:0"),
+        FfmpegStderrLevel::Warn
+    );
+    assert_eq!(
+        classify_ffmpeg_stderr_line("Invalid data found when processing input"),
+        FfmpegStderrLevel::Warn
+    );
+}
+
+#[test]
+fn ffmpeg_stderr_tail_keeps_only_the_most_recent_lines() {
+    let mut tail = FfmpegStderrTail::new(3, 100);
+    for index in 0..10 {
+        tail.push(&format!("line {index}"));
+    }
+    tail.push("   ");
+
+    assert_eq!(
+        tail.lines().collect::<Vec<_>>(),
+        vec!["line 7", "line 8", "line 9"]
+    );
+    assert_eq!(tail.summary(), "line 7\nline 8\nline 9");
+}
+
+#[test]
+fn ffmpeg_stderr_tail_stops_logging_after_budget() {
+    let mut tail = FfmpegStderrTail::new(2, 2);
+
+    assert!(tail.push("Error one").is_some());
+    assert_eq!(
+        tail.push("info two"),
+        Some((FfmpegStderrLevel::Debug, "info two".to_string()))
+    );
+    assert!(tail.push("Error three").is_none());
+    assert!(tail.push("Error four").is_none());
+
+    assert_eq!(tail.suppressed(), 2);
+    assert_eq!(
+        tail.lines().collect::<Vec<_>>(),
+        vec!["Error three", "Error four"]
+    );
+}
+
+#[test]
+fn ffmpeg_stderr_tail_truncates_long_lines() {
+    let mut tail = FfmpegStderrTail::default();
+    let long_line = "x".repeat(FFMPEG_STDERR_MAX_LINE_CHARS * 4);
+
+    let (_, logged) = tail.push(&long_line).unwrap();
+
+    assert_eq!(logged.chars().count(), FFMPEG_STDERR_MAX_LINE_CHARS + 1);
+    assert!(logged.ends_with('…'));
+}
+
+#[test]
+fn capture_chunk_frames_default_and_bounds() {
+    assert_eq!(
+        resolve_capture_chunk_frames(None).unwrap(),
+        DEFAULT_CAPTURE_CHUNK_FRAMES
+    );
+    assert_eq!(resolve_capture_chunk_frames(Some(256)).unwrap(), 256);
+    assert_eq!(
+        resolve_capture_chunk_frames(Some(MIN_CAPTURE_CHUNK_FRAMES as u32)).unwrap(),
+        MIN_CAPTURE_CHUNK_FRAMES
+    );
+    assert_eq!(
+        resolve_capture_chunk_frames(Some(MAX_CAPTURE_CHUNK_FRAMES as u32)).unwrap(),
+        MAX_CAPTURE_CHUNK_FRAMES
+    );
+
+    for invalid in [0, 64, 16_001] {
+        let error = resolve_capture_chunk_frames(Some(invalid)).unwrap_err();
+        assert_eq!(error.subject, "chunk_frames");
+    }
+}
+
+#[test]
+fn capture_input_channel_is_one_based_and_bounded_by_device() {
+    assert_eq!(resolve_capture_input_channel(None, 2).unwrap(), None);
+    assert_eq!(resolve_capture_input_channel(Some(1), 2).unwrap(), Some(0));
+    assert_eq!(resolve_capture_input_channel(Some(3), 8).unwrap(), Some(2));
+
+    for (channel, device_channels) in [(0, 2), (3, 2), (1, 0)] {
+        let error = resolve_capture_input_channel(Some(channel), device_channels).unwrap_err();
+        assert_eq!(error.subject, "input_channel");
+        assert!(error.message.contains(&format!("got {channel}")));
+    }
+}
+
+#[test]
+fn capture_agc_is_off_by_default_and_validates_target() {
+    assert_eq!(resolve_capture_agc(None, Some(-12.0)).unwrap(), None);
+    assert_eq!(
+        resolve_capture_agc(Some(true), None).unwrap(),
+        Some(DEFAULT_AGC_TARGET_DBFS)
+    );
+    assert_eq!(
+        resolve_capture_agc(Some(true), Some(-12.0)).unwrap(),
+        Some(-12.0)
+    );
+
+    for invalid in [-60.0, 0.0, f32::NAN] {
+        let error = resolve_capture_agc(Some(true), Some(invalid)).unwrap_err();
+        assert_eq!(error.subject, "agc_target_dbfs");
+    }
+}
+
+#[test]
+fn automatic_gain_control_raises_quiet_input_and_ignores_silence() {
+    let mut agc = AutomaticGainControl::new(-20.0);
+
+    let mut silence = vec![0.0_f32; 256];
+    agc.process(&mut silence);
+    assert_eq!(agc.gain(), 1.0);
+
+    for _ in 0..200 {
+        let mut quiet = vec![0.01_f32; 256];
+        agc.process(&mut quiet);
+    }
+    // -40 dBFS input needs +20 dB to reach the -20 dBFS target.
+    assert!((agc.gain() - 10.0).abs() < 0.1, "gain {}", agc.gain());
+
+    let mut loud = vec![0.9_f32; 256];
+    agc.process(&mut loud);
+    assert!(loud.iter().all(|sample| *sample <= 1.0));
+    assert!(agc.gain() < 10.0);
+}
+
+#[test]
+fn capture_ring_seconds_is_disabled_by_default_and_bounded() {
+    assert_eq!(resolve_capture_ring_seconds(None).unwrap(), None);
+    assert_eq!(resolve_capture_ring_seconds(Some(0)).unwrap(), None);
+    assert_eq!(
+        resolve_capture_ring_seconds(Some(30)).unwrap(),
+        Some(480_000)
+    );
+
+    let error = resolve_capture_ring_seconds(Some(MAX_CAPTURE_RING_SECONDS + 1)).unwrap_err();
+    assert_eq!(error.subject, "capture_ring_seconds");
+}
+
+#[test]
+fn capture_ring_keeps_only_the_most_recent_samples() {
+    let mut ring = CaptureRing::new(4);
+    assert!(ring.is_empty());
+
+    ring.push(&[1.0, 2.0, 3.0]);
+    assert_eq!(ring.to_vec(), vec![1.0, 2.0, 3.0]);
+
+    ring.push(&[4.0, 5.0]);
+    assert_eq!(ring.to_vec(), vec![2.0, 3.0, 4.0, 5.0]);
+
+    ring.push(&[6.0, 7.0, 8.0, 9.0, 10.0, 11.0]);
+    assert_eq!(ring.to_vec(), vec![8.0, 9.0, 10.0, 11.0]);
+    assert_eq!(ring.len(), ring.capacity());
+}
+
+#[test]
+fn silence_trim_filter_trims_both_ends_and_validates_bounds() {
+    assert_eq!(
+        silence_trim_filter(Some(-40.0), Some(250)).unwrap(),
+        "silenceremove=start_periods=1:start_duration=0.25:start_threshold=-40dB,areverse,\
+         silenceremove=start_periods=1:start_duration=0.25:start_threshold=-40dB,areverse"
+    );
+    assert!(silence_trim_filter(None, None).is_ok());
+
+    let error = silence_trim_filter(Some(3.0), None).unwrap_err();
+    assert_eq!(error.subject, "threshold_db");
+    let error = silence_trim_filter(None, Some(0)).unwrap_err();
+    assert_eq!(error.subject, "min_silence_ms");
+}
+
+#[test]
+fn parses_ffmpeg_duration_and_progress_lines() {
+    assert_eq!(
+        parse_ffmpeg_duration_line("  Duration: 00:01:02.50, start: 0.000000, bitrate: 256 kb/s"),
+        Some(62.5)
+    );
+    assert_eq!(
+        parse_ffmpeg_duration_line("  Duration: N/A, bitrate: N/A"),
+        None
+    );
+
+    assert_eq!(
+        parse_ffmpeg_progress_seconds("out_time_us=1500000"),
+        Some(1.5)
+    );
+    assert_eq!(parse_ffmpeg_progress_seconds("out_time_us=N/A"), None);
+    assert_eq!(
+        parse_ffmpeg_progress_line("progress=continue"),
+        Some(("progress", "continue"))
+    );
+    assert_eq!(
+        parse_ffmpeg_progress_line("[aac @ 0x1] Too many bits=1"),
+        None
+    );
+}
+
+#[test]
+fn capture_permission_errors_are_told_apart_from_device_errors() {
+    assert!(is_capture_permission_denied(
+        "The requested stream configuration is not supported: Access is denied. (0x80070005)"
+    ));
+    assert!(is_capture_permission_denied(
+        "ALSA function 'snd_pcm_open' failed with error 'EACCES: Permission denied'"
+    ));
+    assert!(!is_capture_permission_denied(
+        "The requested device is no longer available."
+    ));
+
+    assert_eq!(
+        capture_permission_settings_url("windows", false),
+        Some("ms-settings:privacy-microphone")
+    );
+    assert!(
+        capture_permission_settings_url("macos", true)
+            .is_some_and(|url| url.ends_with("Privacy_ScreenCapture"))
+    );
+    assert_eq!(capture_permission_settings_url("linux", false), None);
+}
+
+#[test]
+fn tone_onset_is_found_above_the_noise_floor() {
+    let sample_rate = 48_000;
+    let noise = |index: usize| {
+        if index.is_multiple_of(2) {
+            0.002
+        } else {
+            -0.002
+        }
+    };
+    let mut samples = (0..9_600).map(noise).collect::<Vec<f32>>();
+    let onset = samples.len() + 2_400;
+    samples.extend((0..2_400).map(noise));
+    samples.extend((0..4_800).map(|index| {
+        let phase = index as f32 * 1_000.0 / sample_rate as f32;
+        0.3 * (std::f32::consts::TAU * phase).sin() + 0.0001
+    }));
+
+    let detected = detect_tone_onset(&samples, sample_rate, 9_600).expect("tone found");
+    // Within the first quarter period of the 1 kHz tone.
+    assert!((onset..onset + 12).contains(&detected), "{detected}");
+
+    assert_eq!(
+        detect_tone_onset(&samples[..onset], sample_rate, 9_600),
+        None
+    );
+    assert_eq!(detect_tone_onset(&[], sample_rate, 9_600), None);
+}
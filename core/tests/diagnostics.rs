@@ -7,7 +7,7 @@ use sona_core::runtime::diagnostics::{
     DiagnosticsEnrichmentMeasurements, DiagnosticsEnrichmentRepository, DiagnosticsError,
     DiagnosticsService, ModelRuleInput, ModelRulesInput, ModelSummaryInput, PathStatusesInput,
     RuntimeEnvironmentStatus, RuntimePathKind, RuntimePathStatus, SelectedModelsInput,
-    VoiceTypingReadinessInput, build_diagnostics_core_snapshot_at,
+    VoiceTypingReadinessInput, build_diagnostics_core_snapshot_at, redact_log_secrets,
 };
 use sona_core::transcription::asr_metrics::{
     AsrInferenceMetric, AsrModelLoadMetric, AsrRuntimeMetricsSnapshot,
@@ -435,3 +435,30 @@ fn service_snapshot_preserves_the_existing_camel_case_json_contract() {
         })
     );
 }
+
+#[test]
+fn log_redaction_masks_credentials_and_keeps_the_rest() {
+    let log = concat!(
+        "[INFO] Authorization: Bearer abc.def-123\n",
+        "[INFO] GET https://api.example.com/v1?api_key=secret123&lang=en\n",
+        "[DEBUG] config {\"apiKey\": \"sk-abcdefghijklmnopqrstu\", \"model\": \"whisper\"}\n",
+        "[WARN] password=hunter2 user=alice\n",
+        "[INFO] key sk-0123456789abcdefghij leaked\n",
+    );
+
+    let redacted = redact_log_secrets(log);
+
+    for secret in [
+        "abc.def-123",
+        "secret123",
+        "sk-abcdefghijklmnopqrstu",
+        "hunter2",
+        "sk-0123456789abcdefghij",
+    ] {
+        assert!(!redacted.contains(secret), "{secret} leaked: {redacted}");
+    }
+    assert!(redacted.contains("Authorization: Bearer [REDACTED]"));
+    assert!(redacted.contains("api_key=[REDACTED]&lang=en"));
+    assert!(redacted.contains("\"model\": \"whisper\""));
+    assert!(redacted.contains("user=alice"));
+}
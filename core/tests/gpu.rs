@@ -1,5 +1,6 @@
 use sona_core::runtime::gpu::{
-    DEFAULT_GPU_ACCELERATION, GPU_ACCELERATION_VALUES, resolve_gpu_acceleration,
+    DEFAULT_GPU_ACCELERATION, GPU_ACCELERATION_VALUES, GpuDevice, parse_nvidia_smi_gpu_list,
+    resolve_gpu_acceleration, resolve_preferred_gpu,
 };
 
 #[test]
@@ -25,3 +26,38 @@ fn gpu_acceleration_rejects_unknown_values() {
     assert_eq!(error.subject, "gpu_acceleration");
     assert!(error.message.contains("gpu_acceleration must be one of"));
 }
+
+#[test]
+fn parses_nvidia_smi_gpu_list() {
+    let output =
+        "0, NVIDIA GeForce RTX 4090\n1, NVIDIA RTX A2000 Laptop GPU\n\nNo devices were found\n";
+
+    assert_eq!(
+        parse_nvidia_smi_gpu_list(output),
+        vec![
+            GpuDevice {
+                index: 0,
+                name: "NVIDIA GeForce RTX 4090".to_string()
+            },
+            GpuDevice {
+                index: 1,
+                name: "NVIDIA RTX A2000 Laptop GPU".to_string()
+            },
+        ]
+    );
+    assert!(parse_nvidia_smi_gpu_list("").is_empty());
+}
+
+#[test]
+fn preferred_gpu_must_match_an_enumerated_device() {
+    let gpus = parse_nvidia_smi_gpu_list("0, Integrated\n1, Discrete\n");
+
+    assert_eq!(resolve_preferred_gpu(1, &gpus).unwrap().name, "Discrete");
+
+    let error = resolve_preferred_gpu(2, &gpus).unwrap_err();
+    assert_eq!(error.subject, "preferred_gpu");
+    assert!(error.message.contains("must be one of 0, 1, got 2"));
+
+    let error = resolve_preferred_gpu(0, &[]).unwrap_err();
+    assert!(error.message.contains("no CUDA GPUs were detected"));
+}
@@ -313,13 +313,26 @@ fn map_download_error(error: sona_model_downloads::DownloadError) -> CliError {
     match error {
         sona_model_downloads::DownloadError::Cancelled => CliError::Cancelled(message),
         sona_model_downloads::DownloadError::Network(_)
-        | sona_model_downloads::DownloadError::HttpStatus(_)
+        | sona_model_downloads::DownloadError::HttpStatus { .. }
+        | sona_model_downloads::DownloadError::HostNotAllowed { .. }
+        | sona_model_downloads::DownloadError::TooManyRedirects { .. }
         | sona_model_downloads::DownloadError::HttpClient { .. }
+        | sona_model_downloads::DownloadError::Stalled { .. }
         | sona_model_downloads::DownloadError::RangeNotSatisfiable => CliError::Network(message),
         sona_model_downloads::DownloadError::Io(_)
+        | sona_model_downloads::DownloadError::Write { .. }
+        | sona_model_downloads::DownloadError::InsufficientDiskSpace { .. }
         | sona_model_downloads::DownloadError::FileSystem(_) => CliError::Io(message),
-        sona_model_downloads::DownloadError::HashMismatch { .. } => CliError::Model(message),
-        sona_model_downloads::DownloadError::AlreadyInProgress => CliError::Other(message),
+        sona_model_downloads::DownloadError::HashMismatch { .. }
+        | sona_model_downloads::DownloadError::CorruptArchive { .. } => CliError::Model(message),
+        sona_model_downloads::DownloadError::InvalidNetworkPolicy { .. }
+        | sona_model_downloads::DownloadError::InvalidDownloadSpec { .. }
+        | sona_model_downloads::DownloadError::InvalidModelManifest { .. }
+        | sona_model_downloads::DownloadError::InvalidTempDir { .. } => {
+            CliError::Validation(message)
+        }
+        sona_model_downloads::DownloadError::AlreadyInProgress
+        | sona_model_downloads::DownloadError::ResponseTooLarge { .. } => CliError::Other(message),
     }
 }
 
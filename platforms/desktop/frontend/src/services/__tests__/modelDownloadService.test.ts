@@ -1,6 +1,10 @@
 import { beforeEach, describe, expect, it, vi } from 'vitest';
 import { createModelDownloadService } from '../modelDownloadService';
-import { parseDownloadProgressPayload } from '../modelDownloadService';
+import {
+  isRetryableDownloadFailure,
+  parseDownloadFailedPayload,
+  parseDownloadProgressPayload,
+} from '../modelDownloadService';
 import type { ModelCatalogModel, ModelInfo } from '../modelService';
 
 const i18nMocks = vi.hoisted(() => ({
@@ -100,6 +104,7 @@ describe('modelDownloadService', () => {
     expect(extractTarBz2).toHaveBeenCalledWith({
       archivePath: '/catalog/download.tar.bz2',
       targetDir: '/catalog',
+      verify: true,
     });
     expect(remove).toHaveBeenCalledWith('/catalog/download.tar.bz2');
     expect(onProgress).toHaveBeenCalledWith(100, 'Done', true);
@@ -311,6 +316,27 @@ describe('modelDownloadService', () => {
     expect(cancelDownload).toHaveBeenCalledTimes(1);
   });
 
+  it('treats a cancelled rejection from download_file as a cancellation', async () => {
+    const service = createModelDownloadService({
+      downloadFile,
+      extractTarBz2,
+      cancelDownload,
+      remove,
+      listen,
+      join,
+      getModelsDir,
+    });
+
+    downloadFile.mockRejectedValue({ kind: 'cancelled', status: null, message: 'Download was stopped' });
+
+    await expect(service.downloadModel({
+      modelId: 'model-a',
+      model: makeModel(),
+    })).rejects.toThrow('Download cancelled');
+
+    expect(downloadFile).toHaveBeenCalledTimes(1);
+  });
+
   it('parses legacy and named download progress payloads', () => {
     expect(parseDownloadProgressPayload([10, 20, 'download-a'])).toEqual({
       downloaded: 10,
@@ -327,5 +353,54 @@ describe('modelDownloadService', () => {
       total: 4,
       id: 'download-c',
     });
+    expect(parseDownloadProgressPayload({
+      id: 'download-d',
+      downloaded: 5,
+      total: 10,
+      bytesPerSec: 2.5,
+      etaSeconds: 2,
+    })).toEqual({
+      downloaded: 5,
+      total: 10,
+      id: 'download-d',
+      bytesPerSec: 2.5,
+      etaSeconds: 2,
+    });
+    expect(parseDownloadProgressPayload({
+      id: 'download-e',
+      downloaded: 0,
+      total: 0,
+      bytesPerSec: 0,
+      etaSeconds: null,
+    })).toEqual({
+      downloaded: 0,
+      total: 0,
+      id: 'download-e',
+      bytesPerSec: 0,
+      etaSeconds: null,
+    });
+  });
+
+  it('parses download failures and decides whether a retry can help', () => {
+    const outage = parseDownloadFailedPayload({ id: 'a', kind: 'network', status: 503, message: 'HTTP 503' });
+    expect(outage).toEqual({ id: 'a', kind: 'network', status: 503, message: 'HTTP 503' });
+    expect(isRetryableDownloadFailure(outage)).toBe(true);
+
+    const dropped = parseDownloadFailedPayload({ id: 'b', kind: 'network', status: null, message: 'reset' });
+    expect(isRetryableDownloadFailure(dropped)).toBe(true);
+
+    const unauthorized = parseDownloadFailedPayload({ id: 'c', kind: 'network', status: 401, message: 'HTTP 401' });
+    expect(isRetryableDownloadFailure(unauthorized)).toBe(false);
+
+    const full = parseDownloadFailedPayload({ id: 'd', kind: 'diskFull', message: 'no space' });
+    expect(full.status).toBeNull();
+    expect(isRetryableDownloadFailure(full)).toBe(false);
+
+    const rejection = parseDownloadFailedPayload({ kind: 'checksum', status: null, message: 'Hash mismatch' });
+    expect(rejection).toEqual({ id: '', kind: 'checksum', status: null, message: 'Hash mismatch' });
+    expect(isRetryableDownloadFailure(rejection)).toBe(false);
+
+    expect(parseDownloadFailedPayload({ id: 'e', kind: 'somethingNew' }).kind).toBe('other');
+    expect(parseDownloadFailedPayload(null)).toEqual({ id: '', kind: 'other', status: null, message: '' });
   });
 });
@@ -11,10 +11,32 @@ interface DownloadProgressPayloadObject {
   downloaded?: number;
   total?: number;
   id?: string;
+  bytesPerSec?: number;
+  etaSeconds?: number | null;
+}
+
+interface DownloadProgressUpdate {
+  downloaded: number;
+  total: number;
+  id: string;
+  /** Backend moving average; absent in legacy tuple payloads. */
+  bytesPerSec?: number;
+  /** Seconds left at that speed; null while it is unknown. */
+  etaSeconds?: number | null;
+}
+
+interface ExtractProgress {
+  path: string;
+  compressedRead: number;
+  compressedTotal: number;
+  bytesDone?: number;
+  bytesTotal?: number | null;
+  /** Set only for extractions started with `withProgress`. */
+  percent?: number | null;
 }
 
 type DownloadFile = (input: { url: string; outputPath: string; id: string; expectedSha256?: string }) => Promise<void>;
-type ExtractTarBz2 = (input: { archivePath: string; targetDir: string }) => Promise<void>;
+type ExtractTarBz2 = (input: { archivePath: string; targetDir: string; verify?: boolean }) => Promise<void>;
 type Listen = <T>(event: string, handler: (event: { payload: T }) => void) => Promise<() => void>;
 
 interface ModelDownloadServicePorts {
@@ -36,7 +58,7 @@ interface DownloadModelInput {
   mirror?: string;
 }
 
-export function parseDownloadProgressPayload(payload: unknown): { downloaded: number; total: number; id: string } {
+export function parseDownloadProgressPayload(payload: unknown): DownloadProgressUpdate {
   if (Array.isArray(payload)) {
     const [downloaded, total, id] = payload;
     return {
@@ -64,12 +86,76 @@ export function parseDownloadProgressPayload(payload: unknown): { downloaded: nu
         ? value.id
         : '';
 
-    return { downloaded, total, id };
+    const update: DownloadProgressUpdate = { downloaded, total, id };
+    if (typeof value.bytesPerSec === 'number') {
+      update.bytesPerSec = value.bytesPerSec;
+      update.etaSeconds = typeof value.etaSeconds === 'number' ? value.etaSeconds : null;
+    }
+    return update;
   }
 
   return { downloaded: 0, total: 0, id: '' };
 }
 
+/** Categories of the backend `download-failed` event and `download_file` rejections. */
+export type DownloadFailureKind =
+  | 'network'
+  | 'diskFull'
+  | 'permissionDenied'
+  | 'disk'
+  | 'checksum'
+  | 'corruptArchive'
+  | 'hostNotAllowed'
+  | 'tooManyRedirects'
+  | 'cancelled'
+  | 'paused'
+  | 'other';
+
+const DOWNLOAD_FAILURE_KINDS: readonly DownloadFailureKind[] = [
+  'network',
+  'diskFull',
+  'permissionDenied',
+  'disk',
+  'checksum',
+  'corruptArchive',
+  'hostNotAllowed',
+  'tooManyRedirects',
+  'cancelled',
+  'paused',
+  'other',
+];
+
+export interface DownloadFailure {
+  id: string;
+  kind: DownloadFailureKind;
+  /** HTTP status when the server refused the download. */
+  status: number | null;
+  message: string;
+}
+
+/**
+ * Reads a `download-failed` payload or a `download_file` rejection, which has
+ * no `id`; unknown kinds become `other`.
+ */
+export function parseDownloadFailedPayload(payload: unknown): DownloadFailure {
+  const value = (typeof payload === 'object' && payload !== null ? payload : {}) as Record<string, unknown>;
+  const kind = DOWNLOAD_FAILURE_KINDS.find((known) => known === value.kind) ?? 'other';
+  return {
+    id: typeof value.id === 'string' ? value.id : '',
+    kind,
+    status: typeof value.status === 'number' ? value.status : null,
+    message: typeof value.message === 'string' ? value.message : '',
+  };
+}
+
+/** Whether downloading again may succeed without the user changing anything. */
+export function isRetryableDownloadFailure(failure: DownloadFailure): boolean {
+  if (failure.kind !== 'network') {
+    return false;
+  }
+  return failure.status === null || failure.status >= 500 || failure.status === 408 || failure.status === 429;
+}
+
 function isCatalogModel(model: ModelInfo | ModelCatalogModel): model is ModelCatalogModel {
   return 'installPath' in model && 'downloadPath' in model;
 }
@@ -107,8 +193,8 @@ class ModelDownloadService {
 
     let extractUnlisten: (() => void) | undefined;
     if (onProgress) {
-      extractUnlisten = await this.ports.listen<string>(TauriEvent.app.extractProgress, (event) => {
-        const filename = event.payload;
+      extractUnlisten = await this.ports.listen<ExtractProgress>(TauriEvent.app.extractProgress, (event) => {
+        const filename = event.payload.path;
         const displayFilename = filename.length > 30 ? '...' + filename.slice(-27) : filename;
         onProgress(100, i18n.t('settings.model_download_status.extracting_file', {
           filename: displayFilename,
@@ -181,7 +267,7 @@ class ModelDownloadService {
     }
 
     const unlisten = await this.ports.listen<unknown>(TauriEvent.app.downloadProgress, (event) => {
-      const { downloaded, total, id } = parseDownloadProgressPayload(event.payload);
+      const { downloaded, total, id, bytesPerSec } = parseDownloadProgressPayload(event.payload);
 
       if (id && id !== downloadId) return;
 
@@ -195,7 +281,7 @@ class ModelDownloadService {
 
       if (onProgress && (timeDiff > 500 || total === downloaded)) {
         const bytesDiff = Math.max(0, downloaded - uiLastDownloaded);
-        const speedBytesPerSec = bytesDiff / (timeDiff / 1000);
+        const speedBytesPerSec = bytesPerSec ?? bytesDiff / (timeDiff / 1000);
         const speedStr = speedBytesPerSec > 1024 * 1024
           ? `${(speedBytesPerSec / 1024 / 1024).toFixed(1)} MB/s`
           : `${Math.round(speedBytesPerSec / 1024)} KB/s`;
@@ -247,7 +333,11 @@ class ModelDownloadService {
           // Success, exit the loop
           break;
         } catch (error) {
-          if (signal?.aborted || extractErrorMessage(error).includes('cancelled')) {
+          if (
+            signal?.aborted
+            || parseDownloadFailedPayload(error).kind === 'cancelled'
+            || extractErrorMessage(error).includes('cancelled')
+          ) {
             throw Object.assign(new Error('Download cancelled'), { cause: error });
           }
 
@@ -304,6 +394,7 @@ class ModelDownloadService {
       await this.ports.extractTarBz2({
         archivePath,
         targetDir,
+        verify: true,
       });
     } catch (error) {
       throw Object.assign(new Error(`Extraction failed: ${extractErrorMessage(error)}`), { cause: error });
@@ -36,6 +36,8 @@ export type DownloadFileRequest = TauriCommandArgs<typeof TauriCommand.app.downl
 
 export type ExtractTarBz2Request = TauriCommandArgs<typeof TauriCommand.app.extractTarBz2>;
 
+export type ExtractArchiveRequest = TauriCommandArgs<typeof TauriCommand.app.extractArchive>;
+
 export type UpdateTrayMenuRequest = TauriCommandArgs<typeof TauriCommand.app.updateTrayMenu>;
 
 export type ModelSelectionPaths = TauriCommandArgs<
@@ -299,18 +301,35 @@ function normalizeModelCatalogSelectedIds(
   };
 }
 
-export async function extractTarBz2(request: ExtractTarBz2Request): Promise<void> {
-  await invokeTauri(TauriCommand.app.extractTarBz2, request);
+export async function extractTarBz2(
+  request: ExtractTarBz2Request,
+): Promise<TauriCommandResult<typeof TauriCommand.app.extractTarBz2>> {
+  return invokeTauri(TauriCommand.app.extractTarBz2, request);
+}
+
+/** Extracts a `.tar.bz2`, `.tar.gz`, `.tar.xz` or `.zip` archive, detected from its contents. */
+export async function extractArchive(
+  request: ExtractArchiveRequest,
+): Promise<TauriCommandResult<typeof TauriCommand.app.extractArchive>> {
+  return invokeTauri(TauriCommand.app.extractArchive, request);
 }
 
-export async function downloadFile(request: DownloadFileRequest): Promise<void> {
-  await invokeTauri(TauriCommand.app.downloadFile, request);
+/** Resolves to the downloaded file's SHA-256 as lowercase hex. */
+export async function downloadFile(
+  request: DownloadFileRequest,
+): Promise<TauriCommandResult<typeof TauriCommand.app.downloadFile>> {
+  return invokeTauri(TauriCommand.app.downloadFile, request);
 }
 
 export async function cancelDownload(id: string): Promise<void> {
   await invokeTauri(TauriCommand.app.cancelDownload, { id });
 }
 
+/** Stops the extraction started with `id`; it rejects with "Extraction cancelled". */
+export async function cancelExtraction(id: string): Promise<void> {
+  await invokeTauri(TauriCommand.app.cancelExtraction, { id });
+}
+
 export async function openLogFolder(): Promise<void> {
   await invokeTauri(TauriCommand.app.openLogFolder);
 }
@@ -396,6 +415,10 @@ export async function hasActiveDownloads(): Promise<boolean> {
   return invokeTauri(TauriCommand.app.hasActiveDownloads);
 }
 
+export async function ping(): Promise<number> {
+  return invokeTauri(TauriCommand.app.ping);
+}
+
 export async function forceExit(): Promise<void> {
   await invokeTauri(TauriCommand.app.forceExit);
 }
@@ -408,6 +431,7 @@ export async function setMinimizeToTray(enabled: boolean): Promise<void> {
   await invokeTauri(TauriCommand.app.setMinimizeToTray, { enabled });
 }
 
-export async function setLogLevel(level: AppLogLevel): Promise<void> {
-  await invokeTauri(TauriCommand.app.setLogLevel, { level });
+/** Returns the level that was active before. */
+export async function setLogLevel(level: AppLogLevel): Promise<AppLogLevel> {
+  return invokeTauri(TauriCommand.app.setLogLevel, { level });
 }
@@ -11,8 +11,40 @@ export type StartAudioCaptureRequest =
 export type SetCapturePausedRequest =
   TauriCommandArgs<typeof TauriCommand.audio.setSystemAudioCapturePaused>;
 
-export async function setSystemAudioMute(mute: boolean): Promise<void> {
-  await invokeTauri(TauriCommand.audio.setSystemAudioMute, { mute });
+export type OutputDevice =
+  TauriCommandResult<typeof TauriCommand.audio.getOutputDevices>[number];
+
+export async function setSystemAudioMute(mute: boolean, deviceId?: string | null): Promise<string> {
+  return invokeTauri(
+    TauriCommand.audio.setSystemAudioMute,
+    deviceId === undefined ? { mute } : { mute, deviceId },
+  );
+}
+
+export async function getSystemAudioMute(deviceId?: string | null): Promise<boolean> {
+  return invokeTauri(
+    TauriCommand.audio.getSystemAudioMute,
+    deviceId === undefined ? {} : { deviceId },
+  );
+}
+
+/** `level` is 0.0–1.0; the backend clamps values outside it. */
+export async function setSystemVolume(level: number, deviceId?: string | null): Promise<string> {
+  return invokeTauri(
+    TauriCommand.audio.setSystemVolume,
+    deviceId === undefined ? { level } : { level, deviceId },
+  );
+}
+
+export async function getSystemVolume(deviceId?: string | null): Promise<number> {
+  return invokeTauri(
+    TauriCommand.audio.getSystemVolume,
+    deviceId === undefined ? {} : { deviceId },
+  );
+}
+
+export async function getOutputDevices(): Promise<OutputDevice[]> {
+  return invokeTauri(TauriCommand.audio.getOutputDevices);
 }
 
 export async function getSystemAudioDevices(): Promise<AudioDevice[]> {
@@ -6,7 +6,9 @@ type DeepValueOf<T> = T extends string
 
 export const TauriCommand = {
   app: {
+    extractArchive: 'extract_archive',
     extractTarBz2: 'extract_tar_bz2',
+    cancelExtraction: 'cancel_extraction',
     downloadFile: 'download_file',
     cancelDownload: 'cancel_download',
     openLogFolder: 'open_log_folder',
@@ -23,6 +25,7 @@ export const TauriCommand = {
     getAsrRuntimeMetrics: 'get_asr_runtime_metrics',
     getPathStatuses: 'get_path_statuses',
     hasActiveDownloads: 'has_active_downloads',
+    ping: 'ping',
     forceExit: 'force_exit',
     updateTrayMenu: 'update_tray_menu',
     setMinimizeToTray: 'set_minimize_to_tray',
@@ -31,6 +34,10 @@ export const TauriCommand = {
   },
   audio: {
     setSystemAudioMute: 'set_system_audio_mute',
+    getSystemAudioMute: 'get_system_audio_mute',
+    setSystemVolume: 'set_system_volume',
+    getSystemVolume: 'get_system_volume',
+    getOutputDevices: 'get_output_devices',
     getSystemAudioDevices: 'get_system_audio_devices',
     startSystemAudioCapture: 'start_system_audio_capture',
     stopSystemAudioCapture: 'stop_system_audio_capture',
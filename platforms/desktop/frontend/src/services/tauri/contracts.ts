@@ -81,11 +81,41 @@ import { TauriCommand, type TauriCommandName } from "./commands";
 
 type AudioDevice = {
   name: string;
+  /** Windows pseudo-device following the default communications or multimedia device. */
+  role?: 'communications' | 'multimedia';
+};
+
+type OutputDevice = {
+  id: string;
+  name: string;
+  isDefault: boolean;
 };
 
 type ExtractTarBz2Args = {
   archivePath: string;
   targetDir: string;
+  include?: string[] | null;
+  resume?: boolean;
+  /** Extract into `targetDir/modelName`, without a lone top-level folder. */
+  modelName?: string | null;
+  stripComponents?: number | null;
+  /** Decompress the whole archive first and fail early if it is corrupt. */
+  verify?: boolean;
+  /**
+   * Like `verify`, and also size the archive so `extract-progress` carries a
+   * `percent`. Roughly doubles the extraction time for bz2.
+   */
+  withProgress?: boolean;
+  /** Lets `cancel_extraction` stop this extraction between entries. */
+  id?: string | null;
+};
+
+type ExtractSummary = {
+  matched: number;
+  skipped: number;
+  resumed: number;
+  filesExtracted: number;
+  bytesWritten: number;
 };
 
 type DownloadFileArgs = {
@@ -93,6 +123,19 @@ type DownloadFileArgs = {
   outputPath: string;
   id: string;
   expectedSha256?: string;
+  /**
+   * Per-download header overrides for picky mirrors and CDNs, extra headers
+   * such as `Authorization: Bearer <token>` for gated models, and how long
+   * the body may go without bytes before the download counts as stalled.
+   */
+  requestOptions?: {
+    userAgent?: string;
+    accept?: string;
+    stallTimeoutMs?: number;
+    headers?: Record<string, string>;
+  };
+  /** Tag for cancelling related downloads together with `cancel_group`. */
+  group?: string;
 };
 
 type UpdateTrayMenuArgs = {
@@ -102,12 +145,19 @@ type UpdateTrayMenuArgs = {
   quitText: string;
   captionText: string;
   captionChecked: boolean;
+  cancelDownloadsText?: string;
+  stopRecordingText?: string;
 };
 
 type StartAudioCaptureArgs = {
   deviceName: string | null;
   instanceId: string;
   outputPath?: string;
+  chunkFrames?: number;
+  inputChannel?: number;
+  agc?: boolean;
+  agcTargetDbfs?: number;
+  captureRingSeconds?: number;
 };
 
 type SetCapturePausedArgs = {
@@ -125,18 +175,26 @@ export type ModelSelectionPaths = CoreModelSelectionPaths;
 export type ModelCatalogSelectedIds = CoreModelCatalogSelectedIds;
 
 type ManualTauriCommandContractMap = {
+  [TauriCommand.app.extractArchive]: {
+    args: ExtractTarBz2Args;
+    result: ExtractSummary;
+  };
   [TauriCommand.app.extractTarBz2]: {
     args: ExtractTarBz2Args;
-    result: void;
+    result: ExtractSummary;
   };
   [TauriCommand.app.downloadFile]: {
     args: DownloadFileArgs;
-    result: void;
+    result: string;
   };
   [TauriCommand.app.cancelDownload]: {
     args: { id: string };
     result: void;
   };
+  [TauriCommand.app.cancelExtraction]: {
+    args: { id: string };
+    result: void;
+  };
   [TauriCommand.app.openLogFolder]: {
     args: undefined;
     result: void;
@@ -217,15 +275,35 @@ type ManualTauriCommandContractMap = {
   };
   [TauriCommand.app.setLogLevel]: {
     args: { level: AppLogLevel };
-    result: void;
+    result: AppLogLevel;
+  };
+  [TauriCommand.app.ping]: {
+    args: undefined;
+    result: number;
   };
   [TauriCommand.app.checkMediaFormats]: {
     args: { paths: string[] };
     result: boolean[];
   };
   [TauriCommand.audio.setSystemAudioMute]: {
-    args: { mute: boolean };
-    result: void;
+    args: { mute: boolean; deviceId?: string | null };
+    result: string;
+  };
+  [TauriCommand.audio.getSystemAudioMute]: {
+    args: { deviceId?: string | null };
+    result: boolean;
+  };
+  [TauriCommand.audio.setSystemVolume]: {
+    args: { level: number; deviceId?: string | null };
+    result: string;
+  };
+  [TauriCommand.audio.getSystemVolume]: {
+    args: { deviceId?: string | null };
+    result: number;
+  };
+  [TauriCommand.audio.getOutputDevices]: {
+    args: undefined;
+    result: OutputDevice[];
   };
   [TauriCommand.audio.getSystemAudioDevices]: {
     args: undefined;
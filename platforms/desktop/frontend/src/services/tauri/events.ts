@@ -7,18 +7,38 @@ type DeepValueOf<T> = T extends string
 export const TauriEvent = {
   app: {
     downloadProgress: 'download-progress',
+    downloadCancelled: 'download-cancelled',
     extractProgress: 'extract-progress',
+    extractComplete: 'extract-complete',
+    extractCancelled: 'extract-cancelled',
+    modelsRelocateProgress: 'models-relocate-progress',
+    downloadExtractProgress: 'download-extract-progress',
+    downloadStarted: 'download-started',
+    downloadRetry: 'download-retry',
+    downloadMirror: 'download-mirror',
+    downloadComplete: 'download-complete',
+    downloadFailed: 'download-failed',
+    downloadPaused: 'download-paused',
+    downloadSlow: 'download-slow',
+    scanProgress: 'scan-progress',
+    systemSuspendPrepared: 'system-suspend-prepared',
+    systemResumed: 'system-resumed',
+    launchDownloadsResumed: 'launch-downloads-resumed',
     batchProgress: 'batch-progress',
   },
   audio: {
     microphonePeak: 'microphone-audio',
     systemPeak: 'system-audio',
+    capturePosition: 'capture-position',
+    trimSilenceProgress: 'trim-silence-progress',
+    permissionDenied: 'audio-permission-denied',
   },
   tray: {
     openSettings: 'open-settings',
     toggleCaption: 'toggle-caption',
     checkUpdates: 'check-updates',
     requestQuit: 'request-quit',
+    stopRecording: 'tray-stop-recording',
   },
   automation: {
     runtimeCandidate: 'automation-runtime-candidate',
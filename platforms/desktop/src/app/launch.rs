@@ -0,0 +1,276 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use tauri::{Emitter, Manager, Runtime};
+
+const RESTART_TO_TRAY_SETTING_KEY: &str = "restartToTray";
+const RESUME_DOWNLOADS_SETTING_KEY: &str = "resumeDownloadsOnLaunch";
+/// Version that last started, so the first launch of a new version can be
+/// told apart from an ordinary start.
+const LAST_LAUNCH_VERSION_SETTING_KEY: &str = "lastLaunchVersion";
+pub(crate) const LAUNCH_DOWNLOADS_RESUMED_EVENT: &str = "launch-downloads-resumed";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchBehavior {
+    /// Start hidden in the tray when the app relaunches after an update.
+    pub restart_to_tray: bool,
+    /// Continue sidecar-tracked partial downloads in the models directory.
+    pub resume_downloads_on_launch: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumedDownload {
+    /// Download id used by the progress and cancel events.
+    pub id: String,
+    pub url: String,
+    pub output_path: String,
+    pub resumed_from: u64,
+    pub total_size: u64,
+}
+
+/// What the launch behavior did for this start of the app.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchReport {
+    pub update_relaunch: bool,
+    pub started_in_tray: bool,
+    pub resumed_downloads: Vec<ResumedDownload>,
+}
+
+/// Launch report kept for the frontend, which is usually not listening yet
+/// when startup events are emitted.
+#[derive(Default)]
+pub struct LaunchReportState(Mutex<LaunchReport>);
+
+impl LaunchReportState {
+    pub(crate) fn report(&self) -> LaunchReport {
+        self.0
+            .lock()
+            .map(|report| report.clone())
+            .unwrap_or_default()
+    }
+
+    fn update(&self, apply: impl FnOnce(&mut LaunchReport)) {
+        if let Ok(mut report) = self.0.lock() {
+            apply(&mut report);
+        }
+    }
+}
+
+fn read_bool_setting<R: Runtime>(app: &tauri::AppHandle<R>, key: &str) -> Result<bool, String> {
+    crate::platform::app_config::get_setting(app, key.to_string())
+        .map(|value| value.and_then(|value| value.as_bool()).unwrap_or(false))
+}
+
+pub(crate) fn get_launch_behavior<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+) -> Result<LaunchBehavior, String> {
+    Ok(LaunchBehavior {
+        restart_to_tray: read_bool_setting(app, RESTART_TO_TRAY_SETTING_KEY)?,
+        resume_downloads_on_launch: read_bool_setting(app, RESUME_DOWNLOADS_SETTING_KEY)?,
+    })
+}
+
+/// Saves the given settings; `None` leaves a setting unchanged.
+pub(crate) fn set_launch_behavior<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    restart_to_tray: Option<bool>,
+    resume_downloads_on_launch: Option<bool>,
+) -> Result<LaunchBehavior, String> {
+    for (key, value) in [
+        (RESTART_TO_TRAY_SETTING_KEY, restart_to_tray),
+        (RESUME_DOWNLOADS_SETTING_KEY, resume_downloads_on_launch),
+    ] {
+        if let Some(value) = value {
+            crate::platform::app_config::set_setting(
+                app,
+                key.to_string(),
+                serde_json::json!(value),
+            )?;
+        }
+    }
+    get_launch_behavior(app)
+}
+
+/// A first launch ever is not an update relaunch; any version change is,
+/// since the updater can also install an older build.
+fn is_update_relaunch(previous_version: Option<&str>, current_version: &str) -> bool {
+    previous_version.is_some_and(|previous| previous != current_version)
+}
+
+/// Records this version as launched and reports whether it is the first
+/// start after an update.
+fn record_launch_version<R: Runtime>(app: &tauri::AppHandle<R>) -> bool {
+    let current = app.package_info().version.to_string();
+    let previous = match crate::platform::app_config::get_setting(
+        app,
+        LAST_LAUNCH_VERSION_SETTING_KEY.to_string(),
+    ) {
+        Ok(value) => value.and_then(|value| value.as_str().map(str::to_string)),
+        Err(error) => {
+            log::warn!("[launch] Failed to read last launch version: {error}");
+            return false;
+        }
+    };
+    if previous.as_deref() != Some(current.as_str())
+        && let Err(error) = crate::platform::app_config::set_setting(
+            app,
+            LAST_LAUNCH_VERSION_SETTING_KEY.to_string(),
+            serde_json::json!(current),
+        )
+    {
+        log::warn!("[launch] Failed to record launch version: {error}");
+    }
+    is_update_relaunch(previous.as_deref(), &current)
+}
+
+/// Partial downloads in `dir` whose sidecar says where to continue from. Each
+/// one is published into `output_dir` under its own name.
+fn resumable_downloads(dir: &Path, output_dir: &Path) -> Result<Vec<ResumedDownload>, String> {
+    let partials =
+        sona_model_downloads::list_partial_downloads(dir).map_err(|error| error.to_string())?;
+    Ok(partials
+        .into_iter()
+        .filter_map(|partial| {
+            let resume = partial.resume?;
+            let temp_name = partial.path.file_name()?.to_str()?;
+            let id = temp_name
+                .strip_suffix(sona_model_downloads::TEMPORARY_DOWNLOAD_SUFFIX)?
+                .to_string();
+            Some(ResumedDownload {
+                output_path: output_dir.join(&id).to_string_lossy().into_owned(),
+                id,
+                url: resume.url,
+                resumed_from: partial.size,
+                total_size: resume.total_size,
+            })
+        })
+        .collect())
+}
+
+fn resume_partial_downloads<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        // Partial files live in the configured temporary directory when
+        // there is one, otherwise next to the models.
+        let temp_dir = app
+            .state::<crate::platform::model_downloads::DownloadState>()
+            .client()
+            .temp_dir()
+            .map(Path::to_path_buf);
+        let downloads = match crate::platform::paths::models_dir_for_app(&app) {
+            Ok(models_dir) => {
+                crate::platform::blocking::spawn_blocking_map(move || {
+                    resumable_downloads(temp_dir.as_deref().unwrap_or(&models_dir), &models_dir)
+                })
+                .await
+            }
+            Err(error) => Err(error),
+        };
+        let downloads = match downloads {
+            Ok(downloads) if !downloads.is_empty() => downloads,
+            Ok(_) => return,
+            Err(error) => {
+                log::warn!("[launch] Failed to list partial downloads: {error}");
+                return;
+            }
+        };
+
+        log::info!("[launch] Resuming {} partial download(s)", downloads.len());
+        app.state::<LaunchReportState>()
+            .update(|report| report.resumed_downloads = downloads.clone());
+        let _ = app.emit(LAUNCH_DOWNLOADS_RESUMED_EVENT, &downloads);
+
+        for download in downloads {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let state = app.state::<crate::platform::model_downloads::DownloadState>();
+                // Failures are logged and reported through the download events.
+                let _ = crate::platform::model_downloads::download_file(
+                    app.clone(),
+                    state,
+                    download.url,
+                    download.output_path,
+                    download.id,
+                    None,
+                    None,
+                    None,
+                    None,
+                )
+                .await;
+            });
+        }
+    });
+}
+
+/// Applies the launch settings at startup: hides the main window after an
+/// update relaunch when asked to, and resumes partial downloads.
+pub(crate) fn apply_launch_behavior<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let update_relaunch = record_launch_version(app);
+    let behavior = match get_launch_behavior(app) {
+        Ok(behavior) => behavior,
+        Err(error) => {
+            log::warn!("[launch] Failed to read launch settings: {error}");
+            LaunchBehavior::default()
+        }
+    };
+
+    let started_in_tray = update_relaunch && behavior.restart_to_tray;
+    if started_in_tray {
+        log::info!("[launch] Relaunched after an update; starting in the tray");
+        crate::app::window_visibility::start_main_window_hidden(app);
+    }
+    app.state::<LaunchReportState>().update(|report| {
+        report.update_relaunch = update_relaunch;
+        report.started_in_tray = started_in_tray;
+    });
+
+    if behavior.resume_downloads_on_launch {
+        resume_partial_downloads(app);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_changed_version_counts_as_an_update_relaunch() {
+        assert!(!is_update_relaunch(None, "0.8.0"));
+        assert!(!is_update_relaunch(Some("0.8.0"), "0.8.0"));
+        assert!(is_update_relaunch(Some("0.8.0"), "0.8.1"));
+        assert!(is_update_relaunch(Some("0.9.0"), "0.8.0"));
+    }
+
+    #[test]
+    fn resumable_downloads_need_a_sidecar() {
+        let dir = tempfile::tempdir().unwrap();
+        let tracked = dir.path().join("model.tar.bz2.download");
+        std::fs::write(&tracked, b"partial").unwrap();
+        std::fs::write(
+            sona_model_downloads::download_state_path(&tracked),
+            br#"{"url":"https://example.com/model.tar.bz2","etag":null,"totalSize":20,"downloaded":7}"#,
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("orphan.bin.download"), b"partial").unwrap();
+
+        let downloads = resumable_downloads(dir.path(), dir.path()).unwrap();
+
+        assert_eq!(
+            downloads,
+            vec![ResumedDownload {
+                id: "model.tar.bz2".to_string(),
+                url: "https://example.com/model.tar.bz2".to_string(),
+                output_path: dir
+                    .path()
+                    .join("model.tar.bz2")
+                    .to_string_lossy()
+                    .into_owned(),
+                resumed_from: 7,
+                total_size: 20,
+            }]
+        );
+    }
+}
@@ -1,6 +1,8 @@
 pub mod dashboard;
+pub mod launch;
 pub mod server;
 pub mod settings;
 pub mod setup;
 pub mod tray;
 pub mod window_state;
+pub mod window_visibility;
@@ -28,6 +28,16 @@ impl AppLogLevel {
             _ => Self::Info,
         }
     }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Trace => "trace",
+            Self::Debug => "debug",
+            Self::Info => "info",
+            Self::Warn => "warn",
+            Self::Error => "error",
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -46,8 +56,9 @@ impl RuntimeLogLevel {
         AppLogLevel::from_rank(self.level.load(Ordering::Relaxed))
     }
 
-    fn set_log_level(&self, level: AppLogLevel) {
-        self.level.store(level.rank(), Ordering::Relaxed);
+    /// Takes effect for the next record; returns the level it replaced.
+    fn set_log_level(&self, level: AppLogLevel) -> AppLogLevel {
+        AppLogLevel::from_rank(self.level.swap(level.rank(), Ordering::Relaxed))
     }
 
     pub(crate) fn should_log(&self, metadata: &log::Metadata<'_>) -> bool {
@@ -90,8 +101,8 @@ impl AppSettings {
         self.log_level.current_log_level()
     }
 
-    fn set_log_level(&self, level: AppLogLevel) {
-        self.log_level.set_log_level(level);
+    fn set_log_level(&self, level: AppLogLevel) -> AppLogLevel {
+        self.log_level.set_log_level(level)
     }
 }
 
@@ -106,14 +117,24 @@ pub(crate) fn set_minimize_to_tray(state: tauri::State<'_, AppSettings>, enabled
     state.set_minimize_to_tray_enabled(enabled);
 }
 
+/// Changes the level of the running log filter, so debug logging can be
+/// turned on to reproduce an issue and back off afterwards. Returns the
+/// previously active level.
 pub(crate) fn set_log_level(
     state: tauri::State<'_, AppSettings>,
     level: String,
-) -> Result<(), String> {
+) -> Result<String, String> {
     let parsed =
         parse_log_level(&level).ok_or_else(|| format!("Unsupported log level: {level}"))?;
-    state.set_log_level(parsed);
-    Ok(())
+    let previous = state.set_log_level(parsed);
+    if previous != parsed {
+        log::info!(
+            "[settings] Log level changed from {} to {}",
+            previous.as_str(),
+            parsed.as_str()
+        );
+    }
+    Ok(previous.as_str().to_string())
 }
 
 pub(crate) fn parse_log_level(level: &str) -> Option<AppLogLevel> {
@@ -212,6 +233,34 @@ mod tests {
         assert_eq!(parse_log_level("verbose"), None);
     }
 
+    #[test]
+    fn setting_the_log_level_returns_the_previous_level() {
+        let settings = AppSettings::new();
+
+        assert_eq!(
+            settings.set_log_level(AppLogLevel::Debug),
+            AppLogLevel::Info
+        );
+        assert_eq!(
+            settings.set_log_level(AppLogLevel::Info),
+            AppLogLevel::Debug
+        );
+        assert_eq!(settings.current_log_level(), AppLogLevel::Info);
+    }
+
+    #[test]
+    fn log_level_names_round_trip() {
+        for level in [
+            AppLogLevel::Trace,
+            AppLogLevel::Debug,
+            AppLogLevel::Info,
+            AppLogLevel::Warn,
+            AppLogLevel::Error,
+        ] {
+            assert_eq!(parse_log_level(level.as_str()), Some(level));
+        }
+    }
+
     #[test]
     fn filters_records_at_or_above_the_configured_level() {
         assert!(should_log_level(AppLogLevel::Info, log::Level::Info));
@@ -18,6 +18,8 @@ pub fn init(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
 
     app.manage(dashboard_service);
     app.manage(sqlite_context);
+    crate::platform::hardware::apply_preferred_gpu(app.handle());
+    crate::app::window_visibility::restore_main_window_always_on_top(app.handle());
 
     let listener_app_handle = app_handle_for_listener.clone();
     app.listen_any("asr-config-updated", move |_event| {
@@ -31,6 +33,8 @@ pub fn init(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     });
 
     crate::app::tray::setup_tray(app)?;
+    crate::platform::model_downloads::restore_download_settings(app.handle());
+    crate::app::launch::apply_launch_behavior(app.handle());
 
     crate::app::server::start_from_app_handle(&app.handle().clone());
 
@@ -4,107 +4,188 @@ pub(crate) const TRAY_OPEN_SETTINGS_EVENT: &str = "open-settings";
 pub(crate) const TRAY_TOGGLE_CAPTION_EVENT: &str = "toggle-caption";
 pub(crate) const TRAY_CHECK_UPDATES_EVENT: &str = "check-updates";
 pub(crate) const TRAY_REQUEST_QUIT_EVENT: &str = "request-quit";
+pub(crate) const TRAY_STOP_RECORDING_EVENT: &str = "tray-stop-recording";
 
-pub(crate) async fn update_tray_menu<R: tauri::Runtime>(
-    app: tauri::AppHandle<R>,
-    show_text: String,
-    settings_text: String,
-    updates_text: String,
-    quit_text: String,
-    caption_text: String,
-    caption_checked: bool,
+const TRAY_ID: &str = "main-tray";
+
+/// Localized tray labels last sent by the frontend, kept so the backend can
+/// rebuild the menu when downloads or captures start and stop.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct TrayMenuLabels {
+    pub show: String,
+    pub settings: String,
+    pub updates: String,
+    pub quit: String,
+    pub caption: String,
+    pub caption_checked: bool,
+    pub cancel_downloads: String,
+    pub stop_recording: String,
+}
+
+impl Default for TrayMenuLabels {
+    fn default() -> Self {
+        Self {
+            show: "Show Main Window".to_string(),
+            settings: "Settings".to_string(),
+            updates: "Check for Updates".to_string(),
+            quit: "Quit".to_string(),
+            caption: "Live Caption".to_string(),
+            caption_checked: false,
+            cancel_downloads: "Cancel Downloads".to_string(),
+            stop_recording: "Stop Recording".to_string(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TrayMenuState {
+    labels: std::sync::Mutex<TrayMenuLabels>,
+}
+
+/// Backend state that decides which optional tray items are shown.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct TrayActivity {
+    downloads_active: bool,
+    capturing: bool,
+}
+
+impl TrayActivity {
+    async fn current<R: tauri::Runtime>(app: &tauri::AppHandle<R>) -> Self {
+        let downloads_active =
+            match app.try_state::<crate::platform::model_downloads::DownloadState>() {
+                Some(state) => state.has_active_downloads().await,
+                None => false,
+            };
+        let capturing = app
+            .try_state::<crate::integrations::audio::AudioState>()
+            .is_some_and(|state| state.is_capturing());
+        Self {
+            downloads_active,
+            capturing,
+        }
+    }
+}
+
+/// Ids and labels of the items shown only while their action applies.
+fn conditional_tray_items(
+    labels: &TrayMenuLabels,
+    activity: TrayActivity,
+) -> Vec<(&'static str, &str)> {
+    let mut items = Vec::new();
+    if activity.capturing {
+        items.push(("stop_recording", labels.stop_recording.as_str()));
+    }
+    if activity.downloads_active {
+        items.push(("cancel_downloads", labels.cancel_downloads.as_str()));
+    }
+    items
+}
+
+#[cfg(desktop)]
+fn build_tray_menu<R: tauri::Runtime, M: Manager<R>>(
+    manager: &M,
+    labels: &TrayMenuLabels,
+    activity: TrayActivity,
+) -> tauri::Result<tauri::menu::Menu<R>> {
+    use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem};
+
+    let show_i = MenuItem::with_id(manager, "show", &labels.show, true, None::<&str>)?;
+    let caption_i = CheckMenuItem::with_id(
+        manager,
+        "toggle_caption",
+        &labels.caption,
+        true,
+        labels.caption_checked,
+        None::<&str>,
+    )?;
+    let conditional_items = conditional_tray_items(labels, activity)
+        .into_iter()
+        .map(|(id, text)| MenuItem::with_id(manager, id, text, true, None::<&str>))
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let settings_i = MenuItem::with_id(manager, "settings", &labels.settings, true, None::<&str>)?;
+    let updates_i = MenuItem::with_id(
+        manager,
+        "check_updates",
+        &labels.updates,
+        true,
+        None::<&str>,
+    )?;
+    let separator = PredefinedMenuItem::separator(manager)?;
+    let quit_i = MenuItem::with_id(manager, "quit", &labels.quit, true, None::<&str>)?;
+
+    let mut items: Vec<&dyn IsMenuItem<R>> = vec![&show_i, &caption_i];
+    items.extend(
+        conditional_items
+            .iter()
+            .map(|item| item as &dyn IsMenuItem<R>),
+    );
+    items.extend([
+        &settings_i as &dyn IsMenuItem<R>,
+        &updates_i,
+        &separator,
+        &quit_i,
+    ]);
+    Menu::with_items(manager, &items)
+}
+
+/// Rebuilds the tray menu from the stored labels and current backend state.
+pub(crate) async fn refresh_tray_menu<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
 ) -> Result<(), String> {
     #[cfg(desktop)]
     {
-        use tauri::menu::{CheckMenuItem, Menu, MenuItem};
-        let show_i = MenuItem::with_id(&app, "show", &show_text, true, None::<&str>)
-            .map_err(|e| e.to_string())?;
-        let caption_i = CheckMenuItem::with_id(
-            &app,
-            "toggle_caption",
-            &caption_text,
-            true,
-            caption_checked,
-            None::<&str>,
-        )
-        .map_err(|e| e.to_string())?;
-        let settings_i = MenuItem::with_id(&app, "settings", &settings_text, true, None::<&str>)
-            .map_err(|e| e.to_string())?;
-        let updates_i = MenuItem::with_id(&app, "check_updates", &updates_text, true, None::<&str>)
-            .map_err(|e| e.to_string())?;
-        let quit_i = MenuItem::with_id(&app, "quit", &quit_text, true, None::<&str>)
-            .map_err(|e| e.to_string())?;
-
-        let menu = Menu::with_items(
-            &app,
-            &[
-                &show_i,
-                &caption_i,
-                &settings_i,
-                &updates_i,
-                &tauri::menu::PredefinedMenuItem::separator(&app).map_err(|e| e.to_string())?,
-                &quit_i,
-            ],
-        )
-        .map_err(|e| e.to_string())?;
-
-        if let Some(tray) = app.tray_by_id("main-tray") {
-            tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
-        }
+        let Some(tray) = app.tray_by_id(TRAY_ID) else {
+            return Ok(());
+        };
+        let labels = match app.try_state::<TrayMenuState>() {
+            Some(state) => state.labels.lock().map_err(|e| e.to_string())?.clone(),
+            None => TrayMenuLabels::default(),
+        };
+        let activity = TrayActivity::current(app).await;
+        let menu = build_tray_menu(app, &labels, activity).map_err(|e| e.to_string())?;
+        tray.set_menu(Some(menu)).map_err(|e| e.to_string())?;
     }
     Ok(())
 }
 
+/// Queues a tray menu rebuild from code that cannot await it.
+pub(crate) fn schedule_tray_menu_refresh<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Err(error) = refresh_tray_menu(&app).await {
+            log::warn!("[tray] Failed to refresh tray menu: {error}");
+        }
+    });
+}
+
+pub(crate) async fn update_tray_menu<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    labels: TrayMenuLabels,
+) -> Result<(), String> {
+    if let Some(state) = app.try_state::<TrayMenuState>() {
+        *state.labels.lock().map_err(|e| e.to_string())? = labels;
+    }
+    refresh_tray_menu(&app).await
+}
+
 pub(crate) fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     #[cfg(desktop)]
     {
         use tauri::image::Image;
-        use tauri::menu::{CheckMenuItem, Menu, MenuItem};
         use tauri::tray::TrayIconBuilder;
 
-        let show_i = MenuItem::with_id(app, "show", "Show Main Window", true, None::<&str>)?;
-        let caption_i = CheckMenuItem::with_id(
-            app,
-            "toggle_caption",
-            "Live Caption",
-            true,
-            false,
-            None::<&str>,
-        )?;
-        let settings_i = MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?;
-        let updates_i = MenuItem::with_id(
-            app,
-            "check_updates",
-            "Check for Updates",
-            true,
-            None::<&str>,
-        )?;
-        let quit_i = MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?;
-
-        let menu = Menu::with_items(
-            app,
-            &[
-                &show_i,
-                &caption_i,
-                &settings_i,
-                &updates_i,
-                &tauri::menu::PredefinedMenuItem::separator(app)?,
-                &quit_i,
-            ],
-        )?;
+        let menu = build_tray_menu(app, &TrayMenuLabels::default(), TrayActivity::default())?;
 
         let icon = Image::from_bytes(include_bytes!("../../icons/128x128.png"))?;
 
-        let _tray = TrayIconBuilder::with_id("main-tray")
+        let _tray = TrayIconBuilder::with_id(TRAY_ID)
             .icon(icon)
             .menu(&menu)
             .show_menu_on_left_click(false)
             .on_menu_event(move |app, event| match event.id.as_ref() {
                 "show" => {
                     if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.unminimize();
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                        crate::app::window_visibility::show_main_window(&window);
                     }
                 }
                 "toggle_caption" => {
@@ -114,25 +195,31 @@ pub(crate) fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error:
                 }
                 "settings" => {
                     if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.unminimize();
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                        crate::app::window_visibility::show_main_window(&window);
                         let _ = window.emit(TRAY_OPEN_SETTINGS_EVENT, ());
                     }
                 }
                 "check_updates" => {
                     if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.unminimize();
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                        crate::app::window_visibility::show_main_window(&window);
                         let _ = window.emit(TRAY_CHECK_UPDATES_EVENT, ());
                     }
                 }
+                "stop_recording" => {
+                    if let Some(window) = app.get_webview_window("main") {
+                        let _ = window.emit(TRAY_STOP_RECORDING_EVENT, ());
+                    }
+                }
+                "cancel_downloads" => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let state = app.state::<crate::platform::model_downloads::DownloadState>();
+                        let _ = crate::platform::model_downloads::cancel_all_downloads(state).await;
+                    });
+                }
                 "quit" => {
                     if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.unminimize();
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                        crate::app::window_visibility::show_main_window(&window);
                         let _ = window.emit(TRAY_REQUEST_QUIT_EVENT, ());
                     }
                 }
@@ -154,9 +241,7 @@ pub(crate) fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error:
                 if should_show {
                     let app = tray.app_handle();
                     if let Some(window) = app.get_webview_window("main") {
-                        let _ = window.unminimize();
-                        let _ = window.show();
-                        let _ = window.set_focus();
+                        crate::app::window_visibility::show_main_window(&window);
                     }
                 }
             })
@@ -164,3 +249,28 @@ pub(crate) fn setup_tray(app: &mut tauri::App) -> Result<(), Box<dyn std::error:
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conditional_tray_items_follow_backend_activity() {
+        let labels = TrayMenuLabels::default();
+
+        assert!(conditional_tray_items(&labels, TrayActivity::default()).is_empty());
+        assert_eq!(
+            conditional_tray_items(
+                &labels,
+                TrayActivity {
+                    downloads_active: true,
+                    capturing: true,
+                },
+            ),
+            vec![
+                ("stop_recording", "Stop Recording"),
+                ("cancel_downloads", "Cancel Downloads"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,183 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{Emitter, Manager, Runtime};
+
+pub(crate) const WINDOW_HIDDEN_EVENT: &str = "window-hidden";
+pub(crate) const WINDOW_SHOWN_EVENT: &str = "window-shown";
+pub(crate) const WINDOW_MINIMIZED_EVENT: &str = "window-minimized";
+pub(crate) const WINDOW_RESTORED_EVENT: &str = "window-restored";
+const ALWAYS_ON_TOP_SETTING_KEY: &str = "alwaysOnTop";
+
+/// Last visibility reported to the frontend for the main window, so each
+/// transition is emitted once even when several code paths show or hide it.
+#[derive(Default)]
+pub struct MainWindowVisibility {
+    hidden: AtomicBool,
+    minimized: AtomicBool,
+    always_on_top: AtomicBool,
+}
+
+impl MainWindowVisibility {
+    fn hidden_transition(&self, hidden: bool) -> Option<&'static str> {
+        if self.hidden.swap(hidden, Ordering::SeqCst) == hidden {
+            return None;
+        }
+        Some(if hidden {
+            WINDOW_HIDDEN_EVENT
+        } else {
+            WINDOW_SHOWN_EVENT
+        })
+    }
+
+    fn minimized_transition(&self, minimized: bool) -> Option<&'static str> {
+        if self.minimized.swap(minimized, Ordering::SeqCst) == minimized {
+            return None;
+        }
+        Some(if minimized {
+            WINDOW_MINIMIZED_EVENT
+        } else {
+            WINDOW_RESTORED_EVENT
+        })
+    }
+}
+
+fn emit_transition<R: Runtime>(app: &tauri::AppHandle<R>, event: Option<&'static str>) {
+    if let Some(event) = event {
+        let _ = app.emit(event, ());
+    }
+}
+
+/// Unminimizes, shows and focuses the main window, then reports the change.
+pub(crate) fn show_main_window<R: Runtime>(window: &tauri::WebviewWindow<R>) {
+    let _ = window.unminimize();
+    let _ = window.show();
+    let _ = window.set_focus();
+
+    let app = window.app_handle();
+    let visibility = app.state::<MainWindowVisibility>();
+    // Some window managers drop the topmost flag while the window is hidden.
+    if visibility.always_on_top.load(Ordering::SeqCst) {
+        let _ = window.set_always_on_top(true);
+    }
+    emit_transition(app, visibility.minimized_transition(false));
+    emit_transition(app, visibility.hidden_transition(false));
+}
+
+/// Keeps the main window above other apps and persists the choice.
+pub(crate) fn set_main_window_always_on_top<R: Runtime>(
+    app: &tauri::AppHandle<R>,
+    enabled: bool,
+) -> Result<(), String> {
+    let window = app
+        .get_webview_window("main")
+        .ok_or_else(|| "Main window is not available".to_string())?;
+    window
+        .set_always_on_top(enabled)
+        .map_err(|error| error.to_string())?;
+    app.state::<MainWindowVisibility>()
+        .always_on_top
+        .store(enabled, Ordering::SeqCst);
+    crate::platform::app_config::set_setting(
+        app,
+        ALWAYS_ON_TOP_SETTING_KEY.to_string(),
+        serde_json::json!(enabled),
+    )
+}
+
+/// Reapplies the saved always-on-top choice at launch.
+pub(crate) fn restore_main_window_always_on_top<R: Runtime>(app: &tauri::AppHandle<R>) {
+    let enabled = match crate::platform::app_config::get_setting(
+        app,
+        ALWAYS_ON_TOP_SETTING_KEY.to_string(),
+    ) {
+        Ok(value) => value.and_then(|value| value.as_bool()).unwrap_or(false),
+        Err(error) => {
+            log::warn!("[window] Failed to read always-on-top setting: {error}");
+            return;
+        }
+    };
+    if !enabled {
+        return;
+    }
+
+    app.state::<MainWindowVisibility>()
+        .always_on_top
+        .store(true, Ordering::SeqCst);
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_always_on_top(true);
+    }
+}
+
+/// Hides the main window to the tray and reports the change.
+pub(crate) fn hide_main_window<R: Runtime>(window: &tauri::Window<R>) {
+    let _ = window.hide();
+
+    let app = window.app_handle();
+    emit_transition(
+        app,
+        app.state::<MainWindowVisibility>().hidden_transition(true),
+    );
+}
+
+/// Hides the main window during startup so the app comes up in the tray.
+pub(crate) fn start_main_window_hidden<R: Runtime>(app: &tauri::AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    emit_transition(
+        app,
+        app.state::<MainWindowVisibility>().hidden_transition(true),
+    );
+}
+
+/// Tauri has no dedicated minimize event, so resize events re-check the
+/// minimized flag and report transitions.
+pub(crate) fn sync_main_window_minimized<R: Runtime>(window: &tauri::Window<R>) {
+    let Ok(minimized) = window.is_minimized() else {
+        return;
+    };
+
+    let app = window.app_handle();
+    emit_transition(
+        app,
+        app.state::<MainWindowVisibility>()
+            .minimized_transition(minimized),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn visibility_transitions_are_reported_once() {
+        let visibility = MainWindowVisibility::default();
+
+        assert_eq!(visibility.hidden_transition(false), None);
+        assert_eq!(
+            visibility.hidden_transition(true),
+            Some(WINDOW_HIDDEN_EVENT)
+        );
+        assert_eq!(visibility.hidden_transition(true), None);
+        assert_eq!(
+            visibility.hidden_transition(false),
+            Some(WINDOW_SHOWN_EVENT)
+        );
+    }
+
+    #[test]
+    fn minimize_transitions_are_reported_once() {
+        let visibility = MainWindowVisibility::default();
+
+        assert_eq!(
+            visibility.minimized_transition(true),
+            Some(WINDOW_MINIMIZED_EVENT)
+        );
+        assert_eq!(visibility.minimized_transition(true), None);
+        assert_eq!(
+            visibility.minimized_transition(false),
+            Some(WINDOW_RESTORED_EVENT)
+        );
+        assert_eq!(visibility.minimized_transition(false), None);
+    }
+}
@@ -1,13 +1,78 @@
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn extract_archive<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    archive_path: String,
+    target_dir: String,
+    include: Option<Vec<String>>,
+    resume: Option<bool>,
+    model_name: Option<String>,
+    strip_components: Option<u32>,
+    verify: Option<bool>,
+    with_progress: Option<bool>,
+    id: Option<String>,
+) -> Result<sona_archive::ExtractSummary, String> {
+    crate::platform::archive::extract_archive(
+        app,
+        archive_path,
+        target_dir,
+        include,
+        resume.unwrap_or(false),
+        model_name,
+        strip_components,
+        verify.unwrap_or(false),
+        with_progress.unwrap_or(false),
+        id,
+    )
+    .await
+}
+
+/// Former name of [`extract_archive`], which also reads `.tar.bz2` files.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn extract_tar_bz2<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     archive_path: String,
     target_dir: String,
+    include: Option<Vec<String>>,
+    resume: Option<bool>,
+    model_name: Option<String>,
+    strip_components: Option<u32>,
+    verify: Option<bool>,
+    with_progress: Option<bool>,
+    id: Option<String>,
+) -> Result<sona_archive::ExtractSummary, String> {
+    extract_archive(
+        app,
+        archive_path,
+        target_dir,
+        include,
+        resume,
+        model_name,
+        strip_components,
+        verify,
+        with_progress,
+        id,
+    )
+    .await
+}
+
+#[tauri::command]
+pub fn cancel_extraction(
+    state: tauri::State<'_, crate::platform::model_downloads::DownloadState>,
+    id: String,
 ) -> Result<(), String> {
-    crate::platform::archive::extract_tar_bz2(app, archive_path, target_dir).await
+    crate::platform::archive::cancel_extraction(state, id)
 }
 
 #[tauri::command]
 pub async fn create_tar_bz2(source_dir: String, archive_path: String) -> Result<(), String> {
     crate::platform::archive::create_tar_bz2(source_dir, archive_path).await
 }
+
+#[tauri::command]
+pub async fn get_extraction_checkpoint(
+    archive_path: String,
+) -> Result<Option<sona_archive::ExtractionCheckpoint>, String> {
+    crate::platform::archive::get_extraction_checkpoint(archive_path).await
+}
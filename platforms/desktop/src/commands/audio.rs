@@ -1,4 +1,9 @@
-use crate::integrations::audio::{AudioDevice, AudioState};
+use crate::integrations::audio::{
+    AudioDevice, AudioState, CaptureConfigOptions, CaptureLatency, ResolvedCaptureConfig,
+    RunningCapturePayload, SelfTestReport,
+};
+use crate::platform::system_audio::OutputDevice;
+use sona_core::runtime::capture::{CaptureBackend, LinuxAudioBackend};
 use tauri::{AppHandle, State, Window};
 
 #[tauri::command(async)]
@@ -12,6 +17,46 @@ pub fn get_microphone_devices() -> Result<Vec<AudioDevice>, String> {
 }
 
 #[tauri::command(async)]
+pub fn get_capture_backends() -> Vec<CaptureBackend> {
+    crate::integrations::audio::get_capture_backends()
+}
+
+#[tauri::command(async)]
+pub fn detect_linux_audio_backend() -> Option<LinuxAudioBackend> {
+    crate::integrations::audio::detect_linux_audio_backend()
+}
+
+#[tauri::command(async)]
+pub fn get_supported_record_codecs() -> Result<Vec<&'static str>, String> {
+    crate::integrations::audio::get_supported_record_codecs()
+}
+
+#[tauri::command(async)]
+pub fn resolve_capture_config(
+    device_name: Option<String>,
+    options: Option<CaptureConfigOptions>,
+) -> Result<ResolvedCaptureConfig, String> {
+    crate::integrations::audio::resolve_capture_config(device_name, options.unwrap_or_default())
+}
+
+#[tauri::command(async)]
+pub fn measure_capture_latency(
+    device_name: Option<String>,
+    chunk_frames: Option<u32>,
+) -> Result<CaptureLatency, String> {
+    crate::integrations::audio::measure_capture_latency(device_name, chunk_frames)
+}
+
+#[tauri::command]
+pub async fn run_capture_selftest(
+    window: Window,
+    chunk_frames: Option<u32>,
+) -> Result<SelfTestReport, String> {
+    crate::integrations::audio::run_capture_selftest(window, chunk_frames).await
+}
+
+#[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 pub fn start_system_audio_capture(
     app: AppHandle,
     window: Window,
@@ -20,7 +65,20 @@ pub fn start_system_audio_capture(
     device_name: Option<String>,
     instance_id: String,
     output_path: Option<String>,
+    record_codec: Option<String>,
+    chunk_frames: Option<u32>,
+    input_channel: Option<u16>,
+    agc: Option<bool>,
+    agc_target_dbfs: Option<f32>,
+    capture_ring_seconds: Option<u32>,
+    wait_for_audio: Option<bool>,
+    pipe_path: Option<String>,
+    pipe_format: Option<String>,
+    pipe_sample_rate: Option<u32>,
+    pipe_channels: Option<u16>,
+    pipe_sample_format: Option<String>,
 ) -> Result<(), String> {
+    let app_for_tray = app.clone();
     crate::integrations::audio::start_system_audio_capture(
         app,
         window,
@@ -29,10 +87,25 @@ pub fn start_system_audio_capture(
         device_name,
         instance_id,
         output_path,
-    )
+        record_codec,
+        chunk_frames,
+        input_channel,
+        agc,
+        agc_target_dbfs,
+        capture_ring_seconds,
+        wait_for_audio,
+        pipe_path,
+        pipe_format,
+        pipe_sample_rate,
+        pipe_channels,
+        pipe_sample_format,
+    )?;
+    crate::app::tray::schedule_tray_menu_refresh(&app_for_tray);
+    Ok(())
 }
 
 #[tauri::command(async)]
+#[allow(clippy::too_many_arguments)]
 pub fn start_microphone_capture(
     app: AppHandle,
     window: Window,
@@ -41,7 +114,20 @@ pub fn start_microphone_capture(
     device_name: Option<String>,
     instance_id: String,
     output_path: Option<String>,
+    record_codec: Option<String>,
+    chunk_frames: Option<u32>,
+    input_channel: Option<u16>,
+    agc: Option<bool>,
+    agc_target_dbfs: Option<f32>,
+    capture_ring_seconds: Option<u32>,
+    wait_for_audio: Option<bool>,
+    pipe_path: Option<String>,
+    pipe_format: Option<String>,
+    pipe_sample_rate: Option<u32>,
+    pipe_channels: Option<u16>,
+    pipe_sample_format: Option<String>,
 ) -> Result<(), String> {
+    let app_for_tray = app.clone();
     crate::integrations::audio::start_microphone_capture(
         app,
         window,
@@ -50,23 +136,88 @@ pub fn start_microphone_capture(
         device_name,
         instance_id,
         output_path,
-    )
+        record_codec,
+        chunk_frames,
+        input_channel,
+        agc,
+        agc_target_dbfs,
+        capture_ring_seconds,
+        wait_for_audio,
+        pipe_path,
+        pipe_format,
+        pipe_sample_rate,
+        pipe_channels,
+        pipe_sample_format,
+    )?;
+    crate::app::tray::schedule_tray_menu_refresh(&app_for_tray);
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn stop_system_audio_capture(
+    app: AppHandle,
     state: State<'_, AudioState>,
     instance_id: String,
 ) -> Result<String, String> {
-    crate::integrations::audio::stop_system_audio_capture(state, instance_id).await
+    let result = crate::integrations::audio::stop_system_audio_capture(state, instance_id).await;
+    crate::app::tray::schedule_tray_menu_refresh(&app);
+    result
 }
 
 #[tauri::command]
 pub async fn stop_microphone_capture(
+    app: AppHandle,
     state: State<'_, AudioState>,
     instance_id: String,
 ) -> Result<String, String> {
-    crate::integrations::audio::stop_microphone_capture(state, instance_id).await
+    let result = crate::integrations::audio::stop_microphone_capture(state, instance_id).await;
+    crate::app::tray::schedule_tray_menu_refresh(&app);
+    result
+}
+
+#[tauri::command]
+pub async fn stop_all_audio_captures(
+    app: AppHandle,
+    state: State<'_, AudioState>,
+) -> Result<(), String> {
+    let result = crate::integrations::audio::stop_all_audio_captures(state).await;
+    crate::app::tray::schedule_tray_menu_refresh(&app);
+    result
+}
+
+#[tauri::command]
+pub async fn dump_ring_buffer(
+    state: State<'_, AudioState>,
+    output_path: String,
+    source: Option<String>,
+) -> Result<String, String> {
+    crate::integrations::audio::dump_ring_buffer(state, output_path, source).await
+}
+
+#[tauri::command]
+pub async fn finalize_recording(
+    state: State<'_, AudioState>,
+    source: Option<String>,
+) -> Result<Option<crate::integrations::audio::RecordingInfo>, String> {
+    crate::integrations::audio::finalize_recording(state, source).await
+}
+
+#[tauri::command]
+pub async fn trim_silence(
+    app: AppHandle,
+    input_path: String,
+    output_path: String,
+    threshold_db: Option<f64>,
+    min_silence_ms: Option<u32>,
+) -> Result<f64, String> {
+    crate::integrations::audio::trim_silence(
+        app,
+        input_path,
+        output_path,
+        threshold_db,
+        min_silence_ms,
+    )
+    .await
 }
 
 #[tauri::command]
@@ -87,12 +238,52 @@ pub fn set_microphone_capture_paused(
     crate::integrations::audio::set_microphone_capture_paused(state, instance_id, paused)
 }
 
+#[tauri::command]
+pub fn reconnect_capture(
+    state: State<'_, AudioState>,
+) -> Result<Vec<RunningCapturePayload>, String> {
+    crate::integrations::audio::reconnect_capture(state)
+}
+
 #[tauri::command]
 pub fn set_microphone_boost(state: State<'_, AudioState>, boost: f32) -> Result<(), String> {
     crate::integrations::audio::set_microphone_boost(state, boost)
 }
 
 #[tauri::command]
-pub async fn set_system_audio_mute(mute: bool) -> Result<(), String> {
-    crate::platform::system_audio::set_system_audio_mute(mute).await
+pub async fn get_output_devices() -> Result<Vec<OutputDevice>, String> {
+    crate::platform::system_audio::get_output_devices().await
+}
+
+#[tauri::command]
+pub async fn get_default_output_device() -> Result<Option<OutputDevice>, String> {
+    crate::platform::system_audio::get_default_output_device().await
+}
+
+#[tauri::command]
+pub async fn set_default_output_device(device_id: String) -> Result<OutputDevice, String> {
+    crate::platform::system_audio::set_default_output_device(device_id).await
+}
+
+#[tauri::command]
+pub async fn set_system_audio_mute(
+    mute: bool,
+    device_id: Option<String>,
+) -> Result<String, String> {
+    crate::platform::system_audio::set_system_audio_mute(mute, device_id).await
+}
+
+#[tauri::command]
+pub async fn get_system_audio_mute(device_id: Option<String>) -> Result<bool, String> {
+    crate::platform::system_audio::get_system_audio_mute(device_id).await
+}
+
+#[tauri::command]
+pub async fn set_system_volume(level: f32, device_id: Option<String>) -> Result<String, String> {
+    crate::platform::system_audio::set_system_volume(level, device_id).await
+}
+
+#[tauri::command]
+pub async fn get_system_volume(device_id: Option<String>) -> Result<f32, String> {
+    crate::platform::system_audio::get_system_volume(device_id).await
 }
@@ -1,4 +1,7 @@
-use crate::platform::model_downloads::DownloadState;
+use crate::platform::model_downloads::{
+    DownloadFailure, DownloadState, NetworkPolicyPayload, PartialDownloadInfo,
+    SlowDownloadThreshold,
+};
 
 #[tauri::command]
 pub async fn cancel_download(
@@ -8,12 +11,239 @@ pub async fn cancel_download(
     crate::platform::model_downloads::cancel_download(state, id).await
 }
 
+#[tauri::command]
+pub async fn cancel_all_downloads(state: tauri::State<'_, DownloadState>) -> Result<(), String> {
+    crate::platform::model_downloads::cancel_all_downloads(state).await
+}
+
+#[tauri::command]
+pub async fn cancel_group(
+    state: tauri::State<'_, DownloadState>,
+    group: String,
+) -> Result<usize, String> {
+    crate::platform::model_downloads::cancel_group(state, group).await
+}
+
+#[tauri::command]
+pub async fn pause_all_downloads(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    crate::platform::model_downloads::pause_all_downloads(app).await
+}
+
+#[tauri::command]
+pub async fn resume_all_downloads(app: tauri::AppHandle) -> Result<Vec<String>, String> {
+    crate::platform::model_downloads::resume_all_downloads(app).await
+}
+
+#[tauri::command]
+pub async fn pause_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    crate::platform::model_downloads::pause_download(app, id).await
+}
+
+#[tauri::command]
+pub async fn resume_download(app: tauri::AppHandle, id: String) -> Result<(), String> {
+    crate::platform::model_downloads::resume_download(app, id).await
+}
+
+#[tauri::command]
+pub async fn list_partial_downloads(dir: String) -> Result<Vec<PartialDownloadInfo>, String> {
+    crate::platform::model_downloads::list_partial_downloads(dir).await
+}
+
+#[tauri::command]
+pub async fn clean_partial_downloads(
+    state: tauri::State<'_, DownloadState>,
+    dir: String,
+) -> Result<Vec<PartialDownloadInfo>, String> {
+    crate::platform::model_downloads::clean_partial_downloads(state, dir).await
+}
+
+#[tauri::command]
+pub fn set_network_policy(
+    state: tauri::State<'_, DownloadState>,
+    connect_timeout_ms: Option<u64>,
+    read_idle_timeout_ms: Option<u64>,
+    max_retries: Option<u32>,
+    backoff_base_ms: Option<u64>,
+    max_backoff_ms: Option<u64>,
+) -> Result<NetworkPolicyPayload, String> {
+    crate::platform::model_downloads::set_network_policy(
+        state,
+        connect_timeout_ms,
+        read_idle_timeout_ms,
+        max_retries,
+        backoff_base_ms,
+        max_backoff_ms,
+    )
+}
+
+#[tauri::command]
+pub fn clear_network_cache(state: tauri::State<'_, DownloadState>) -> Result<(), String> {
+    crate::platform::model_downloads::clear_network_cache(state)
+}
+
+#[tauri::command]
+pub fn get_network_stats() -> sona_model_downloads::NetworkStats {
+    sona_model_downloads::network_stats()
+}
+
+#[tauri::command]
+pub fn get_overall_progress(
+    state: tauri::State<'_, DownloadState>,
+) -> crate::platform::overall_progress::OverallProgress {
+    state.overall_progress()
+}
+
+#[tauri::command]
+pub fn get_download_summary(
+    state: tauri::State<'_, DownloadState>,
+) -> crate::platform::overall_progress::DownloadSummary {
+    state.download_summary()
+}
+
+#[tauri::command]
+pub fn set_download_temp_dir(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    dir: Option<String>,
+) -> Result<Option<String>, String> {
+    crate::platform::model_downloads::set_download_temp_dir(&app, state, dir)
+}
+
+#[tauri::command]
+pub fn set_allowed_download_hosts(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    hosts: Vec<String>,
+) -> Result<Vec<String>, String> {
+    crate::platform::model_downloads::set_allowed_download_hosts(&app, state, hosts)
+}
+
+#[tauri::command]
+pub fn set_download_user_agent(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    user_agent: Option<String>,
+) -> Result<String, String> {
+    crate::platform::model_downloads::set_download_user_agent(&app, state, user_agent)
+}
+
+#[tauri::command]
+pub fn set_download_proxy(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    proxy_url: Option<String>,
+) -> Result<Option<String>, String> {
+    crate::platform::model_downloads::set_download_proxy(&app, state, proxy_url)
+}
+
+#[tauri::command]
+pub fn set_ip_preference(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    preference: sona_model_downloads::IpPreference,
+) -> Result<NetworkPolicyPayload, String> {
+    crate::platform::model_downloads::set_ip_preference(&app, state, preference)
+}
+
+#[tauri::command]
+pub async fn test_ip_connectivity(
+    host: String,
+) -> Result<sona_model_downloads::IpConnectivity, String> {
+    crate::platform::model_downloads::test_ip_connectivity(host).await
+}
+
+#[tauri::command]
+pub async fn scan_models(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    dir: String,
+    manifests: Vec<sona_model_downloads::ModelManifest>,
+) -> Result<sona_model_downloads::ModelScanReport, String> {
+    crate::platform::model_downloads::scan_models(app, state, dir, manifests).await
+}
+
+#[tauri::command]
+pub fn cancel_model_scan(state: tauri::State<'_, DownloadState>) -> Result<(), String> {
+    crate::platform::model_downloads::cancel_model_scan(state)
+}
+
+#[tauri::command]
+pub async fn export_model_info(dir: String) -> Result<String, String> {
+    crate::platform::model_downloads::export_model_info(dir).await
+}
+
+#[tauri::command]
+pub async fn download_to_memory(
+    state: tauri::State<'_, DownloadState>,
+    url: String,
+    id: String,
+    max_bytes: Option<u64>,
+) -> Result<Vec<u8>, String> {
+    crate::platform::model_downloads::download_to_memory(state, url, id, max_bytes).await
+}
+
+#[tauri::command]
+pub async fn check_connectivity(
+    state: tauri::State<'_, DownloadState>,
+    url: String,
+) -> Result<bool, String> {
+    crate::platform::model_downloads::check_connectivity(state, url).await
+}
+
+#[tauri::command]
+pub async fn verify_remote_file(
+    state: tauri::State<'_, DownloadState>,
+    url: String,
+    path: String,
+) -> Result<sona_model_downloads::RemoteVerification, String> {
+    crate::platform::model_downloads::verify_remote_file(state, url, path).await
+}
+
+#[tauri::command]
+pub async fn relocate_models_dir(
+    app: tauri::AppHandle,
+    state: tauri::State<'_, DownloadState>,
+    from: String,
+    to: String,
+) -> Result<sona_runtime_fs::DirectoryCopySummary, String> {
+    crate::platform::model_downloads::relocate_models_dir(app, state, from, to).await
+}
+
+#[tauri::command]
+pub async fn validate_model_dir(
+    dir: String,
+    required_files: Vec<String>,
+) -> Result<sona_runtime_fs::ModelDirValidationReport, String> {
+    crate::platform::model_downloads::validate_model_dir(dir, required_files).await
+}
+
+#[tauri::command]
+pub async fn flush_and_verify<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    path: String,
+    expected_sha256: String,
+) -> Result<(), String> {
+    crate::platform::model_downloads::flush_and_verify(app, path, expected_sha256).await
+}
+
 #[tauri::command]
 pub async fn has_active_downloads(state: tauri::State<'_, DownloadState>) -> Result<bool, String> {
     crate::platform::model_downloads::has_active_downloads(state).await
 }
 
 #[tauri::command]
+pub async fn download_and_extract<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    state: tauri::State<'_, DownloadState>,
+    url: String,
+    target_dir: String,
+    id: String,
+) -> Result<sona_model_downloads::StreamExtractSummary, String> {
+    crate::platform::model_downloads::download_and_extract(app, state, url, target_dir, id).await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn download_file<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     state: tauri::State<'_, DownloadState>,
@@ -21,7 +251,10 @@ pub async fn download_file<R: tauri::Runtime>(
     output_path: String,
     id: String,
     expected_sha256: Option<String>,
-) -> Result<(), String> {
+    slow_threshold: Option<SlowDownloadThreshold>,
+    request_options: Option<sona_model_downloads::RequestOptions>,
+    group: Option<String>,
+) -> Result<String, DownloadFailure> {
     crate::platform::model_downloads::download_file(
         app,
         state,
@@ -29,6 +262,20 @@ pub async fn download_file<R: tauri::Runtime>(
         output_path,
         id,
         expected_sha256,
+        slow_threshold,
+        request_options,
+        group,
     )
     .await
 }
+
+#[tauri::command]
+pub async fn robust_download<R: tauri::Runtime>(
+    app: tauri::AppHandle<R>,
+    state: tauri::State<'_, DownloadState>,
+    spec: sona_model_downloads::DownloadSpec,
+    id: String,
+    slow_threshold: Option<SlowDownloadThreshold>,
+) -> Result<(), String> {
+    crate::platform::model_downloads::robust_download(app, state, spec, id, slow_threshold).await
+}
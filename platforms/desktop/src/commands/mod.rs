@@ -14,8 +14,12 @@ pub mod tag;
 pub fn get_handlers() -> impl Fn(tauri::ipc::Invoke) -> bool {
     tauri::generate_handler![
         crate::commands::system::greet,
+        crate::commands::system::ping,
+        crate::commands::archive::extract_archive,
         crate::commands::archive::extract_tar_bz2,
+        crate::commands::archive::cancel_extraction,
         crate::commands::archive::create_tar_bz2,
+        crate::commands::archive::get_extraction_checkpoint,
         crate::commands::system::get_dashboard_snapshot,
         crate::commands::tag::tag_list,
         crate::commands::tag::tag_save_all,
@@ -27,6 +31,8 @@ pub fn get_handlers() -> impl Fn(tauri::ipc::Invoke) -> bool {
         crate::commands::tag::tag_set_active_id,
         crate::commands::storage::storage_get_usage_snapshot,
         crate::commands::storage::storage_clear_webview_browsing_data,
+        crate::commands::storage::get_directory_size,
+        crate::commands::storage::benchmark_disk,
         crate::commands::automation::automation_load_repository_state,
         crate::commands::automation::automation_persist_rules,
         crate::commands::automation::automation_persist_profiles,
@@ -66,7 +72,16 @@ pub fn get_handlers() -> impl Fn(tauri::ipc::Invoke) -> bool {
         crate::commands::history::prepare_backup_import,
         crate::commands::history::apply_prepared_history_import,
         crate::commands::history::dispose_prepared_backup_import,
+        crate::commands::downloads::download_and_extract,
+        crate::commands::downloads::robust_download,
+        crate::commands::downloads::set_download_temp_dir,
+        crate::commands::downloads::set_allowed_download_hosts,
+        crate::commands::downloads::set_download_user_agent,
+        crate::commands::downloads::set_download_proxy,
+        crate::commands::downloads::set_ip_preference,
+        crate::commands::downloads::test_ip_connectivity,
         crate::commands::downloads::download_file,
+        crate::commands::downloads::download_to_memory,
         crate::commands::sync::sync_get_status,
         crate::commands::sync::sync_test_provider,
         crate::commands::sync::sync_test_webdav_provider,
@@ -91,16 +106,54 @@ pub fn get_handlers() -> impl Fn(tauri::ipc::Invoke) -> bool {
         crate::commands::system::get_model_catalog_snapshot,
         crate::commands::system::resolve_model_catalog_selected_ids_command,
         crate::commands::system::get_diagnostics_core_snapshot,
+        crate::commands::system::export_diagnostics,
         crate::commands::system::check_gpu_availability,
+        crate::commands::system::list_gpus,
+        crate::commands::system::set_preferred_gpu,
+        crate::commands::system::get_system_resources,
         crate::commands::system::force_exit,
+        crate::commands::system::can_quit_safely,
+        crate::commands::system::get_operations,
+        crate::commands::system::quit_app,
+        crate::commands::system::restart_app,
         crate::commands::downloads::has_active_downloads,
+        crate::commands::downloads::cancel_all_downloads,
+        crate::commands::downloads::cancel_group,
+        crate::commands::downloads::pause_all_downloads,
+        crate::commands::downloads::resume_all_downloads,
+        crate::commands::downloads::pause_download,
+        crate::commands::downloads::resume_download,
+        crate::commands::downloads::list_partial_downloads,
+        crate::commands::downloads::clean_partial_downloads,
+        crate::commands::downloads::flush_and_verify,
+        crate::commands::downloads::scan_models,
+        crate::commands::downloads::cancel_model_scan,
+        crate::commands::downloads::export_model_info,
+        crate::commands::downloads::validate_model_dir,
+        crate::commands::downloads::check_connectivity,
+        crate::commands::downloads::set_network_policy,
+        crate::commands::downloads::clear_network_cache,
+        crate::commands::downloads::get_network_stats,
+        crate::commands::downloads::get_overall_progress,
+        crate::commands::downloads::get_download_summary,
+        crate::commands::downloads::verify_remote_file,
+        crate::commands::downloads::relocate_models_dir,
         crate::commands::system::update_tray_menu,
         crate::commands::system::set_minimize_to_tray,
+        crate::commands::system::set_always_on_top,
+        crate::commands::system::get_launch_behavior,
+        crate::commands::system::set_launch_behavior,
+        crate::commands::system::get_launch_report,
+        crate::commands::system::prepare_for_suspend,
+        crate::commands::system::resume_from_suspend,
         crate::commands::system::set_log_level,
         crate::commands::system::set_aux_window_state,
         crate::commands::system::get_aux_window_state,
         crate::commands::system::clear_aux_window_state,
         crate::commands::audio::set_system_audio_mute,
+        crate::commands::audio::get_system_audio_mute,
+        crate::commands::audio::set_system_volume,
+        crate::commands::audio::get_system_volume,
         crate::commands::system::open_log_folder,
         crate::commands::system::get_runtime_environment_status,
         crate::commands::system::get_path_statuses,
@@ -125,13 +178,27 @@ pub fn get_handlers() -> impl Fn(tauri::ipc::Invoke) -> bool {
         crate::commands::system::get_mouse_position,
         crate::commands::system::get_text_cursor_position,
         crate::commands::audio::get_system_audio_devices,
+        crate::commands::audio::get_output_devices,
+        crate::commands::audio::get_default_output_device,
+        crate::commands::audio::set_default_output_device,
         crate::commands::audio::start_system_audio_capture,
         crate::commands::audio::stop_system_audio_capture,
         crate::commands::audio::set_system_audio_capture_paused,
         crate::commands::audio::set_microphone_boost,
+        crate::commands::audio::reconnect_capture,
         crate::commands::audio::get_microphone_devices,
+        crate::commands::audio::get_capture_backends,
+        crate::commands::audio::detect_linux_audio_backend,
+        crate::commands::audio::get_supported_record_codecs,
+        crate::commands::audio::resolve_capture_config,
+        crate::commands::audio::measure_capture_latency,
+        crate::commands::audio::run_capture_selftest,
         crate::commands::audio::start_microphone_capture,
         crate::commands::audio::stop_microphone_capture,
+        crate::commands::audio::stop_all_audio_captures,
+        crate::commands::audio::dump_ring_buffer,
+        crate::commands::audio::finalize_recording,
+        crate::commands::audio::trim_silence,
         crate::commands::audio::set_microphone_capture_paused,
         crate::commands::llm::complete_llm,
         crate::commands::llm::describe_llm_model,
@@ -7,6 +7,19 @@ pub async fn storage_get_usage_snapshot<R: Runtime>(
     crate::platform::storage_usage::get_usage_snapshot(&app).await
 }
 
+#[tauri::command]
+pub async fn get_directory_size<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<u64, String> {
+    crate::platform::storage_usage::get_directory_size(app, path).await
+}
+
+#[tauri::command]
+pub async fn benchmark_disk(dir: String) -> Result<sona_runtime_fs::DiskBenchmark, String> {
+    crate::platform::storage_usage::benchmark_disk(dir).await
+}
+
 #[tauri::command]
 pub async fn storage_clear_webview_browsing_data<R: Runtime>(
     app: AppHandle<R>,
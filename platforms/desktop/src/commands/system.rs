@@ -12,11 +12,42 @@ pub fn greet(name: &str) -> String {
     crate::platform::system::greet(name)
 }
 
+// Async so the probe is answered on the async runtime instead of queueing
+// behind main-thread work.
+#[tauri::command]
+pub async fn ping() -> u64 {
+    crate::platform::system::ping()
+}
+
 #[tauri::command]
 pub fn force_exit(app: AppHandle) {
     crate::platform::system::force_exit(app);
 }
 
+#[tauri::command]
+pub async fn can_quit_safely(
+    app: AppHandle,
+) -> Result<crate::platform::system::QuitStatus, String> {
+    crate::platform::system::can_quit_safely(app).await
+}
+
+#[tauri::command]
+pub fn get_operations(
+    app: AppHandle,
+) -> Result<Vec<crate::platform::overall_progress::Operation>, String> {
+    crate::platform::system::get_operations(&app)
+}
+
+#[tauri::command]
+pub async fn quit_app(app: AppHandle) -> Result<(), String> {
+    crate::platform::system::quit_app(app).await
+}
+
+#[tauri::command]
+pub async fn restart_app(app: AppHandle) -> Result<(), String> {
+    crate::platform::system::restart_app(app).await
+}
+
 #[tauri::command]
 pub fn inject_text(
     text: String,
@@ -49,6 +80,25 @@ pub async fn check_gpu_availability() -> Result<bool, String> {
 }
 
 #[tauri::command]
+pub async fn list_gpus() -> Vec<sona_core::runtime::gpu::GpuDevice> {
+    crate::platform::hardware::list_gpus().await
+}
+
+#[tauri::command]
+pub async fn get_system_resources() -> Result<crate::platform::hardware::SystemResources, String> {
+    crate::platform::hardware::get_system_resources().await
+}
+
+#[tauri::command]
+pub async fn set_preferred_gpu<R: Runtime>(
+    app: AppHandle<R>,
+    index: u32,
+) -> Result<String, String> {
+    crate::platform::hardware::set_preferred_gpu(&app, index).await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn update_tray_menu(
     app: AppHandle,
     show_text: String,
@@ -57,15 +107,22 @@ pub async fn update_tray_menu(
     quit_text: String,
     caption_text: String,
     caption_checked: bool,
+    cancel_downloads_text: Option<String>,
+    stop_recording_text: Option<String>,
 ) -> Result<(), String> {
+    let defaults = crate::app::tray::TrayMenuLabels::default();
     crate::app::tray::update_tray_menu(
         app,
-        show_text,
-        settings_text,
-        updates_text,
-        quit_text,
-        caption_text,
-        caption_checked,
+        crate::app::tray::TrayMenuLabels {
+            show: show_text,
+            settings: settings_text,
+            updates: updates_text,
+            quit: quit_text,
+            caption: caption_text,
+            caption_checked,
+            cancel_downloads: cancel_downloads_text.unwrap_or(defaults.cancel_downloads),
+            stop_recording: stop_recording_text.unwrap_or(defaults.stop_recording),
+        },
     )
     .await
 }
@@ -75,11 +132,51 @@ pub fn set_minimize_to_tray(state: State<'_, crate::app::settings::AppSettings>,
     crate::app::settings::set_minimize_to_tray(state, enabled);
 }
 
+#[tauri::command]
+pub fn set_always_on_top(app: AppHandle, enabled: bool) -> Result<(), String> {
+    crate::app::window_visibility::set_main_window_always_on_top(&app, enabled)
+}
+
+#[tauri::command]
+pub fn get_launch_behavior(app: AppHandle) -> Result<crate::app::launch::LaunchBehavior, String> {
+    crate::app::launch::get_launch_behavior(&app)
+}
+
+#[tauri::command]
+pub fn set_launch_behavior(
+    app: AppHandle,
+    restart_to_tray: Option<bool>,
+    resume_downloads_on_launch: Option<bool>,
+) -> Result<crate::app::launch::LaunchBehavior, String> {
+    crate::app::launch::set_launch_behavior(&app, restart_to_tray, resume_downloads_on_launch)
+}
+
+#[tauri::command]
+pub fn get_launch_report(
+    state: State<'_, crate::app::launch::LaunchReportState>,
+) -> crate::app::launch::LaunchReport {
+    state.report()
+}
+
+#[tauri::command]
+pub async fn prepare_for_suspend(
+    app: AppHandle,
+) -> Result<crate::platform::power::SuspendReport, String> {
+    crate::platform::power::prepare_for_suspend(app).await
+}
+
+#[tauri::command]
+pub async fn resume_from_suspend(
+    app: AppHandle,
+) -> Result<crate::platform::power::ResumeReport, String> {
+    crate::platform::power::resume_from_suspend(app).await
+}
+
 #[tauri::command]
 pub fn set_log_level(
     state: State<'_, crate::app::settings::AppSettings>,
     level: String,
-) -> Result<(), String> {
+) -> Result<String, String> {
     crate::app::settings::set_log_level(state, level)
 }
 
@@ -151,6 +248,11 @@ pub async fn get_diagnostics_core_snapshot(
     crate::platform::diagnostics::get_diagnostics_core_snapshot_for_app(&app, state, input).await
 }
 
+#[tauri::command]
+pub async fn export_diagnostics(app: AppHandle, output_path: String) -> Result<String, String> {
+    crate::platform::diagnostics::export_diagnostics(&app, output_path).await
+}
+
 // Relocated task_ledger commands
 
 #[tauri::command]
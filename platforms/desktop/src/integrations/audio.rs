@@ -3,15 +3,39 @@ use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ringbuf::HeapRb;
 use ringbuf::traits::{Consumer, Producer, Split};
 use rubato::{FftFixedOut, Resampler};
-use sona_local_asr::audio::LiveWavRecorder;
+use sona_core::runtime::capture::{
+    AutomaticGainControl, CAPTURE_RING_SAMPLE_RATE, CaptureAudioConfig, CaptureBackend,
+    CapturePipeFormat, CaptureRing, LinuxAudioBackend, LinuxAudioServer, RecordCodec,
+    capture_backends, capture_permission_settings_url, detect_tone_onset,
+    is_capture_permission_denied, parse_pactl_server, resolve_capture_agc,
+    resolve_capture_audio_config, resolve_capture_chunk_frames, resolve_capture_input_channel,
+    resolve_capture_pipe_format, resolve_capture_ring_seconds, resolve_record_codec,
+};
+use sona_local_asr::audio::{LiveWavRecorder, WavFileInfo, save_wav_file};
 use std::collections::HashSet;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
 use tauri::{AppHandle, Emitter, Manager, Runtime, Window};
 
+use crate::platform::capture_pipe::CapturePipe;
+use crate::platform::overall_progress::{Operation, OperationKind, OperationStatus};
+
 const MICROPHONE_PEAK_EVENT: &str = "microphone-audio";
 const SYSTEM_PEAK_EVENT: &str = "system-audio";
+const CAPTURE_STARTED_EVENT: &str = "capture-started";
+const CAPTURE_POSITION_EVENT: &str = "capture-position";
+const TRIM_SILENCE_PROGRESS_EVENT: &str = "trim-silence-progress";
+const AUDIO_PERMISSION_DENIED_EVENT: &str = "audio-permission-denied";
+const CAPTURE_SAMPLE_RATE: u64 = 16000;
+/// How long a start that waits for audio holds `capture-started` back.
+const CAPTURE_FIRST_AUDIO_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long stopping a capture waits for its worker to drain the last chunks
+/// before aborting it.
+const CAPTURE_WORKER_STOP_TIMEOUT: Duration = Duration::from_secs(2);
 
 #[derive(Clone, Copy)]
 enum CaptureKind {
@@ -27,6 +51,17 @@ impl CaptureKind {
         }
     }
 
+    fn from_source(source: Option<&str>) -> Result<Self, String> {
+        match source.unwrap_or("microphone") {
+            "microphone" => Ok(CaptureKind::Microphone),
+            "system" => Ok(CaptureKind::System),
+            other => Err(format!(
+                "Unknown capture source '{}'; expected microphone or system",
+                other
+            )),
+        }
+    }
+
     fn log_name(self) -> &'static str {
         match self {
             CaptureKind::System => "system",
@@ -175,10 +210,159 @@ impl CaptureKind {
     }
 }
 
+/// Format negotiated for a freshly started hardware capture. `sample_rate`,
+/// `channels` and `sample_format` describe the stream delivered to consumers
+/// after downmixing and resampling; the `device_*` fields report what the
+/// device itself opened with.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CaptureStartedPayload {
+    source: &'static str,
+    instance_id: String,
+    device_id: String,
+    sample_rate: u32,
+    channels: u16,
+    sample_format: &'static str,
+    chunk_frames: usize,
+    input_channel: Option<u16>,
+    /// Target RMS level of the automatic gain control, `None` when it is off.
+    agc_target_dbfs: Option<f32>,
+    device_sample_rate: u32,
+    device_channels: u16,
+    device_sample_format: String,
+    /// Time from the start request until the device delivered its first
+    /// samples. Only measured when the start waited for audio, and `None`
+    /// when none arrived within [`CAPTURE_FIRST_AUDIO_TIMEOUT`].
+    startup_delay_ms: Option<u64>,
+    /// Layout of the pipe stream, when the capture streams into one.
+    pipe: Option<CapturePipePayload>,
+}
+
+/// What a capture pipe's reader receives: the container (`pcm` for bare
+/// samples or `wav`) and the layout of the samples in it.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CapturePipePayload {
+    path: String,
+    format: &'static str,
+    sample_rate: u32,
+    channels: u16,
+    sample_format: &'static str,
+}
+
+impl CapturePipePayload {
+    fn new(pipe: &CapturePipe) -> Self {
+        let config = pipe.config();
+        Self {
+            path: pipe.path().display().to_string(),
+            format: pipe.format().as_str(),
+            sample_rate: config.sample_rate,
+            channels: config.channels,
+            sample_format: config.sample_format.as_str(),
+        }
+    }
+}
+
+impl CaptureStartedPayload {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        kind: CaptureKind,
+        instance_id: String,
+        device_id: String,
+        config: &cpal::StreamConfig,
+        device_sample_format: SampleFormat,
+        chunk_frames: usize,
+        input_channel: Option<u16>,
+        agc_target_dbfs: Option<f32>,
+    ) -> Self {
+        Self {
+            source: kind.log_name(),
+            instance_id,
+            device_id,
+            sample_rate: 16000,
+            channels: 1,
+            sample_format: "f32",
+            chunk_frames,
+            input_channel,
+            agc_target_dbfs,
+            device_sample_rate: config.sample_rate,
+            device_channels: config.channels,
+            device_sample_format: device_sample_format.to_string(),
+            startup_delay_ms: None,
+            pipe: None,
+        }
+    }
+}
+
+/// Running position of a hardware capture, emitted next to each peak event.
+/// Counted from the 16 kHz mono samples produced since the stream opened, so
+/// it keeps advancing while owners are paused and does not drift with IPC
+/// delivery timing.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CapturePositionPayload {
+    source: &'static str,
+    samples: u64,
+    position_ms: u64,
+}
+
+impl CapturePositionPayload {
+    fn new(kind: CaptureKind, samples: u64) -> Self {
+        Self {
+            source: kind.log_name(),
+            samples,
+            position_ms: samples * 1000 / CAPTURE_SAMPLE_RATE,
+        }
+    }
+}
+
+/// Sent instead of relying on the generic capture error when the OS refused
+/// access to the device, so the frontend can send the user to the right
+/// privacy settings.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AudioPermissionDeniedPayload {
+    source: &'static str,
+    message: String,
+    /// Deep link to the OS privacy pane, when the platform has one.
+    settings_url: Option<&'static str>,
+}
+
+/// Emits [`AUDIO_PERMISSION_DENIED_EVENT`] when `message` is a permission
+/// failure.
+fn report_capture_permission_denied<R: Runtime>(
+    app: &AppHandle<R>,
+    kind: CaptureKind,
+    message: &str,
+) {
+    if !is_capture_permission_denied(message) {
+        return;
+    }
+    let payload = AudioPermissionDeniedPayload {
+        source: kind.log_name(),
+        message: message.to_string(),
+        settings_url: capture_permission_settings_url(
+            std::env::consts::OS,
+            matches!(kind, CaptureKind::System),
+        ),
+    };
+    if let Err(err) = app.emit(AUDIO_PERMISSION_DENIED_EVENT, payload) {
+        eprintln!(
+            "[Audio] Failed to emit {} permission-denied event: {}",
+            kind.log_name(),
+            err
+        );
+    }
+}
+
 pub enum RecorderCommand {
-    Start(String), // filepath
-    Stop(tokio::sync::oneshot::Sender<String>),
+    Start(String, RecordCodec), // filepath, codec applied once the WAV is finalized
+    /// Replies with the WAV path, its codec and, when the file was finalized
+    /// and synced, what its header holds.
+    Stop(tokio::sync::oneshot::Sender<(String, RecordCodec, Option<WavFileInfo>)>),
     SetPaused(bool),
+    /// Writes the rolling capture buffer to the given WAV path.
+    DumpRing(String, tokio::sync::oneshot::Sender<Result<String, String>>),
 }
 
 #[derive(Default)]
@@ -192,6 +376,14 @@ struct SharedCaptureState {
     paused_instances: HashSet<String>,
     recorder_tx: Option<tokio::sync::mpsc::Sender<RecorderCommand>>,
     active_device_name: Option<String>,
+    /// Unix milliseconds when the running hardware session started.
+    started_at: Option<u64>,
+    /// Set while every owner is paused. Shared with the device callback so it
+    /// can stop emitting peak events without locking this state.
+    all_paused: Arc<AtomicBool>,
+    /// Task that records and feeds this session's samples. Awaited on the
+    /// final stop so it cannot feed an owner of the next session.
+    worker: Option<JoinHandle<()>>,
 }
 
 /// Result of detaching one logical owner from a shared hardware capture.
@@ -202,6 +394,7 @@ struct SharedCaptureDetachResult {
     stop_signal: Option<Sender<()>>,
     recorder_tx: Option<tokio::sync::mpsc::Sender<RecorderCommand>>,
     active_device_name: Option<String>,
+    worker: Option<JoinHandle<()>>,
 }
 
 impl SharedCaptureState {
@@ -226,11 +419,35 @@ impl SharedCaptureState {
         active_instances
     }
 
+    fn running_capture(&self, kind: CaptureKind) -> Option<RunningCapturePayload> {
+        if !self.is_running() {
+            return None;
+        }
+        let mut paused_instance_ids = self.paused_instances.iter().cloned().collect::<Vec<_>>();
+        paused_instance_ids.sort();
+        Some(RunningCapturePayload {
+            source: kind.log_name(),
+            device_name: self.active_device_name.clone(),
+            instance_ids: self.owners(),
+            paused_instance_ids,
+        })
+    }
+
+    fn all_paused_flag(&self) -> Arc<AtomicBool> {
+        self.all_paused.clone()
+    }
+
+    fn sync_all_paused(&self) {
+        let all_paused = !self.instance_ids.is_empty() && self.active_instances().is_empty();
+        self.all_paused.store(all_paused, Ordering::Relaxed);
+    }
+
     fn attach_instance(&mut self, instance_id: String) -> Vec<String> {
         // Re-attaching an existing instance should also make it active again if
         // it had previously been paused.
         self.paused_instances.remove(&instance_id);
         self.instance_ids.insert(instance_id);
+        self.sync_all_paused();
         self.owners()
     }
 
@@ -247,8 +464,10 @@ impl SharedCaptureState {
         self.paused_instances.clear();
         self.instance_ids.insert(instance_id);
         self.active_device_name = Some(active_device_name);
+        self.started_at = Some(crate::platform::time::unix_timestamp_millis());
         self.stop_signal = Some(stop_signal);
         self.recorder_tx = Some(recorder_tx);
+        self.sync_all_paused();
         self.owners()
     }
 
@@ -269,6 +488,7 @@ impl SharedCaptureState {
         } else {
             self.paused_instances.remove(instance_id);
         }
+        self.sync_all_paused();
 
         Ok(self.active_instances())
     }
@@ -276,6 +496,7 @@ impl SharedCaptureState {
     fn detach_instance(&mut self, instance_id: &str) -> SharedCaptureDetachResult {
         self.instance_ids.remove(instance_id);
         self.paused_instances.remove(instance_id);
+        self.sync_all_paused();
         let remaining_instances = self.owners();
         let should_stop_hardware = remaining_instances.is_empty();
         // Keep the recorder channel available while at least one owner still
@@ -296,6 +517,12 @@ impl SharedCaptureState {
         } else {
             self.active_device_name.clone()
         };
+        let worker = if should_stop_hardware {
+            self.started_at = None;
+            self.worker.take()
+        } else {
+            None
+        };
 
         SharedCaptureDetachResult {
             should_stop_hardware,
@@ -303,6 +530,7 @@ impl SharedCaptureState {
             stop_signal,
             recorder_tx,
             active_device_name,
+            worker,
         }
     }
 
@@ -326,6 +554,18 @@ impl Default for AudioState {
 }
 
 impl AudioState {
+    /// True while either hardware capture is running for at least one owner.
+    pub(crate) fn is_capturing(&self) -> bool {
+        [&self.system_capture, &self.mic_capture]
+            .into_iter()
+            .any(|capture| {
+                capture
+                    .lock()
+                    .map(|capture| capture.is_running())
+                    .unwrap_or(false)
+            })
+    }
+
     pub fn new() -> Self {
         Self {
             system_start_guard: Mutex::new(()),
@@ -340,17 +580,44 @@ impl AudioState {
 #[derive(serde::Serialize)]
 pub struct AudioDevice {
     name: String,
+    /// Set on the Windows pseudo-devices that follow a default role.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<&'static str>,
+}
+
+/// One pseudo-device per default role whose endpoint exists, listed ahead of
+/// the real devices. Windows only.
+fn default_role_devices(kind: CaptureKind) -> Vec<AudioDevice> {
+    #[cfg(target_os = "windows")]
+    {
+        use crate::platform::system_audio::{DefaultDeviceRole, default_endpoint_id};
+
+        let capture = matches!(kind, CaptureKind::Microphone);
+        DefaultDeviceRole::ALL
+            .into_iter()
+            .filter(|role| default_endpoint_id(*role, capture).is_ok())
+            .map(|role| AudioDevice {
+                name: role.device_name().to_string(),
+                role: Some(role.as_str()),
+            })
+            .collect()
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = kind;
+        Vec::new()
+    }
 }
 
 pub fn get_system_audio_devices() -> Result<Vec<AudioDevice>, String> {
     let host = cpal::default_host();
     let devices = host.output_devices().map_err(|e| e.to_string())?;
 
-    let result = devices
-        .map(|device| AudioDevice {
-            name: device.to_string(),
-        })
-        .collect();
+    let mut result = default_role_devices(CaptureKind::System);
+    result.extend(devices.map(|device| AudioDevice {
+        name: device.to_string(),
+        role: None,
+    }));
 
     Ok(result)
 }
@@ -378,6 +645,7 @@ fn queue_recording_start(
     capture_label: &str,
     instance_id: &str,
     output_path: Option<String>,
+    record_codec: RecordCodec,
     fallback_path: impl FnOnce() -> Result<String, String>,
 ) -> Result<(), String> {
     if !should_record {
@@ -396,7 +664,7 @@ fn queue_recording_start(
     };
 
     let wav_filepath = resolve_recording_output_path(output_path, fallback_path)?;
-    if let Err(err) = tx.try_send(RecorderCommand::Start(wav_filepath.clone())) {
+    if let Err(err) = tx.try_send(RecorderCommand::Start(wav_filepath.clone(), record_codec)) {
         eprintln!(
             "[Audio] Failed to queue {} recorder start for instance {} at {}: {}",
             capture_label, instance_id, wav_filepath, err
@@ -457,10 +725,13 @@ fn spawn_capture_worker_task(
     mut task_consumer: impl Consumer<Item = f32> + Send + 'static,
     mut data_rx: tokio::sync::mpsc::Receiver<()>,
     mut recorder_rx: tokio::sync::mpsc::Receiver<RecorderCommand>,
-) {
+    mut ring: Option<CaptureRing>,
+    mut pipe: Option<CapturePipe>,
+) -> JoinHandle<()> {
     tauri::async_runtime::spawn(async move {
         let mut writer: Option<LiveWavRecorder> = None;
         let mut current_filepath = String::new();
+        let mut current_codec = RecordCodec::Pcm;
         let mut pull_buffer = vec![0.0; 16000];
         let mut recorder_paused = false;
 
@@ -469,7 +740,7 @@ fn spawn_capture_worker_task(
                 biased;
                 cmd = recorder_rx.recv() => {
                     match cmd {
-                        Some(RecorderCommand::Start(path)) => {
+                        Some(RecorderCommand::Start(path, codec)) => {
                             if let Some(w) = writer.take() {
                                 let _ = w.finalize();
                             }
@@ -477,6 +748,7 @@ fn spawn_capture_worker_task(
                                 Ok(w) => {
                                     writer = Some(w);
                                     current_filepath = path;
+                                    current_codec = codec;
                                     recorder_paused = false;
                                 }
                                 Err(e) => eprintln!(
@@ -488,15 +760,27 @@ fn spawn_capture_worker_task(
                         }
                         Some(RecorderCommand::Stop(tx)) => {
                             recorder_paused = false;
-                            if let Some(w) = writer.take() {
-                                let _ = w.finalize();
-                            }
-                            let _ = tx.send(current_filepath.clone());
+                            let info = writer.take().and_then(|w| match w.finalize_with_info() {
+                                Ok(info) => Some(info),
+                                Err(e) => {
+                                    eprintln!(
+                                        "[Audio] Failed to finalize {} WAV file: {}",
+                                        kind.log_name(),
+                                        e
+                                    );
+                                    None
+                                }
+                            });
+                            let _ = tx.send((current_filepath.clone(), current_codec, info));
                             current_filepath.clear();
+                            current_codec = RecordCodec::Pcm;
                         }
                         Some(RecorderCommand::SetPaused(paused)) => {
                             recorder_paused = paused;
                         }
+                        Some(RecorderCommand::DumpRing(path, tx)) => {
+                            let _ = tx.send(dump_capture_ring(kind, ring.as_ref(), path));
+                        }
                         None => break,
                     }
                 }
@@ -509,6 +793,8 @@ fn spawn_capture_worker_task(
                                 &mut task_consumer,
                                 &mut pull_buffer,
                                 &mut writer,
+                                &mut ring,
+                                &mut pipe,
                                 recorder_paused,
                             ).await;
                         }
@@ -525,6 +811,8 @@ fn spawn_capture_worker_task(
                 &mut task_consumer,
                 &mut pull_buffer,
                 &mut writer,
+                &mut ring,
+                &mut pipe,
                 recorder_paused,
             )
             .await;
@@ -536,15 +824,32 @@ fn spawn_capture_worker_task(
         if let Some(w) = writer {
             let _ = w.finalize();
         }
-    });
+    })
+}
+
+/// Waits for a stopped session's worker to drain its last chunks, so none of
+/// them reach an owner of a capture started afterwards. A worker that does
+/// not finish within `timeout` is aborted.
+async fn finish_capture_worker(kind: CaptureKind, mut worker: JoinHandle<()>, timeout: Duration) {
+    if tokio::time::timeout(timeout, &mut worker).await.is_err() {
+        eprintln!(
+            "[Audio] {} capture worker did not stop within {:?}; aborting it",
+            kind.log_name(),
+            timeout
+        );
+        worker.abort();
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn drain_capture_worker_chunk(
     app: &AppHandle,
     kind: CaptureKind,
     task_consumer: &mut impl Consumer<Item = f32>,
     pull_buffer: &mut [f32],
     writer: &mut Option<LiveWavRecorder>,
+    ring: &mut Option<CaptureRing>,
+    pipe: &mut Option<CapturePipe>,
     recorder_paused: bool,
 ) -> bool {
     let len = task_consumer.pop_slice(pull_buffer);
@@ -564,10 +869,52 @@ async fn drain_capture_worker_chunk(
         );
     }
 
+    if let Some(ring) = ring.as_mut() {
+        ring.push(chunk);
+    }
+    if let Some(pipe) = pipe.as_mut() {
+        pipe.write_samples(chunk);
+    }
+
     feed_capture_audio_to_instances(app, kind, chunk).await;
     true
 }
 
+fn dump_capture_ring(
+    kind: CaptureKind,
+    ring: Option<&CaptureRing>,
+    path: String,
+) -> Result<String, String> {
+    let ring = ring.ok_or_else(|| {
+        format!(
+            "{} capture was started without capture_ring_seconds",
+            kind.label()
+        )
+    })?;
+    save_wav_file(
+        &ring.to_vec(),
+        CAPTURE_RING_SAMPLE_RATE,
+        std::path::Path::new(&path),
+    )
+    .map_err(|e| {
+        format!(
+            "Failed to write {} ring buffer to {}: {}",
+            kind.log_name(),
+            path,
+            e
+        )
+    })?;
+    println!(
+        "[Audio] Saved {} ring buffer ({} of {} samples) to {}",
+        kind.log_name(),
+        ring.len(),
+        ring.capacity(),
+        path
+    );
+    Ok(path)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn start_system_audio_capture(
     app: AppHandle,
     window: Window,
@@ -576,6 +923,18 @@ pub fn start_system_audio_capture(
     device_name: Option<String>,
     instance_id: String,
     output_path: Option<String>,
+    record_codec: Option<String>,
+    chunk_frames: Option<u32>,
+    input_channel: Option<u16>,
+    agc: Option<bool>,
+    agc_target_dbfs: Option<f32>,
+    capture_ring_seconds: Option<u32>,
+    wait_for_audio: Option<bool>,
+    pipe_path: Option<String>,
+    pipe_format: Option<String>,
+    pipe_sample_rate: Option<u32>,
+    pipe_channels: Option<u16>,
+    pipe_sample_format: Option<String>,
 ) -> Result<(), String> {
     start_shared_capture(
         app,
@@ -585,9 +944,20 @@ pub fn start_system_audio_capture(
         device_name,
         instance_id,
         output_path,
+        resolve_record_codec(record_codec).map_err(|error| error.to_string())?,
+        resolve_capture_chunk_frames(chunk_frames).map_err(|error| error.to_string())?,
+        input_channel,
+        resolve_capture_agc(agc, agc_target_dbfs).map_err(|error| error.to_string())?,
+        resolve_capture_ring_seconds(capture_ring_seconds).map_err(|error| error.to_string())?,
+        wait_for_audio.unwrap_or(false),
+        pipe_path,
+        resolve_capture_pipe_format(pipe_format).map_err(|error| error.to_string())?,
+        resolve_capture_audio_config(pipe_sample_rate, pipe_channels, pipe_sample_format)
+            .map_err(|error| error.to_string())?,
     )
 }
 
+#[allow(clippy::too_many_arguments)]
 fn start_shared_capture(
     app: AppHandle,
     window: Window,
@@ -596,7 +966,24 @@ fn start_shared_capture(
     device_name: Option<String>,
     instance_id: String,
     output_path: Option<String>,
+    record_codec: RecordCodec,
+    chunk_frames: usize,
+    input_channel: Option<u16>,
+    agc_target_dbfs: Option<f32>,
+    ring_capacity: Option<usize>,
+    wait_for_audio: bool,
+    pipe_path: Option<String>,
+    pipe_format: CapturePipeFormat,
+    pipe_config: CaptureAudioConfig,
 ) -> Result<(), String> {
+    let requested_at = Instant::now();
+    if kind.should_record(&instance_id) {
+        // Fail before touching the device: an unsupported encoder would only
+        // surface after the user stops recording otherwise.
+        sona_local_asr::audio::ensure_ffmpeg_supports_record_codec(record_codec)
+            .map_err(|error| error.to_string())?;
+    }
+
     let _start_guard = kind.start_guard(state).lock().map_err(|e| e.to_string())?;
     let requested_device = requested_device_label(&device_name);
 
@@ -607,12 +994,16 @@ fn start_shared_capture(
             let active_device = capture.active_device_label().to_string();
             let recorder_tx = capture.recorder_tx.clone();
             println!(
-                "[Audio] {} capture already running. Attached instance: {}. requested_device={}, active_device={}, owners={:?}",
+                "[Audio] {} capture already running. Attached instance: {}. requested_device={}, active_device={}, owners={:?}, requested_chunk_frames={}, requested_input_channel={:?}, requested_agc_target_dbfs={:?}, requested_pipe={:?} (shared stream keeps its format and pipe)",
                 kind.label(),
                 instance_id,
                 requested_device,
                 active_device,
-                owners
+                owners,
+                chunk_frames,
+                input_channel,
+                agc_target_dbfs,
+                pipe_path
             );
             drop(capture);
             queue_recording_start(
@@ -621,6 +1012,7 @@ fn start_shared_capture(
                 kind.label(),
                 &instance_id,
                 output_path.clone(),
+                record_codec,
                 || crate::platform::audio_storage::create_history_recording_path_for_app(&app),
             )?;
             return Ok(());
@@ -634,31 +1026,68 @@ fn start_shared_capture(
         requested_device
     );
 
+    let pipe = pipe_path
+        .as_deref()
+        .map(|path| CapturePipe::create(path, pipe_format, pipe_config))
+        .transpose()?;
+    let pipe_payload = pipe.as_ref().map(CapturePipePayload::new);
+    if let Some(pipe) = &pipe {
+        println!(
+            "[Audio] Streaming {} capture to pipe {} as {} ({} Hz, {} ch, {})",
+            kind.log_name(),
+            pipe.path().display(),
+            pipe.format().as_str(),
+            pipe.config().sample_rate,
+            pipe.config().channels,
+            pipe.config().sample_format.as_str()
+        );
+    }
+
     let (stop_tx, rx) = channel::<()>();
     let task_rb = HeapRb::<f32>::new(16000 * 5);
     let (task_producer, task_consumer) = task_rb.split();
     let (data_tx, data_rx) = tokio::sync::mpsc::channel::<()>(100);
     let (recorder_tx, recorder_rx) = tokio::sync::mpsc::channel::<RecorderCommand>(10);
-    let (startup_tx, startup_rx) = channel::<Result<String, String>>();
-
-    spawn_capture_worker_task(app.clone(), kind, task_consumer, data_rx, recorder_rx);
+    let (startup_tx, startup_rx) = channel::<Result<CaptureStartedPayload, String>>();
+    let all_paused = kind
+        .capture(state)
+        .lock()
+        .map_err(|e| e.to_string())?
+        .all_paused_flag();
+
+    let worker = spawn_capture_worker_task(
+        app.clone(),
+        kind,
+        task_consumer,
+        data_rx,
+        recorder_rx,
+        ring_capacity.map(CaptureRing::new),
+        pipe,
+    );
     spawn_cpal_startup_thread(
         window,
         kind,
         device_name,
         instance_id.clone(),
         requested_device.clone(),
+        chunk_frames,
+        input_channel,
+        agc_target_dbfs,
+        wait_for_audio.then_some(requested_at),
         rx,
         startup_tx,
         data_tx,
         task_producer,
+        all_paused,
     );
 
-    let active_device = match startup_rx.recv() {
-        Ok(Ok(device_name)) => device_name,
+    let mut started = match startup_rx.recv() {
+        Ok(Ok(started)) => started,
         Ok(Err(err)) => return Err(err),
         Err(err) => return Err(kind.startup_channel_error_message(err)),
     };
+    let active_device = started.device_id.clone();
+    started.pipe = pipe_payload;
 
     {
         let mut capture = kind.capture(state).lock().map_err(|e| e.to_string())?;
@@ -668,6 +1097,7 @@ fn start_shared_capture(
             stop_tx,
             recorder_tx.clone(),
         );
+        capture.worker = Some(worker);
         println!(
             "[Audio] {} capture startup committed. instance={}, active_device={}, owners={:?}",
             kind.label(),
@@ -677,18 +1107,99 @@ fn start_shared_capture(
         );
     }
 
+    // Only emitted for a fresh hardware stream; attached instances reuse the
+    // format announced when the capture first started.
+    if let Err(err) = app.emit(CAPTURE_STARTED_EVENT, &started) {
+        eprintln!(
+            "[Audio] Failed to emit {} capture-started event: {}",
+            kind.log_name(),
+            err
+        );
+    }
+
     queue_recording_start(
         Some(&recorder_tx),
         kind.should_record(&instance_id),
         kind.label(),
         &instance_id,
         output_path,
+        record_codec,
         || crate::platform::audio_storage::create_history_recording_path_for_app(&app),
     )?;
 
     Ok(())
 }
 
+fn find_capture_device(host: &cpal::Host, kind: CaptureKind, name: &str) -> Option<cpal::Device> {
+    if let Some(role) = crate::platform::system_audio::DefaultDeviceRole::from_device_name(name) {
+        return find_role_device(host, kind, role);
+    }
+    match kind {
+        CaptureKind::System => host
+            .output_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.to_string() == name)),
+        CaptureKind::Microphone => host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.to_string() == name)),
+    }
+}
+
+/// The device Windows currently uses as the default for `role`. Elsewhere
+/// roles do not exist, so the plain default device stands in.
+fn find_role_device(
+    host: &cpal::Host,
+    kind: CaptureKind,
+    role: crate::platform::system_audio::DefaultDeviceRole,
+) -> Option<cpal::Device> {
+    #[cfg(target_os = "windows")]
+    {
+        let capture = matches!(kind, CaptureKind::Microphone);
+        let endpoint_id = crate::platform::system_audio::default_endpoint_id(role, capture)
+            .map_err(|e| {
+                eprintln!(
+                    "[Audio] No default {} {} device: {}",
+                    role.as_str(),
+                    kind.log_name(),
+                    e
+                )
+            })
+            .ok()?;
+        host.devices().ok()?.find(|device| {
+            device
+                .id()
+                .is_ok_and(|id| id.id().eq_ignore_ascii_case(&endpoint_id))
+        })
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        println!(
+            "[Audio] The default {} role only exists on Windows; using the default {} device",
+            role.as_str(),
+            kind.log_name()
+        );
+        default_capture_device(host, kind)
+    }
+}
+
+fn default_capture_device(host: &cpal::Host, kind: CaptureKind) -> Option<cpal::Device> {
+    match kind {
+        CaptureKind::System => host.default_output_device(),
+        CaptureKind::Microphone => host.default_input_device(),
+    }
+}
+
+fn default_capture_config(
+    device: &cpal::Device,
+    kind: CaptureKind,
+) -> Result<cpal::SupportedStreamConfig, cpal::Error> {
+    match kind {
+        CaptureKind::System => device.default_output_config(),
+        CaptureKind::Microphone => device.default_input_config(),
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn spawn_cpal_startup_thread<R: Runtime + 'static>(
     window: Window<R>,
@@ -696,15 +1207,21 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
     device_name: Option<String>,
     instance_id: String,
     requested_device: String,
+    chunk_frames: usize,
+    input_channel: Option<u16>,
+    agc_target_dbfs: Option<f32>,
+    wait_for_audio_since: Option<Instant>,
     rx: std::sync::mpsc::Receiver<()>,
-    startup_tx: Sender<Result<String, String>>,
+    startup_tx: Sender<Result<CaptureStartedPayload, String>>,
     data_tx: tokio::sync::mpsc::Sender<()>,
     mut task_producer: impl Producer<Item = f32> + Send + 'static,
+    all_paused: Arc<AtomicBool>,
 ) {
     thread::spawn(move || {
         let startup_instance_id = instance_id;
         let startup_requested_device = requested_device;
         let fail_start = |message: String| {
+            report_capture_permission_denied(window.app_handle(), kind, &message);
             eprintln!(
                 "[Audio] Failed to start {} capture for instance {} (requested_device={}): {}",
                 kind.log_name(),
@@ -715,26 +1232,17 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
             let _ = startup_tx.send(Err(message));
         };
 
-        let err_fn = move |err| eprintln!("[Audio] {} error: {}", kind.stream_error_label(), err);
-        let host = cpal::default_host();
-        let device = match kind {
-            CaptureKind::System => device_name
-                .as_ref()
-                .and_then(|name| {
-                    host.output_devices()
-                        .ok()
-                        .and_then(|mut devices| devices.find(|d| d.to_string() == *name))
-                })
-                .or_else(|| host.default_output_device()),
-            CaptureKind::Microphone => device_name
-                .as_ref()
-                .and_then(|name| {
-                    host.input_devices()
-                        .ok()
-                        .and_then(|mut devices| devices.find(|d| d.to_string() == *name))
-                })
-                .or_else(|| host.default_input_device()),
+        let err_app = window.app_handle().clone();
+        let err_fn = move |err: cpal::Error| {
+            // Revoking access mid-capture surfaces here rather than at start.
+            report_capture_permission_denied(&err_app, kind, &err.to_string());
+            eprintln!("[Audio] {} error: {}", kind.stream_error_label(), err)
         };
+        let host = cpal::default_host();
+        let device = device_name
+            .as_deref()
+            .and_then(|name| find_capture_device(&host, kind, name))
+            .or_else(|| default_capture_device(&host, kind));
 
         let Some(device) = device else {
             fail_start(kind.no_device_message().to_string());
@@ -742,11 +1250,7 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
         };
         let resolved_device_name = device.to_string();
 
-        let supported_config = match kind {
-            CaptureKind::System => device.default_output_config(),
-            CaptureKind::Microphone => device.default_input_config(),
-        };
-        let supported_config = match supported_config {
+        let supported_config = match default_capture_config(&device, kind) {
             Ok(c) => c,
             Err(e) => {
                 fail_start(kind.config_error_message(e));
@@ -756,9 +1260,28 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
 
         let sample_format = supported_config.sample_format();
         let config: cpal::StreamConfig = supported_config.into();
+        let started = CaptureStartedPayload::new(
+            kind,
+            startup_instance_id.clone(),
+            resolved_device_name.clone(),
+            &config,
+            sample_format,
+            chunk_frames,
+            input_channel,
+            agc_target_dbfs,
+        );
         let sample_rate = config.sample_rate;
         let channels = config.channels;
-        let chunk_size_out = 1024;
+        // Picking one channel happens before the mono downmix so interfaces
+        // with many inputs can capture a single source.
+        let selected_channel = match resolve_capture_input_channel(input_channel, channels) {
+            Ok(selected) => selected,
+            Err(error) => {
+                fail_start(error.to_string());
+                return;
+            }
+        };
+        let chunk_size_out = chunk_frames;
 
         let mut resampler =
             match FftFixedOut::<f32>::new(sample_rate as usize, 16000, chunk_size_out, 2, 1) {
@@ -774,6 +1297,10 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
         let (mut producer, mut consumer) = rb.split();
         let mut input_buffer: Vec<Vec<f32>> = vec![vec![0.0; input_frames_next]; 1];
         let mut output_buffer: Vec<Vec<f32>> = vec![vec![0.0; chunk_size_out]; 1];
+        let mut captured_samples = 0_u64;
+        let mut agc = agc_target_dbfs.map(AutomaticGainControl::new);
+        let (first_audio_tx, first_audio_rx) = channel::<()>();
+        let mut first_audio_tx = Some(first_audio_tx);
 
         let stream_result = match sample_format {
             SampleFormat::F32 => {
@@ -781,11 +1308,13 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                 device.build_input_stream(
                     config,
                     move |data: &[f32], _: &_| {
+                        signal_first_audio(&mut first_audio_tx, data.len());
                         let boost = kind.read_boost(window_clone.app_handle());
                         process_capture_audio(
                             kind,
                             data,
                             channels as usize,
+                            selected_channel,
                             &mut producer,
                             &mut consumer,
                             &mut resampler,
@@ -795,6 +1324,9 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                             &data_tx,
                             &mut task_producer,
                             boost,
+                            &all_paused,
+                            &mut captured_samples,
+                            &mut agc,
                         );
                     },
                     err_fn,
@@ -806,12 +1338,14 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                 device.build_input_stream(
                     config,
                     move |data: &[i16], _: &_| {
+                        signal_first_audio(&mut first_audio_tx, data.len());
                         let boost = kind.read_boost(window_clone.app_handle());
                         let data_f32: Vec<f32> = data.iter().map(|&s| s as f32 / 32768.0).collect();
                         process_capture_audio(
                             kind,
                             &data_f32,
                             channels as usize,
+                            selected_channel,
                             &mut producer,
                             &mut consumer,
                             &mut resampler,
@@ -821,6 +1355,9 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                             &data_tx,
                             &mut task_producer,
                             boost,
+                            &all_paused,
+                            &mut captured_samples,
+                            &mut agc,
                         );
                     },
                     err_fn,
@@ -832,6 +1369,7 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                 device.build_input_stream(
                     config,
                     move |data: &[u16], _: &_| {
+                        signal_first_audio(&mut first_audio_tx, data.len());
                         let boost = kind.read_boost(window_clone.app_handle());
                         let data_f32: Vec<f32> = data
                             .iter()
@@ -841,6 +1379,7 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                             kind,
                             &data_f32,
                             channels as usize,
+                            selected_channel,
                             &mut producer,
                             &mut consumer,
                             &mut resampler,
@@ -850,6 +1389,9 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
                             &data_tx,
                             &mut task_producer,
                             boost,
+                            &all_paused,
+                            &mut captured_samples,
+                            &mut agc,
                         );
                     },
                     err_fn,
@@ -875,13 +1417,29 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
             return;
         }
 
+        let mut started = started;
+        if let Some(requested_at) = wait_for_audio_since {
+            match first_audio_rx.recv_timeout(CAPTURE_FIRST_AUDIO_TIMEOUT) {
+                Ok(()) => {
+                    started.startup_delay_ms = Some(requested_at.elapsed().as_millis() as u64)
+                }
+                // Loopback devices stay silent until something plays, so the
+                // start goes ahead without a measurement.
+                Err(_) => eprintln!(
+                    "[Audio] No {} audio within {:?}; reporting start without a startup delay",
+                    kind.log_name(),
+                    CAPTURE_FIRST_AUDIO_TIMEOUT
+                ),
+            }
+        }
+
         println!(
             "[Audio] {} started successfully on background thread. instance={}, active_device={}",
             kind.thread_success_subject(),
             startup_instance_id,
             resolved_device_name
         );
-        if startup_tx.send(Ok(resolved_device_name.clone())).is_err() {
+        if startup_tx.send(Ok(started)).is_err() {
             return;
         }
 
@@ -895,6 +1453,15 @@ fn spawn_cpal_startup_thread<R: Runtime + 'static>(
     });
 }
 
+/// Signals the first non-empty callback of a stream; later calls do nothing.
+fn signal_first_audio(first_audio_tx: &mut Option<Sender<()>>, samples: usize) {
+    if samples > 0
+        && let Some(tx) = first_audio_tx.take()
+    {
+        let _ = tx.send(());
+    }
+}
+
 async fn feed_system_audio_to_instances(app: &AppHandle, chunk: &[f32]) {
     let instance_ids: Vec<String> = {
         let audio_state = app.state::<AudioState>();
@@ -925,19 +1492,702 @@ async fn feed_system_audio_to_instances(app: &AppHandle, chunk: &[f32]) {
     }
 }
 
+/// Lists the recording codecs the bundled FFmpeg can produce.
+pub fn get_supported_record_codecs() -> Result<Vec<&'static str>, String> {
+    let codecs = sona_local_asr::audio::probe_supported_record_codecs()
+        .map_err(|error| error.to_string())?;
+    Ok(codecs.into_iter().map(RecordCodec::as_str).collect())
+}
+
+/// Capture settings picked in the UI, checked by [`resolve_capture_config`]
+/// the same way the start commands check them.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureConfigOptions {
+    source: Option<String>,
+    record_codec: Option<String>,
+    chunk_frames: Option<u32>,
+    input_channel: Option<u16>,
+    agc: Option<bool>,
+    agc_target_dbfs: Option<f32>,
+}
+
+/// What a capture on the selected device would run with.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResolvedCaptureConfig {
+    source: &'static str,
+    device_id: String,
+    sample_rate: u32,
+    channels: u16,
+    chunk_frames: usize,
+    input_channel: Option<u16>,
+    agc_target_dbfs: Option<f32>,
+    /// Only set when a codec was asked for; it is then known to be encodable.
+    record_codec: Option<&'static str>,
+    device_sample_rate: u32,
+    device_channels: u16,
+    device_sample_format: String,
+}
+
+/// Checks that a capture could open `config` before any stream is built: the
+/// sample format must be one the callbacks convert, the input channel must
+/// exist and the rate must be resamplable to 16 kHz.
+fn check_capture_stream_config(
+    kind: CaptureKind,
+    config: &cpal::StreamConfig,
+    sample_format: SampleFormat,
+    chunk_frames: usize,
+    input_channel: Option<u16>,
+) -> Result<(), String> {
+    if !matches!(
+        sample_format,
+        SampleFormat::F32 | SampleFormat::I16 | SampleFormat::U16
+    ) {
+        return Err(format!(
+            "{}: {}",
+            kind.unsupported_sample_format_message(),
+            sample_format
+        ));
+    }
+    resolve_capture_input_channel(input_channel, config.channels)
+        .map_err(|error| error.to_string())?;
+    FftFixedOut::<f32>::new(config.sample_rate as usize, 16000, chunk_frames, 2, 1)
+        .map_err(|error| kind.resampler_error_message(error))?;
+    Ok(())
+}
+
+/// Resolves the device and settings a capture start would use, without
+/// opening a stream, so the settings UI can report problems on selection.
+/// Unlike a start, a named device that is missing is an error rather than a
+/// fallback to the default device.
+pub fn resolve_capture_config(
+    device_name: Option<String>,
+    options: CaptureConfigOptions,
+) -> Result<ResolvedCaptureConfig, String> {
+    let kind = CaptureKind::from_source(options.source.as_deref())?;
+    let chunk_frames =
+        resolve_capture_chunk_frames(options.chunk_frames).map_err(|error| error.to_string())?;
+    let agc_target_dbfs = resolve_capture_agc(options.agc, options.agc_target_dbfs)
+        .map_err(|error| error.to_string())?;
+    let record_codec = match options.record_codec {
+        Some(codec) => {
+            let codec = resolve_record_codec(Some(codec)).map_err(|error| error.to_string())?;
+            sona_local_asr::audio::ensure_ffmpeg_supports_record_codec(codec)
+                .map_err(|error| error.to_string())?;
+            Some(codec.as_str())
+        }
+        None => None,
+    };
+
+    let host = cpal::default_host();
+    let device = match device_name.as_deref() {
+        Some(name) => find_capture_device(&host, kind, name)
+            .ok_or_else(|| format!("{} device not found: {}", kind.label(), name))?,
+        None => default_capture_device(&host, kind)
+            .ok_or_else(|| kind.no_device_message().to_string())?,
+    };
+    let supported_config =
+        default_capture_config(&device, kind).map_err(|error| kind.config_error_message(error))?;
+    let sample_format = supported_config.sample_format();
+    let config: cpal::StreamConfig = supported_config.into();
+    check_capture_stream_config(
+        kind,
+        &config,
+        sample_format,
+        chunk_frames,
+        options.input_channel,
+    )?;
+
+    Ok(ResolvedCaptureConfig {
+        source: kind.log_name(),
+        device_id: device.to_string(),
+        sample_rate: 16000,
+        channels: 1,
+        chunk_frames,
+        input_channel: options.input_channel,
+        agc_target_dbfs,
+        record_codec,
+        device_sample_rate: config.sample_rate,
+        device_channels: config.channels,
+        device_sample_format: sample_format.to_string(),
+    })
+}
+
+/// Captured audio before the calibration tone plays, setting the noise floor.
+const LATENCY_NOISE_WINDOW: Duration = Duration::from_millis(300);
+/// How long the tone is given to show up in the capture.
+const LATENCY_LISTEN_WINDOW: Duration = Duration::from_millis(1500);
+const LATENCY_TONE_HZ: f32 = 1000.0;
+const LATENCY_TONE_DURATION: Duration = Duration::from_millis(150);
+const LATENCY_TONE_AMPLITUDE: f32 = 0.5;
+
+/// Result of [`measure_capture_latency`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureLatency {
+    /// From the tone being handed to the output device to the capture
+    /// callback that delivered its start: output buffering, the acoustic
+    /// path and input buffering together.
+    device_latency_ms: f64,
+    /// Longest wait for `chunk_frames` 16 kHz samples to fill a chunk before
+    /// it is sent to the frontend.
+    chunk_ms: f64,
+    total_ms: f64,
+    chunk_frames: usize,
+    input_device: String,
+    output_device: String,
+}
+
+/// Mono capture of the calibration, with when each callback arrived.
+#[derive(Default)]
+struct LatencyRecording {
+    samples: Vec<f32>,
+    /// Sample count after each callback, and when that callback ran.
+    arrivals: Vec<(usize, Instant)>,
+}
+
+fn build_latency_input_stream<T>(
+    device: &cpal::Device,
+    config: cpal::StreamConfig,
+    recording: Arc<Mutex<LatencyRecording>>,
+) -> Result<cpal::Stream, cpal::Error>
+where
+    T: cpal::SizedSample,
+    f32: cpal::FromSample<T>,
+{
+    let channels = config.channels.max(1) as usize;
+    device.build_input_stream(
+        config,
+        move |data: &[T], _: &_| {
+            let arrived = Instant::now();
+            let Ok(mut recording) = recording.lock() else {
+                return;
+            };
+            recording.samples.extend(data.chunks(channels).map(|frame| {
+                frame
+                    .iter()
+                    .map(|sample| cpal::Sample::to_sample::<f32>(*sample))
+                    .sum::<f32>()
+                    / frame.len() as f32
+            }));
+            let captured = recording.samples.len();
+            recording.arrivals.push((captured, arrived));
+        },
+        |error| eprintln!("[Audio] Latency calibration capture error: {}", error),
+        None,
+    )
+}
+
+fn build_latency_tone_stream<T>(
+    device: &cpal::Device,
+    config: cpal::StreamConfig,
+    tone_started: Arc<Mutex<Option<Instant>>>,
+) -> Result<cpal::Stream, cpal::Error>
+where
+    T: cpal::SizedSample + cpal::FromSample<f32>,
+{
+    let channels = config.channels.max(1) as usize;
+    let sample_rate = config.sample_rate as f32;
+    let tone_frames = (LATENCY_TONE_DURATION.as_secs_f32() * sample_rate) as usize;
+    let mut frame_index = 0_usize;
+    device.build_output_stream(
+        config,
+        move |data: &mut [T], _: &_| {
+            if frame_index == 0
+                && let Ok(mut started) = tone_started.lock()
+            {
+                started.get_or_insert_with(Instant::now);
+            }
+            for frame in data.chunks_mut(channels) {
+                let value = if frame_index < tone_frames {
+                    let phase = frame_index as f32 * LATENCY_TONE_HZ / sample_rate;
+                    LATENCY_TONE_AMPLITUDE * (std::f32::consts::TAU * phase).sin()
+                } else {
+                    0.0
+                };
+                frame.fill(T::from_sample(value));
+                frame_index += 1;
+            }
+        },
+        |error| eprintln!("[Audio] Latency calibration playback error: {}", error),
+        None,
+    )
+}
+
+/// Plays a short tone on the default output device while capturing from the
+/// microphone, and measures how long the tone takes to show up in the
+/// captured audio. Blocks for about two seconds. The microphone has to hear
+/// the speakers, so headphones make the calibration fail.
+pub fn measure_capture_latency(
+    device_name: Option<String>,
+    chunk_frames: Option<u32>,
+) -> Result<CaptureLatency, String> {
+    let kind = CaptureKind::Microphone;
+    let chunk_frames =
+        resolve_capture_chunk_frames(chunk_frames).map_err(|error| error.to_string())?;
+    let host = cpal::default_host();
+    let input = match device_name.as_deref() {
+        Some(name) => find_capture_device(&host, kind, name)
+            .ok_or_else(|| format!("{} device not found: {}", kind.label(), name))?,
+        None => default_capture_device(&host, kind)
+            .ok_or_else(|| kind.no_device_message().to_string())?,
+    };
+    let output = host
+        .default_output_device()
+        .ok_or_else(|| "No output device available to play the calibration tone".to_string())?;
+
+    let input_config =
+        default_capture_config(&input, kind).map_err(|error| kind.config_error_message(error))?;
+    let input_sample_rate = input_config.sample_rate();
+    let recording = Arc::new(Mutex::new(LatencyRecording::default()));
+    let input_stream = match input_config.sample_format() {
+        SampleFormat::F32 => {
+            build_latency_input_stream::<f32>(&input, input_config.into(), recording.clone())
+        }
+        SampleFormat::I16 => {
+            build_latency_input_stream::<i16>(&input, input_config.into(), recording.clone())
+        }
+        SampleFormat::U16 => {
+            build_latency_input_stream::<u16>(&input, input_config.into(), recording.clone())
+        }
+        other => {
+            return Err(format!(
+                "{}: {}",
+                kind.unsupported_sample_format_message(),
+                other
+            ));
+        }
+    }
+    .map_err(|error| kind.build_stream_error_message(error))?;
+    input_stream
+        .play()
+        .map_err(|error| kind.play_stream_error_message(error))?;
+    thread::sleep(LATENCY_NOISE_WINDOW);
+
+    let output_config = output
+        .default_output_config()
+        .map_err(|error| format!("Failed to get output config: {}", error))?;
+    let tone_started = Arc::new(Mutex::new(None));
+    let output_stream = match output_config.sample_format() {
+        SampleFormat::F32 => {
+            build_latency_tone_stream::<f32>(&output, output_config.into(), tone_started.clone())
+        }
+        SampleFormat::I16 => {
+            build_latency_tone_stream::<i16>(&output, output_config.into(), tone_started.clone())
+        }
+        SampleFormat::U16 => {
+            build_latency_tone_stream::<u16>(&output, output_config.into(), tone_started.clone())
+        }
+        other => {
+            return Err(format!(
+                "Output device uses an unsupported sample format: {}",
+                other
+            ));
+        }
+    }
+    .map_err(|error| format!("Failed to build output stream: {}", error))?;
+    output_stream
+        .play()
+        .map_err(|error| format!("Failed to play calibration tone: {}", error))?;
+    thread::sleep(LATENCY_LISTEN_WINDOW);
+    drop(output_stream);
+    drop(input_stream);
+
+    let tone_started = tone_started
+        .lock()
+        .map_err(|error| error.to_string())?
+        .ok_or_else(|| "The output device never asked for the calibration tone".to_string())?;
+    let recording = recording.lock().map_err(|error| error.to_string())?;
+    // Samples delivered before the tone was handed over cannot contain it.
+    let noise_frames = recording
+        .arrivals
+        .iter()
+        .take_while(|(_, arrived)| *arrived < tone_started)
+        .last()
+        .map_or(0, |(captured, _)| *captured);
+    let onset = detect_tone_onset(&recording.samples, input_sample_rate, noise_frames)
+        .ok_or_else(|| {
+            format!(
+                "The calibration tone was not picked up by {}; raise the volume or move the microphone closer to the speakers",
+                input
+            )
+        })?;
+    let (_, onset_arrived) = recording
+        .arrivals
+        .iter()
+        .find(|(captured, _)| *captured > onset)
+        .ok_or_else(|| "The calibration tone arrived after the capture ended".to_string())?;
+
+    let device_latency_ms = onset_arrived.duration_since(tone_started).as_secs_f64() * 1000.0;
+    let chunk_ms = chunk_frames as f64 * 1000.0 / CAPTURE_RING_SAMPLE_RATE as f64;
+    let latency = CaptureLatency {
+        device_latency_ms,
+        chunk_ms,
+        total_ms: device_latency_ms + chunk_ms,
+        chunk_frames,
+        input_device: input.to_string(),
+        output_device: output.to_string(),
+    };
+    println!(
+        "[Audio] Capture latency: {:.1} ms device + {:.1} ms chunk ({} -> {})",
+        latency.device_latency_ms, latency.chunk_ms, latency.output_device, latency.input_device
+    );
+    Ok(latency)
+}
+
+const SELFTEST_SOURCE_SAMPLE_RATE: u32 = 48000;
+const SELFTEST_SOURCE_CHANNELS: u16 = 2;
+const SELFTEST_DURATION: Duration = Duration::from_secs(3);
+/// Frames handed over per simulated device callback, 10 ms of the source.
+const SELFTEST_CALLBACK_FRAMES: usize = 480;
+/// How far the delivered sample count may stray from the tone's length: the
+/// resampler holds the last partial chunk back, and FFmpeg rounds the tone
+/// to whole frames.
+const SELFTEST_SAMPLE_TOLERANCE: f64 = 0.1;
+/// Time allowed on top of the tone for FFmpeg to start and exit.
+const SELFTEST_TIMEOUT_SLACK: Duration = Duration::from_secs(10);
+
+/// Result of [`run_capture_selftest`].
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfTestReport {
+    passed: bool,
+    /// One entry per failed check.
+    failures: Vec<String>,
+    /// Chunks the worker side pulled from the capture ring.
+    packet_count: u64,
+    /// 16 kHz mono samples in those chunks.
+    samples: u64,
+    expected_samples: u64,
+    /// Highest chunk peak, in the units of the peak events.
+    peak_level: i16,
+    expected_peak_level: i16,
+    /// From the first synthetic frame entering the pipeline to the first
+    /// chunk reaching the worker side.
+    first_packet_latency_ms: Option<f64>,
+    chunk_frames: usize,
+}
+
+impl SelfTestReport {
+    fn failures(&self) -> Vec<String> {
+        let mut failures = Vec::new();
+        if self.packet_count == 0 {
+            failures.push("No audio reached the capture worker".to_string());
+            return failures;
+        }
+        let drift = self.samples.abs_diff(self.expected_samples) as f64;
+        if drift > self.expected_samples as f64 * SELFTEST_SAMPLE_TOLERANCE {
+            failures.push(format!(
+                "Received {} samples at {} Hz mono, expected about {}",
+                self.samples, CAPTURE_SAMPLE_RATE, self.expected_samples
+            ));
+        }
+        if self.peak_level < self.expected_peak_level / 2
+            || self.peak_level > self.expected_peak_level.saturating_mul(2)
+        {
+            failures.push(format!(
+                "Peak level {} does not match the test tone ({})",
+                self.peak_level, self.expected_peak_level
+            ));
+        }
+        failures
+    }
+}
+
+/// Feeds FFmpeg's output to [`process_capture_audio`] in device-sized
+/// callbacks, the way the cpal thread does, until the tone ends.
+fn feed_selftest_source(
+    window: Window,
+    mut child: std::process::Child,
+    chunk_frames: usize,
+    data_tx: tokio::sync::mpsc::Sender<()>,
+    mut task_producer: impl Producer<Item = f32>,
+    first_frame_at: Arc<Mutex<Option<Instant>>>,
+) -> Result<(), String> {
+    use std::io::Read;
+
+    let kind = CaptureKind::Microphone;
+    let channels = SELFTEST_SOURCE_CHANNELS as usize;
+    let mut resampler = FftFixedOut::<f32>::new(
+        SELFTEST_SOURCE_SAMPLE_RATE as usize,
+        CAPTURE_SAMPLE_RATE as usize,
+        chunk_frames,
+        2,
+        1,
+    )
+    .map_err(|error| kind.resampler_error_message(error))?;
+    let input_frames_next = resampler.input_frames_next();
+    let rb = HeapRb::<f32>::new(input_frames_next * 4);
+    let (mut producer, mut consumer) = rb.split();
+    let mut input_buffer: Vec<Vec<f32>> = vec![vec![0.0; input_frames_next]; 1];
+    let mut output_buffer: Vec<Vec<f32>> = vec![vec![0.0; chunk_frames]; 1];
+    let mut captured_samples = 0_u64;
+    // Paused keeps the tone out of the level meters and position of any
+    // real capture; levels are checked on the worker side instead.
+    let paused = AtomicBool::new(true);
+
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| "FFmpeg stdout was not captured".to_string())?;
+    let mut bytes = vec![0_u8; SELFTEST_CALLBACK_FRAMES * channels * 4];
+    loop {
+        match stdout.read_exact(&mut bytes) {
+            Ok(()) => {}
+            Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(format!("Failed to read FFmpeg output: {}", error)),
+        }
+        if let Ok(mut first) = first_frame_at.lock() {
+            first.get_or_insert_with(Instant::now);
+        }
+        let data: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|sample| f32::from_le_bytes([sample[0], sample[1], sample[2], sample[3]]))
+            .collect();
+        process_capture_audio(
+            kind,
+            &data,
+            channels,
+            None,
+            &mut producer,
+            &mut consumer,
+            &mut resampler,
+            &mut input_buffer,
+            &mut output_buffer,
+            &window,
+            &data_tx,
+            &mut task_producer,
+            1.0,
+            &paused,
+            &mut captured_samples,
+            &mut None,
+        );
+    }
+
+    let output = child
+        .wait_with_output()
+        .map_err(|error| format!("Failed to wait for FFmpeg: {}", error))?;
+    if !output.status.success() {
+        return Err(format!(
+            "FFmpeg exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// Pushes a few seconds of FFmpeg's synthetic sine through the capture
+/// pipeline — resampling, the capture ring, the worker notification and the
+/// level computation — and checks what comes out. No device is opened, so a
+/// pass with a failing capture points at the device rather than the
+/// plumbing. Nothing reaches transcription or recordings.
+pub async fn run_capture_selftest(
+    window: Window,
+    chunk_frames: Option<u32>,
+) -> Result<SelfTestReport, String> {
+    let chunk_frames =
+        resolve_capture_chunk_frames(chunk_frames).map_err(|error| error.to_string())?;
+    let child = sona_local_asr::audio::spawn_ffmpeg_test_tone(
+        SELFTEST_SOURCE_SAMPLE_RATE,
+        SELFTEST_SOURCE_CHANNELS,
+        SELFTEST_DURATION,
+    )
+    .map_err(|error| error.to_string())?;
+
+    let task_rb = HeapRb::<f32>::new(16000 * 5);
+    let (task_producer, mut task_consumer) = task_rb.split();
+    let (data_tx, mut data_rx) = tokio::sync::mpsc::channel::<()>(100);
+    let first_frame_at = Arc::new(Mutex::new(None));
+    let source = {
+        let first_frame_at = first_frame_at.clone();
+        tauri::async_runtime::spawn_blocking(move || {
+            feed_selftest_source(
+                window,
+                child,
+                chunk_frames,
+                data_tx,
+                task_producer,
+                first_frame_at,
+            )
+        })
+    };
+
+    let mut packet_count = 0_u64;
+    let mut samples = 0_u64;
+    let mut peak_level = 0_i16;
+    let mut first_packet_at = None;
+    let mut pull_buffer = vec![0.0; 16000];
+    let mut pull = |consumer: &mut ringbuf::HeapCons<f32>| {
+        let len = consumer.pop_slice(&mut pull_buffer);
+        if len == 0 {
+            return false;
+        }
+        first_packet_at.get_or_insert_with(Instant::now);
+        packet_count += 1;
+        samples += len as u64;
+        peak_level = peak_level.max(chunk_peak_i16(&pull_buffer[..len]));
+        true
+    };
+    let received = tokio::time::timeout(SELFTEST_DURATION + SELFTEST_TIMEOUT_SLACK, async {
+        while data_rx.recv().await.is_some() {
+            pull(&mut task_consumer);
+        }
+    })
+    .await;
+    while pull(&mut task_consumer) {}
+
+    if received.is_err() {
+        return Err(format!(
+            "The capture self-test did not finish within {:?}",
+            SELFTEST_DURATION + SELFTEST_TIMEOUT_SLACK
+        ));
+    }
+    source.await.map_err(|error| error.to_string())??;
+
+    let first_frame_at = *first_frame_at.lock().map_err(|error| error.to_string())?;
+    let mut report = SelfTestReport {
+        passed: false,
+        failures: Vec::new(),
+        packet_count,
+        samples,
+        expected_samples: (SELFTEST_DURATION.as_secs_f64() * CAPTURE_SAMPLE_RATE as f64) as u64,
+        peak_level,
+        expected_peak_level: chunk_peak_i16(&[sona_local_asr::audio::FFMPEG_TEST_TONE_PEAK]),
+        first_packet_latency_ms: first_frame_at
+            .zip(first_packet_at)
+            .map(|(fed, arrived)| arrived.duration_since(fed).as_secs_f64() * 1000.0),
+        chunk_frames,
+    };
+    report.failures = report.failures();
+    report.passed = report.failures.is_empty();
+    println!(
+        "[Audio] Capture self-test {}: packets={}, samples={}/{}, peak={}/{}, first_packet_latency_ms={:?}",
+        if report.passed { "passed" } else { "failed" },
+        report.packet_count,
+        report.samples,
+        report.expected_samples,
+        report.peak_level,
+        report.expected_peak_level,
+        report.first_packet_latency_ms
+    );
+    Ok(report)
+}
+
+/// Lists the capture backends usable on this OS. Backends that need FFmpeg
+/// are left out when the bundled FFmpeg lacks them or cannot be probed.
+pub fn get_capture_backends() -> Vec<CaptureBackend> {
+    let ffmpeg_input_devices =
+        sona_local_asr::audio::probe_ffmpeg_input_devices().unwrap_or_else(|error| {
+            eprintln!("[Audio] Failed to probe FFmpeg input devices: {error}");
+            Vec::new()
+        });
+    let mut backends = capture_backends(std::env::consts::OS, &ffmpeg_input_devices);
+    if cfg!(target_os = "linux")
+        && !detect_linux_audio_backend().is_some_and(|backend| backend.pulse_compatible)
+    {
+        // FFmpeg's pulse input only works against a PulseAudio-compatible
+        // server; on bare ALSA or PipeWire without pipewire-pulse it fails.
+        backends.retain(|backend| backend.id != "pulse");
+    }
+    backends
+}
+
+/// Detects the sound server running on Linux: `pactl info` when a
+/// PulseAudio-compatible server answers, `wpctl status` for PipeWire without
+/// its PulseAudio layer, and ALSA when only the kernel driver is there.
+/// `None` on other OSes, or when not even ALSA is present.
+pub fn detect_linux_audio_backend() -> Option<LinuxAudioBackend> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let run = |program: &str, arg: &str| {
+        std::process::Command::new(program)
+            .arg(arg)
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+    };
+
+    let backend = if let Some(server) = run("pactl", "info")
+        .and_then(|output| parse_pactl_server(&String::from_utf8_lossy(&output.stdout)))
+    {
+        LinuxAudioBackend {
+            server,
+            pulse_compatible: true,
+        }
+    } else if run("wpctl", "status").is_some() {
+        LinuxAudioBackend {
+            server: LinuxAudioServer::PipeWire,
+            pulse_compatible: false,
+        }
+    } else if std::path::Path::new("/proc/asound/cards").exists() {
+        LinuxAudioBackend {
+            server: LinuxAudioServer::Alsa,
+            pulse_compatible: false,
+        }
+    } else {
+        eprintln!("[Audio] No Linux audio backend detected");
+        return None;
+    };
+    println!("[Audio] Linux audio backend: {:?}", backend);
+    Some(backend)
+}
+
+/// Progress of [`trim_silence`]. `total_seconds` is the input length and is
+/// missing until FFmpeg has probed the file.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TrimSilencePayload {
+    processed_seconds: f64,
+    total_seconds: Option<f64>,
+}
+
+/// Writes a copy of a recording without its leading and trailing silence and
+/// returns the new duration in seconds.
+pub async fn trim_silence<R: Runtime>(
+    app: AppHandle<R>,
+    input_path: String,
+    output_path: String,
+    threshold_db: Option<f64>,
+    min_silence_ms: Option<u32>,
+) -> Result<f64, String> {
+    sona_local_asr::audio::trim_silence(
+        std::path::Path::new(&input_path),
+        std::path::Path::new(&output_path),
+        threshold_db,
+        min_silence_ms,
+        |processed_seconds, total_seconds| {
+            let _ = app.emit(
+                TRIM_SILENCE_PROGRESS_EVENT,
+                TrimSilencePayload {
+                    processed_seconds,
+                    total_seconds,
+                },
+            );
+        },
+    )
+    .await
+    .map_err(|error| error.to_string())
+}
+
 pub fn get_microphone_devices() -> Result<Vec<AudioDevice>, String> {
     let host = cpal::default_host();
     let devices = host.input_devices().map_err(|e| e.to_string())?;
 
-    let result = devices
-        .map(|device| AudioDevice {
-            name: device.to_string(),
-        })
-        .collect();
+    let mut result = default_role_devices(CaptureKind::Microphone);
+    result.extend(devices.map(|device| AudioDevice {
+        name: device.to_string(),
+        role: None,
+    }));
 
     Ok(result)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn start_microphone_capture(
     app: AppHandle,
     window: Window,
@@ -946,6 +2196,18 @@ pub fn start_microphone_capture(
     device_name: Option<String>,
     instance_id: String,
     output_path: Option<String>,
+    record_codec: Option<String>,
+    chunk_frames: Option<u32>,
+    input_channel: Option<u16>,
+    agc: Option<bool>,
+    agc_target_dbfs: Option<f32>,
+    capture_ring_seconds: Option<u32>,
+    wait_for_audio: Option<bool>,
+    pipe_path: Option<String>,
+    pipe_format: Option<String>,
+    pipe_sample_rate: Option<u32>,
+    pipe_channels: Option<u16>,
+    pipe_sample_format: Option<String>,
 ) -> Result<(), String> {
     start_shared_capture(
         app,
@@ -955,6 +2217,16 @@ pub fn start_microphone_capture(
         device_name,
         instance_id,
         output_path,
+        resolve_record_codec(record_codec).map_err(|error| error.to_string())?,
+        resolve_capture_chunk_frames(chunk_frames).map_err(|error| error.to_string())?,
+        input_channel,
+        resolve_capture_agc(agc, agc_target_dbfs).map_err(|error| error.to_string())?,
+        resolve_capture_ring_seconds(capture_ring_seconds).map_err(|error| error.to_string())?,
+        wait_for_audio.unwrap_or(false),
+        pipe_path,
+        resolve_capture_pipe_format(pipe_format).map_err(|error| error.to_string())?,
+        resolve_capture_audio_config(pipe_sample_rate, pipe_channels, pipe_sample_format)
+            .map_err(|error| error.to_string())?,
     )
 }
 
@@ -1000,6 +2272,7 @@ fn process_capture_audio<R: Runtime>(
     kind: CaptureKind,
     data: &[f32],
     channels: usize,
+    selected_channel: Option<usize>,
     producer: &mut impl Producer<Item = f32>,
     consumer: &mut impl Consumer<Item = f32>,
     resampler: &mut FftFixedOut<f32>,
@@ -1009,13 +2282,15 @@ fn process_capture_audio<R: Runtime>(
     data_tx: &tokio::sync::mpsc::Sender<()>,
     task_producer: &mut impl Producer<Item = f32>,
     boost: f32,
+    all_paused: &AtomicBool,
+    captured_samples: &mut u64,
+    agc: &mut Option<AutomaticGainControl>,
 ) {
     for frame in data.chunks(channels) {
-        let mut sum = 0.0;
-        for sample in frame {
-            sum += sample;
-        }
-        let mut mono_sample = sum / channels as f32;
+        let mut mono_sample = match selected_channel {
+            Some(index) => frame.get(index).copied().unwrap_or(0.0),
+            None => frame.iter().sum::<f32>() / channels as f32,
+        };
 
         if matches!(kind, CaptureKind::Microphone) && (boost - 1.0).abs() > f32::EPSILON {
             mono_sample = (mono_sample * boost).clamp(-1.0, 1.0);
@@ -1035,20 +2310,29 @@ fn process_capture_audio<R: Runtime>(
         match result {
             Ok((_in_len, out_len)) => {
                 if out_len > 0 {
-                    let output_f32 = &output_buffer[0][..out_len];
+                    let output_f32 = &mut output_buffer[0][..out_len];
+                    if let Some(agc) = agc.as_mut() {
+                        agc.process(output_f32);
+                    }
+                    let output_f32 = &*output_f32;
 
                     let _ = task_producer.push_slice(output_f32);
                     let _ = data_tx.try_send(());
+                    *captured_samples += out_len as u64;
 
-                    let mut max_abs = 0.0_f32;
-                    for &sample in output_f32 {
-                        let abs_val = sample.abs();
-                        if abs_val > max_abs {
-                            max_abs = abs_val;
-                        }
+                    // The device stays open while paused so resume is
+                    // instant, but the frontend gets no level updates.
+                    if all_paused.load(Ordering::Relaxed) {
+                        continue;
                     }
-                    let peak_i16 = (max_abs.clamp(0.0, 1.0) * 32767.0) as i16;
-                    let _ = window.app_handle().emit(kind.peak_event(), peak_i16);
+
+                    let _ = window
+                        .app_handle()
+                        .emit(kind.peak_event(), chunk_peak_i16(output_f32));
+                    let _ = window.app_handle().emit(
+                        CAPTURE_POSITION_EVENT,
+                        CapturePositionPayload::new(kind, *captured_samples),
+                    );
                 }
             }
             Err(e) => {
@@ -1062,6 +2346,14 @@ fn process_capture_audio<R: Runtime>(
     }
 }
 
+/// Peak level of a resampled chunk as sent in the peak events.
+fn chunk_peak_i16(chunk: &[f32]) -> i16 {
+    let max_abs = chunk
+        .iter()
+        .fold(0.0_f32, |max_abs, sample| max_abs.max(sample.abs()));
+    (max_abs.clamp(0.0, 1.0) * 32767.0) as i16
+}
+
 pub async fn stop_microphone_capture(
     state: tauri::State<'_, AudioState>,
     instance_id: String,
@@ -1110,27 +2402,13 @@ async fn stop_shared_capture(
 
     let mut saved_path = String::new();
     if was_recording {
-        let (tx, rx) = tokio::sync::oneshot::channel();
-        let sent = detach_result
-            .recorder_tx
-            .as_ref()
-            .map(|recorder_tx| recorder_tx.try_send(RecorderCommand::Stop(tx)).is_ok())
-            .unwrap_or(false);
-
-        if sent {
-            match rx.await {
-                Ok(path) => saved_path = path,
-                Err(_) => eprintln!(
-                    "[Audio] Failed to receive {} WAV filepath from task",
-                    kind.log_name()
-                ),
-            }
-        } else {
-            eprintln!(
+        match finish_recording(kind, detach_result.recorder_tx.as_ref()).await {
+            Some((path, _)) => saved_path = path,
+            None => eprintln!(
                 "[Audio] {} recorder stop was requested for {}, but no recorder task was available",
                 kind.stop_log_label(),
                 instance_id
-            );
+            ),
         }
     }
 
@@ -1147,10 +2425,179 @@ async fn stop_shared_capture(
             CaptureKind::Microphone => println!("[Audio] Mic stop requested but not running"),
         }
     }
+    // Dropping the stream closes the worker's data channel, which ends it.
+    if let Some(worker) = detach_result.worker {
+        finish_capture_worker(kind, worker, CAPTURE_WORKER_STOP_TIMEOUT).await;
+    }
 
     Ok(saved_path)
 }
 
+/// A finalized recording, as the WAV header and the saved file describe it.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingInfo {
+    /// The saved file, after any encoding to the recording codec.
+    path: String,
+    duration_secs: f64,
+    sample_rate: u32,
+    channels: u16,
+    /// Size of the saved file.
+    bytes: u64,
+}
+
+/// Closes the file `recorder_tx`'s task is recording and encodes it. Returns
+/// the saved path, empty when nothing was recording, and the file's info when
+/// the WAV was finalized. `None` when the recorder task is gone.
+async fn finish_recording(
+    kind: CaptureKind,
+    recorder_tx: Option<&tokio::sync::mpsc::Sender<RecorderCommand>>,
+) -> Option<(String, Option<RecordingInfo>)> {
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    recorder_tx?.try_send(RecorderCommand::Stop(tx)).ok()?;
+    let Ok((path, codec, wav_info)) = rx.await else {
+        eprintln!(
+            "[Audio] Failed to receive {} WAV filepath from task",
+            kind.log_name()
+        );
+        return Some((String::new(), None));
+    };
+
+    let saved_path = encode_saved_recording(kind, path, codec).await;
+    let info = wav_info.map(|wav_info| {
+        let bytes = if std::path::Path::new(&saved_path) == wav_info.path {
+            wav_info.bytes
+        } else {
+            std::fs::metadata(&saved_path).map_or(0, |metadata| metadata.len())
+        };
+        RecordingInfo {
+            path: saved_path.clone(),
+            duration_secs: wav_info.duration_secs,
+            sample_rate: wav_info.sample_rate,
+            channels: wav_info.channels,
+            bytes,
+        }
+    });
+    Some((saved_path, info))
+}
+
+/// Finalizes the file a running capture is recording to while the capture
+/// keeps running, and returns its info. `None` when nothing was recording.
+/// Stopping the recording owner afterwards reports an empty saved path.
+pub async fn finalize_recording(
+    state: tauri::State<'_, AudioState>,
+    source: Option<String>,
+) -> Result<Option<RecordingInfo>, String> {
+    let kind = CaptureKind::from_source(source.as_deref())?;
+    let recorder_tx = {
+        let capture = kind.capture(&state).lock().map_err(|e| e.to_string())?;
+        if !capture.is_running() {
+            return Err(format!("{} capture is not running", kind.label()));
+        }
+        capture.recorder_tx.clone()
+    };
+    let (_, info) = finish_recording(kind, recorder_tx.as_ref())
+        .await
+        .ok_or_else(|| format!("{} recorder task is not available", kind.label()))?;
+    if let Some(info) = &info {
+        println!(
+            "[Audio] Finalized {} recording {} ({:.2}s, {} bytes)",
+            kind.log_name(),
+            info.path,
+            info.duration_secs,
+            info.bytes
+        );
+    }
+    Ok(info)
+}
+
+async fn encode_saved_recording(kind: CaptureKind, path: String, codec: RecordCodec) -> String {
+    if path.is_empty() || codec == RecordCodec::Pcm {
+        return path;
+    }
+
+    match sona_local_asr::audio::encode_recording(std::path::Path::new(&path), codec).await {
+        Ok(encoded) => encoded.to_string_lossy().into_owned(),
+        Err(e) => {
+            // Keep the WAV rather than losing the recording.
+            eprintln!(
+                "[Audio] Failed to encode {} recording {} as {}: {}",
+                kind.log_name(),
+                path,
+                codec.as_str(),
+                e
+            );
+            path
+        }
+    }
+}
+
+/// A capture owner detached by [`stop_and_report_all_audio_captures`].
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoppedCapture {
+    source: &'static str,
+    instance_id: String,
+    /// Finalized recording; empty when the owner was not recording.
+    saved_path: String,
+}
+
+/// Detaches every owner from both capture kinds, finalizing any recordings.
+pub async fn stop_all_audio_captures(state: tauri::State<'_, AudioState>) -> Result<(), String> {
+    stop_and_report_all_audio_captures(&state).await.map(|_| ())
+}
+
+/// [`stop_all_audio_captures`], reporting which owners were stopped.
+pub(crate) async fn stop_and_report_all_audio_captures(
+    state: &tauri::State<'_, AudioState>,
+) -> Result<Vec<StoppedCapture>, String> {
+    let mut stopped = Vec::new();
+    for kind in [CaptureKind::System, CaptureKind::Microphone] {
+        let owners = kind
+            .capture(state)
+            .lock()
+            .map_err(|e| e.to_string())?
+            .owners();
+        for instance_id in owners {
+            let saved_path = stop_shared_capture(state, kind, instance_id.clone()).await?;
+            stopped.push(StoppedCapture {
+                source: kind.log_name(),
+                instance_id,
+                saved_path,
+            });
+        }
+    }
+    Ok(stopped)
+}
+
+/// Saves the last `capture_ring_seconds` of a running capture to a WAV file.
+pub async fn dump_ring_buffer(
+    state: tauri::State<'_, AudioState>,
+    output_path: String,
+    source: Option<String>,
+) -> Result<String, String> {
+    let kind = CaptureKind::from_source(source.as_deref())?;
+    let recorder_tx = {
+        let capture = kind.capture(&state).lock().map_err(|e| e.to_string())?;
+        if !capture.is_running() {
+            return Err(format!("{} capture is not running", kind.label()));
+        }
+        capture.recorder_tx.clone()
+    }
+    .ok_or_else(|| format!("{} recorder task is not available", kind.label()))?;
+
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    recorder_tx
+        .try_send(RecorderCommand::DumpRing(output_path, tx))
+        .map_err(|err| format!("Failed to request {} ring buffer: {}", kind.log_name(), err))?;
+    rx.await.map_err(|_| {
+        format!(
+            "{} capture stopped before the ring buffer was saved",
+            kind.label()
+        )
+    })?
+}
+
 pub fn set_system_audio_capture_paused(
     state: tauri::State<'_, AudioState>,
     instance_id: String,
@@ -1183,6 +2630,67 @@ pub fn set_microphone_capture_paused(
     )
 }
 
+/// A hardware capture still running in this process.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningCapturePayload {
+    source: &'static str,
+    device_name: Option<String>,
+    instance_ids: Vec<String>,
+    paused_instance_ids: Vec<String>,
+}
+
+/// Lists the captures that kept running while no window was attached, e.g.
+/// after the main window was closed to the tray or reloaded. Capture events
+/// go to the whole app, so knowing what runs is all a window needs to pick
+/// them up again.
+pub fn reconnect_capture(
+    state: tauri::State<'_, AudioState>,
+) -> Result<Vec<RunningCapturePayload>, String> {
+    let running = running_captures(&state)?;
+    println!(
+        "[Audio] Reconnected to {} running capture(s)",
+        running.len()
+    );
+    Ok(running)
+}
+
+/// The hardware captures running right now, with their owners.
+pub(crate) fn running_captures(state: &AudioState) -> Result<Vec<RunningCapturePayload>, String> {
+    let mut running = Vec::new();
+    for kind in [CaptureKind::System, CaptureKind::Microphone] {
+        let capture = kind.capture(state).lock().map_err(|e| e.to_string())?;
+        running.extend(capture.running_capture(kind));
+    }
+    Ok(running)
+}
+
+/// Running captures as entries of the operations list. Captures paused by
+/// every owner show as paused.
+pub(crate) fn capture_operations(state: &AudioState) -> Result<Vec<Operation>, String> {
+    let mut operations = Vec::new();
+    for kind in [CaptureKind::System, CaptureKind::Microphone] {
+        let capture = kind.capture(state).lock().map_err(|e| e.to_string())?;
+        if !capture.is_running() {
+            continue;
+        }
+        let status = if capture.all_paused.load(Ordering::Relaxed) {
+            OperationStatus::Paused
+        } else {
+            OperationStatus::Running
+        };
+        operations.push(Operation {
+            id: kind.log_name().to_string(),
+            kind: OperationKind::Capture,
+            status,
+            progress: None,
+            started_at: capture.started_at.unwrap_or_default(),
+            detail: capture.active_device_name.clone(),
+        });
+    }
+    Ok(operations)
+}
+
 pub fn set_microphone_boost(state: tauri::State<'_, AudioState>, boost: f32) -> Result<(), String> {
     let mut mic_boost = state.mic_boost.lock().map_err(|e| e.to_string())?;
     *mic_boost = boost;
@@ -1194,6 +2702,108 @@ pub fn set_microphone_boost(state: tauri::State<'_, AudioState>, boost: f32) ->
 mod tests {
     use super::*;
 
+    #[test]
+    fn capture_selftest_checks_packets_sample_count_and_level() {
+        let report = SelfTestReport {
+            passed: false,
+            failures: Vec::new(),
+            packet_count: 30,
+            samples: 46_400,
+            expected_samples: 48_000,
+            peak_level: 4_000,
+            expected_peak_level: chunk_peak_i16(&[0.125]),
+            first_packet_latency_ms: Some(100.0),
+            chunk_frames: 1600,
+        };
+        assert!(report.failures().is_empty());
+
+        let failures = SelfTestReport {
+            samples: 16_000,
+            peak_level: 30_000,
+            ..report.clone()
+        }
+        .failures();
+        assert_eq!(failures.len(), 2);
+        assert!(failures[0].starts_with("Received 16000 samples"));
+        assert!(failures[1].starts_with("Peak level 30000"));
+
+        assert_eq!(
+            SelfTestReport {
+                packet_count: 0,
+                samples: 0,
+                ..report
+            }
+            .failures(),
+            vec!["No audio reached the capture worker".to_string()]
+        );
+    }
+
+    #[test]
+    fn capture_started_payload_reports_delivered_and_device_formats() {
+        let config = cpal::StreamConfig {
+            channels: 2,
+            sample_rate: 48000,
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let payload = CaptureStartedPayload::new(
+            CaptureKind::Microphone,
+            "record".to_string(),
+            "USB Mic".to_string(),
+            &config,
+            SampleFormat::I16,
+            512,
+            Some(2),
+            Some(-20.0),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            serde_json::json!({
+                "source": "microphone",
+                "instanceId": "record",
+                "deviceId": "USB Mic",
+                "sampleRate": 16000,
+                "channels": 1,
+                "sampleFormat": "f32",
+                "chunkFrames": 512,
+                "inputChannel": 2,
+                "agcTargetDbfs": -20.0,
+                "deviceSampleRate": 48000,
+                "deviceChannels": 2,
+                "deviceSampleFormat": "i16",
+                "startupDelayMs": null,
+            })
+        );
+    }
+
+    #[test]
+    fn first_audio_is_signalled_once_for_a_non_empty_callback() {
+        let (tx, rx) = channel();
+        let mut tx = Some(tx);
+
+        signal_first_audio(&mut tx, 0);
+        assert!(rx.try_recv().is_err());
+        signal_first_audio(&mut tx, 480);
+        signal_first_audio(&mut tx, 480);
+
+        assert_eq!(rx.try_iter().count(), 1);
+        assert!(tx.is_none());
+    }
+
+    #[test]
+    fn capture_position_payload_derives_milliseconds_from_samples() {
+        let payload = CapturePositionPayload::new(CaptureKind::System, 24_008);
+
+        assert_eq!(
+            serde_json::to_value(&payload).unwrap(),
+            serde_json::json!({
+                "source": "system",
+                "samples": 24_008,
+                "positionMs": 1500,
+            })
+        );
+    }
+
     #[test]
     fn shared_capture_state_only_becomes_running_after_commit() {
         let mut capture = SharedCaptureState::default();
@@ -1218,6 +2828,33 @@ mod tests {
         assert!(capture.recorder_tx.is_some());
     }
 
+    #[test]
+    fn running_capture_reports_owners_and_paused_instances() {
+        let mut capture = SharedCaptureState::default();
+        assert!(capture.running_capture(CaptureKind::Microphone).is_none());
+
+        let (stop_tx, _stop_rx) = channel::<()>();
+        let (recorder_tx, _recorder_rx) = tokio::sync::mpsc::channel::<RecorderCommand>(1);
+        capture.commit_start(
+            "record".to_string(),
+            "default mic".to_string(),
+            stop_tx,
+            recorder_tx,
+        );
+        capture.attach_instance("caption".to_string());
+        update_capture_pause_state(&mut capture, "caption", true, "Mic", false).unwrap();
+
+        assert_eq!(
+            capture.running_capture(CaptureKind::Microphone),
+            Some(RunningCapturePayload {
+                source: "microphone",
+                device_name: Some("default mic".to_string()),
+                instance_ids: vec!["caption".to_string(), "record".to_string()],
+                paused_instance_ids: vec!["caption".to_string()],
+            })
+        );
+    }
+
     #[test]
     fn shared_capture_state_failed_start_leaves_no_runtime_state() {
         let capture = SharedCaptureState::default();
@@ -1283,6 +2920,52 @@ mod tests {
         assert!(capture.active_device_name.is_none());
     }
 
+    #[tokio::test]
+    async fn only_the_final_detach_hands_over_the_worker() {
+        let mut capture = SharedCaptureState::default();
+        let (stop_tx, _stop_rx) = channel::<()>();
+        let (recorder_tx, _recorder_rx) = tokio::sync::mpsc::channel::<RecorderCommand>(1);
+        capture.commit_start(
+            "record".to_string(),
+            "default mic".to_string(),
+            stop_tx,
+            recorder_tx,
+        );
+        capture.worker = Some(tauri::async_runtime::spawn(async {}));
+        capture.attach_instance("preview".to_string());
+
+        assert!(capture.detach_instance("preview").worker.is_none());
+        assert!(capture.detach_instance("record").worker.is_some());
+        assert!(capture.worker.is_none());
+    }
+
+    #[tokio::test]
+    async fn finishing_a_worker_waits_for_its_tail_or_aborts_it() {
+        let (fed_tx, mut fed_rx) = tokio::sync::mpsc::unbounded_channel::<&str>();
+        let (data_tx, mut data_rx) = tokio::sync::mpsc::channel::<()>(4);
+        let draining_fed = fed_tx.clone();
+        let draining = tauri::async_runtime::spawn(async move {
+            while data_rx.recv().await.is_some() {}
+            let _ = draining_fed.send("tail");
+        });
+        data_tx.send(()).await.unwrap();
+        drop(data_tx);
+
+        finish_capture_worker(CaptureKind::Microphone, draining, Duration::from_secs(1)).await;
+        assert_eq!(fed_rx.try_recv(), Ok("tail"));
+
+        let stuck_fed = fed_tx;
+        let stuck = tauri::async_runtime::spawn(async move {
+            std::future::pending::<()>().await;
+            let _ = stuck_fed.send("late");
+        });
+        finish_capture_worker(CaptureKind::Microphone, stuck, Duration::from_millis(20)).await;
+
+        // The aborted worker drops its sender without ever sending.
+        let closed = tokio::time::timeout(Duration::from_secs(1), fed_rx.recv()).await;
+        assert_eq!(closed, Ok(None));
+    }
+
     #[test]
     fn shared_capture_state_pause_filters_active_instances_without_detaching_owner() {
         let mut capture = SharedCaptureState::default();
@@ -1304,6 +2987,13 @@ mod tests {
         );
         assert_eq!(active_instances, vec!["voice-typing".to_string()]);
         assert_eq!(capture.active_instances(), vec!["voice-typing".to_string()]);
+        assert!(!capture.all_paused_flag().load(Ordering::Relaxed));
+
+        capture.set_instance_paused("voice-typing", true).unwrap();
+        assert!(capture.all_paused_flag().load(Ordering::Relaxed));
+
+        capture.attach_instance("record".to_string());
+        assert!(!capture.all_paused_flag().load(Ordering::Relaxed));
     }
 
     #[test]
@@ -1357,6 +3047,49 @@ mod tests {
         assert!(capture.active_instances().is_empty());
     }
 
+    #[test]
+    fn capture_kind_from_source_defaults_to_microphone() {
+        assert!(matches!(
+            CaptureKind::from_source(None),
+            Ok(CaptureKind::Microphone)
+        ));
+        assert!(matches!(
+            CaptureKind::from_source(Some("system")),
+            Ok(CaptureKind::System)
+        ));
+        assert!(CaptureKind::from_source(Some("speaker")).is_err());
+    }
+
+    #[test]
+    fn check_capture_stream_config_reports_what_a_start_would_reject() {
+        let config = cpal::StreamConfig {
+            channels: 2,
+            sample_rate: 48000,
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let check = |sample_format, input_channel| {
+            check_capture_stream_config(
+                CaptureKind::Microphone,
+                &config,
+                sample_format,
+                1600,
+                input_channel,
+            )
+        };
+
+        assert!(check(SampleFormat::I16, Some(2)).is_ok());
+        assert!(
+            check(SampleFormat::F64, None)
+                .unwrap_err()
+                .starts_with("Unsupported mic sample format")
+        );
+        assert!(
+            check(SampleFormat::F32, Some(3))
+                .unwrap_err()
+                .contains("input_channel")
+        );
+    }
+
     #[test]
     fn resolve_recording_output_path_prefers_explicit_output_path() {
         let resolved = resolve_recording_output_path(Some("C:/tmp/custom.wav".to_string()), || {
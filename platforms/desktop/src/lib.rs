@@ -106,6 +106,11 @@ pub fn run_app() -> Result<(), tauri::Error> {
         )
         .setup(crate::app::setup::init)
         .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Resized(_) = event
+                && window.label() == "main"
+            {
+                crate::app::window_visibility::sync_main_window_minimized(window);
+            }
             if let tauri::WindowEvent::CloseRequested { api, .. } = event {
                 let app = window.app_handle();
                 let state = app.state::<crate::app::settings::AppSettings>();
@@ -117,7 +122,7 @@ pub fn run_app() -> Result<(), tauri::Error> {
                 ) {
                     crate::app::settings::MainWindowCloseAction::Ignore => {}
                     crate::app::settings::MainWindowCloseAction::HideToTray => {
-                        let _ = window.hide();
+                        crate::app::window_visibility::hide_main_window(window);
                         api.prevent_close();
                     }
                     crate::app::settings::MainWindowCloseAction::RequestQuit => {
@@ -131,15 +136,17 @@ pub fn run_app() -> Result<(), tauri::Error> {
         .plugin(tauri_plugin_window_state::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
             if let Some(window) = app.get_webview_window("main") {
-                let _ = window.unminimize();
-                let _ = window.show();
-                let _ = window.set_focus();
+                crate::app::window_visibility::show_main_window(&window);
             }
         }))
         .manage(crate::platform::model_downloads::DownloadState::new())
         .manage(crate::app::server::ApiServerController::default())
         .manage(app_settings)
         .manage(crate::app::window_state::AuxWindowStateStore::default())
+        .manage(crate::app::window_visibility::MainWindowVisibility::default())
+        .manage(crate::app::launch::LaunchReportState::default())
+        .manage(crate::platform::power::SuspendState::default())
+        .manage(crate::app::tray::TrayMenuState::default())
         .manage(crate::platform::automation_runtime::AutomationRuntimeState::default())
         .manage(crate::platform::history_repository::HistoryRepositoryState::default())
         .manage(crate::platform::history_repository::PreparedBackupImportState::default())
@@ -23,7 +23,9 @@ pub fn resolve_api_server_runtime_dirs_for_app<R: tauri::Runtime>(
     app: &tauri::AppHandle<R>,
 ) -> Result<ApiServerRuntimeDirs, String> {
     let provider = TauriPathProvider::from_app(app);
-    resolve_api_server_runtime_dirs(&provider)
+    let mut dirs = resolve_api_server_runtime_dirs(&provider)?;
+    dirs.models_dir = crate::platform::paths::models_dir_for_app(app)?;
+    Ok(dirs)
 }
 
 #[cfg(test)]
@@ -1,21 +1,168 @@
-use tauri::Emitter;
+use sona_archive::ExtractSummary;
+use std::path::{Component, Path};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use tauri::{Emitter, Manager};
 
 use crate::platform::blocking::{map_err_string, spawn_blocking_map};
+use crate::platform::model_downloads::DownloadState;
+use crate::platform::overall_progress::OperationStatus;
+use crate::platform::write_scope::ensure_write_allowed;
 
 const EXTRACT_PROGRESS_EVENT: &str = "extract-progress";
+const EXTRACT_COMPLETE_EVENT: &str = "extract-complete";
+const EXTRACT_CANCELLED_EVENT: &str = "extract-cancelled";
 
-pub async fn extract_tar_bz2<R: tauri::Runtime>(
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtractProgressPayload<'a> {
+    #[serde(flatten)]
+    progress: &'a sona_archive::ExtractProgress<'a>,
+    /// Only set for extractions started `with_progress`.
+    percent: Option<f64>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExtractCompletePayload {
+    files_extracted: usize,
+    bytes_written: u64,
+    duration_ms: u64,
+    target_dir: String,
+}
+
+/// Joins `model_name` onto `models_dir`, refusing names that are not a single
+/// plain path component.
+fn model_target_dir(models_dir: &str, model_name: &str) -> Result<String, String> {
+    let mut components = Path::new(model_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(Path::new(models_dir)
+            .join(model_name)
+            .to_string_lossy()
+            .into_owned()),
+        _ => Err(format!("Invalid model name: {model_name}")),
+    }
+}
+
+/// Extracts a `.tar.bz2`, `.tar.gz`, `.tar.xz`, `.zip` or plain tar archive,
+/// whichever its leading bytes say it is, on a blocking thread.
+///
+/// With `model_name`, extracts into `<target_dir>/<model_name>` and, unless
+/// `strip_components` is given, moves the files up out of a lone top-level
+/// folder in the archive so models never end up in `name/name/`. With
+/// `verify`, the whole archive is decompressed once before anything is
+/// written, so a corrupt download fails with a clear error up front.
+/// `with_progress` does the same pass and also sums the entry sizes, so
+/// `extract-progress` carries a percentage; for bz2 that roughly doubles the
+/// time taken, so it is opt-in.
+///
+/// With `id`, [`cancel_extraction`] stops the extraction between entries;
+/// it then fails with "Extraction cancelled" after `extract-cancelled` is
+/// emitted. Files already extracted are kept for a `resume`.
+#[allow(clippy::too_many_arguments)]
+pub async fn extract_archive<R: tauri::Runtime>(
     app: tauri::AppHandle<R>,
     archive_path: String,
     target_dir: String,
-) -> Result<(), String> {
-    spawn_blocking_map(move || {
-        sona_archive::extract_tar_bz2(&archive_path, &target_dir, |path_str| {
-            let _ = app.emit(EXTRACT_PROGRESS_EVENT, path_str);
-        })
-        .map_err(map_err_string)
+    include: Option<Vec<String>>,
+    resume: bool,
+    model_name: Option<String>,
+    strip_components: Option<u32>,
+    verify: bool,
+    with_progress: bool,
+    id: Option<String>,
+) -> Result<ExtractSummary, String> {
+    let collapse_top_level = model_name.is_some() && strip_components.is_none();
+    let target_dir = match model_name {
+        Some(model_name) => model_target_dir(&target_dir, &model_name)?,
+        None => target_dir,
+    };
+    ensure_write_allowed(&app, Path::new(&target_dir))?;
+    let cancel = match &id {
+        Some(id) => app.state::<DownloadState>().insert_extraction(id)?,
+        None => Arc::new(AtomicBool::new(false)),
+    };
+    let state_app = app.clone();
+    let extraction_id = id.clone();
+    let result = spawn_blocking_map(move || {
+        let mut bytes_total = None;
+        if verify || with_progress {
+            let totals = sona_archive::scan_tar_bz2(&archive_path).map_err(|error| {
+                log::warn!("[archive] {archive_path} failed verification: {error}");
+                format!("downloaded archive is corrupt: {}", error.reason)
+            })?;
+            log::info!(
+                "[archive] Verified {} entries ({} bytes) in {archive_path}",
+                totals.entries,
+                totals.bytes
+            );
+            bytes_total = with_progress.then_some(totals.bytes);
+        }
+        let started = std::time::Instant::now();
+        let downloads = app.state::<DownloadState>();
+        let tracked_path = Path::new(&archive_path);
+        let summary = sona_archive::extract_tar_bz2_matching(
+            &archive_path,
+            &target_dir,
+            include.as_deref().unwrap_or_default(),
+            resume,
+            strip_components.unwrap_or(0),
+            bytes_total,
+            &cancel,
+            |progress| {
+                downloads
+                    .track_progress(|tracker| tracker.update_extraction(tracked_path, progress));
+                let _ = app.emit(
+                    EXTRACT_PROGRESS_EVENT,
+                    ExtractProgressPayload {
+                        progress,
+                        percent: progress.percent(),
+                    },
+                );
+            },
+        );
+        let status = match &summary {
+            Ok(_) => OperationStatus::Completed,
+            Err(error) if error.is_cancelled() => OperationStatus::Cancelled,
+            Err(_) => OperationStatus::Failed,
+        };
+        downloads.track_progress(|tracker| tracker.finish_extraction(tracked_path, status));
+        if status == OperationStatus::Cancelled {
+            log::info!("[archive] Cancelled extraction of {archive_path}");
+            let _ = app.emit(EXTRACT_CANCELLED_EVENT, extraction_id.as_deref());
+            return Err("Extraction cancelled".to_string());
+        }
+        let summary = summary.map_err(map_err_string)?;
+        if collapse_top_level
+            && sona_archive::collapse_single_top_level_dir(&target_dir).map_err(map_err_string)?
+        {
+            log::info!("[archive] Collapsed the top-level folder of {archive_path}");
+        }
+
+        let _ = app.emit(
+            EXTRACT_COMPLETE_EVENT,
+            ExtractCompletePayload {
+                files_extracted: summary.files_extracted,
+                bytes_written: summary.bytes_written,
+                duration_ms: started.elapsed().as_millis() as u64,
+                target_dir,
+            },
+        );
+        Ok::<_, String>(summary)
     })
-    .await
+    .await;
+    if let Some(id) = &id {
+        state_app.state::<DownloadState>().remove_extraction(id);
+    }
+    result
+}
+
+/// Stops the extraction started with `id`, if it is still running.
+pub fn cancel_extraction(state: tauri::State<'_, DownloadState>, id: String) -> Result<(), String> {
+    if state.cancel_extraction(&id) {
+        log::info!("[archive] Cancelling extraction {id}");
+    }
+    Ok(())
 }
 
 pub async fn create_tar_bz2(source_dir: String, archive_path: String) -> Result<(), String> {
@@ -24,3 +171,14 @@ pub async fn create_tar_bz2(source_dir: String, archive_path: String) -> Result<
     })
     .await
 }
+
+/// Progress left behind by an extraction that did not finish, for showing
+/// "resuming extraction" before calling `extract_tar_bz2` with `resume`.
+pub async fn get_extraction_checkpoint(
+    archive_path: String,
+) -> Result<Option<sona_archive::ExtractionCheckpoint>, String> {
+    spawn_blocking_map(move || {
+        Ok::<_, String>(sona_archive::read_extraction_checkpoint(&archive_path))
+    })
+    .await
+}
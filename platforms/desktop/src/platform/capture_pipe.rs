@@ -0,0 +1,491 @@
+//! FIFO (Unix) or named pipe (Windows) that a capture streams its audio into
+//! for external tools. The capture worker's 16 kHz mono stream goes out in
+//! the pipe's [`CaptureAudioConfig`] (16 kHz mono s16le unless asked
+//! otherwise), either bare or behind a streaming WAV header that every newly
+//! connected reader receives first.
+//!
+//! Writing never blocks capture: chunks are dropped while no reader is
+//! connected or while the reader falls behind.
+
+use std::io;
+use std::path::PathBuf;
+
+use rubato::{FftFixedIn, Resampler};
+use sona_core::runtime::capture::{CaptureAudioConfig, CapturePipeFormat, CaptureSampleFormat};
+
+/// Rate of the samples the capture worker hands to [`CapturePipe::write_samples`].
+const CAPTURE_SAMPLE_RATE: u32 = 16_000;
+/// Input frames per resampler pass (64 ms), which is also the latency
+/// resampling adds.
+const RESAMPLER_CHUNK_FRAMES: usize = 1024;
+
+pub(crate) struct CapturePipe {
+    path: PathBuf,
+    format: CapturePipeFormat,
+    encoder: PipeEncoder,
+    /// Rest of a chunk the pipe only took part of. It goes out before
+    /// anything newer so the reader never sees half a sample.
+    pending: Vec<u8>,
+    #[cfg(unix)]
+    sender: Option<tokio::net::unix::pipe::Sender>,
+    /// Only FIFOs created here are removed again.
+    #[cfg(unix)]
+    created: bool,
+    #[cfg(windows)]
+    server: tokio::net::windows::named_pipe::NamedPipeServer,
+    #[cfg(windows)]
+    connected: bool,
+}
+
+impl CapturePipe {
+    /// Creates the FIFO at `path`, or reuses an existing one.
+    #[cfg(unix)]
+    pub(crate) fn create(
+        path: &str,
+        format: CapturePipeFormat,
+        config: CaptureAudioConfig,
+    ) -> Result<Self, String> {
+        use std::os::unix::fs::FileTypeExt;
+
+        let encoder = PipeEncoder::new(config)?;
+
+        let path = PathBuf::from(path);
+        let created = match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_fifo() => false,
+            Ok(_) => {
+                return Err(format!(
+                    "Capture pipe {} exists and is not a FIFO",
+                    path.display()
+                ));
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                use std::os::unix::ffi::OsStrExt;
+
+                let c_path = std::ffi::CString::new(path.as_os_str().as_bytes())
+                    .map_err(|_| format!("Capture pipe path {} is invalid", path.display()))?;
+                // SAFETY: `c_path` is a valid NUL-terminated string.
+                if unsafe { libc::mkfifo(c_path.as_ptr(), 0o600) } != 0 {
+                    return Err(format!(
+                        "Failed to create capture pipe {}: {}",
+                        path.display(),
+                        io::Error::last_os_error()
+                    ));
+                }
+                true
+            }
+            Err(error) => {
+                return Err(format!(
+                    "Failed to inspect capture pipe {}: {error}",
+                    path.display()
+                ));
+            }
+        };
+        Ok(Self {
+            path,
+            format,
+            encoder,
+            pending: Vec::new(),
+            sender: None,
+            created,
+        })
+    }
+
+    /// Creates the named pipe `path`, e.g. `\\.\pipe\sona-capture`. Must be
+    /// called inside the async runtime.
+    #[cfg(windows)]
+    pub(crate) fn create(
+        path: &str,
+        format: CapturePipeFormat,
+        config: CaptureAudioConfig,
+    ) -> Result<Self, String> {
+        let encoder = PipeEncoder::new(config)?;
+        if !path.starts_with(r"\\.\pipe\") {
+            return Err(format!(
+                r"Capture pipe {path} must be a named pipe path such as \\.\pipe\sona-capture"
+            ));
+        }
+        let server = tokio::net::windows::named_pipe::ServerOptions::new()
+            .first_pipe_instance(true)
+            .create(path)
+            .map_err(|error| format!("Failed to create capture pipe {path}: {error}"))?;
+        Ok(Self {
+            path: PathBuf::from(path),
+            format,
+            encoder,
+            pending: Vec::new(),
+            server,
+            connected: false,
+        })
+    }
+
+    pub(crate) fn path(&self) -> &std::path::Path {
+        &self.path
+    }
+
+    pub(crate) fn format(&self) -> CapturePipeFormat {
+        self.format
+    }
+
+    pub(crate) fn config(&self) -> CaptureAudioConfig {
+        self.encoder.config
+    }
+
+    /// Queues the stream header for a reader that just connected; it goes
+    /// out like any other pending bytes, ahead of the next samples.
+    fn start_stream(&mut self) {
+        self.encoder.reset();
+        self.pending = self.format.stream_header(self.encoder.config);
+    }
+
+    /// Sends `samples` to the reader, if one is connected and keeping up.
+    /// Must be called inside the async runtime.
+    pub(crate) fn write_samples(&mut self, samples: &[f32]) {
+        if !self.connect() {
+            return;
+        }
+        if !self.pending.is_empty() {
+            let pending = std::mem::take(&mut self.pending);
+            if !self.send(&pending, true) {
+                // Still backed up; this chunk is dropped.
+                return;
+            }
+        }
+        let bytes = self.encoder.encode(samples);
+        if !bytes.is_empty() {
+            self.send(&bytes, false);
+        }
+    }
+
+    /// Writes what the pipe takes right now. The unsent rest is kept in
+    /// `pending` once part of `bytes` went out, or when `bytes` were pending
+    /// already; a chunk the pipe takes none of is dropped. True when all of
+    /// `bytes` were sent.
+    fn send(&mut self, bytes: &[u8], was_pending: bool) -> bool {
+        match self.try_write(bytes) {
+            Ok(written) if written == bytes.len() => true,
+            Ok(written) => {
+                self.pending = bytes[written..].to_vec();
+                false
+            }
+            Err(error) if error.kind() == io::ErrorKind::WouldBlock => {
+                if was_pending {
+                    self.pending = bytes.to_vec();
+                }
+                false
+            }
+            Err(error) => {
+                println!(
+                    "[Audio] Capture pipe {} reader disconnected: {}",
+                    self.path.display(),
+                    error
+                );
+                self.disconnect();
+                false
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn connect(&mut self) -> bool {
+        if self.sender.is_some() {
+            return true;
+        }
+        // Fails with ENXIO until a reader has the FIFO open.
+        match tokio::net::unix::pipe::OpenOptions::new().open_sender(&self.path) {
+            Ok(sender) => {
+                println!(
+                    "[Audio] Capture pipe {} reader connected",
+                    self.path.display()
+                );
+                self.sender = Some(sender);
+                self.start_stream();
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    #[cfg(windows)]
+    fn connect(&mut self) -> bool {
+        use futures_util::FutureExt;
+
+        if !self.connected
+            && let Some(result) = self.server.connect().now_or_never()
+        {
+            match result {
+                Ok(()) => {
+                    println!(
+                        "[Audio] Capture pipe {} reader connected",
+                        self.path.display()
+                    );
+                    self.connected = true;
+                    self.start_stream();
+                }
+                Err(error) => {
+                    eprintln!(
+                        "[Audio] Capture pipe {} connection failed: {}",
+                        self.path.display(),
+                        error
+                    );
+                    self.disconnect();
+                }
+            }
+        }
+        self.connected
+    }
+
+    #[cfg(unix)]
+    fn try_write(&self, bytes: &[u8]) -> io::Result<usize> {
+        match &self.sender {
+            Some(sender) => sender.try_write(bytes),
+            None => Err(io::ErrorKind::NotConnected.into()),
+        }
+    }
+
+    #[cfg(windows)]
+    fn try_write(&self, bytes: &[u8]) -> io::Result<usize> {
+        self.server.try_write(bytes)
+    }
+
+    #[cfg(unix)]
+    fn disconnect(&mut self) {
+        self.sender = None;
+        self.pending.clear();
+    }
+
+    /// Frees the pipe instance for the next reader.
+    #[cfg(windows)]
+    fn disconnect(&mut self) {
+        let _ = self.server.disconnect();
+        self.connected = false;
+        self.pending.clear();
+    }
+}
+
+/// Turns 16 kHz mono chunks into the bytes of a [`CaptureAudioConfig`]:
+/// resampled when the rate differs, copied into every channel and encoded
+/// as little-endian samples.
+struct PipeEncoder {
+    config: CaptureAudioConfig,
+    resampler: Option<FftFixedIn<f32>>,
+    /// Samples waiting for a full resampler pass.
+    input: Vec<f32>,
+    output: Vec<Vec<f32>>,
+}
+
+impl PipeEncoder {
+    fn new(config: CaptureAudioConfig) -> Result<Self, String> {
+        let resampler = (config.sample_rate != CAPTURE_SAMPLE_RATE)
+            .then(|| {
+                FftFixedIn::<f32>::new(
+                    CAPTURE_SAMPLE_RATE as usize,
+                    config.sample_rate as usize,
+                    RESAMPLER_CHUNK_FRAMES,
+                    2,
+                    1,
+                )
+                .map_err(|error| format!("Failed to create capture pipe resampler: {error}"))
+            })
+            .transpose()?;
+        let output = resampler
+            .as_ref()
+            .map(|resampler| resampler.output_buffer_allocate(true))
+            .unwrap_or_default();
+        Ok(Self {
+            config,
+            resampler,
+            input: Vec::new(),
+            output,
+        })
+    }
+
+    fn reset(&mut self) {
+        self.input.clear();
+        if let Some(resampler) = self.resampler.as_mut() {
+            resampler.reset();
+        }
+    }
+
+    fn encode(&mut self, samples: &[f32]) -> Vec<u8> {
+        let Some(resampler) = self.resampler.as_mut() else {
+            return encode_frames(samples, self.config);
+        };
+        self.input.extend_from_slice(samples);
+        let mut resampled = Vec::new();
+        let mut consumed = 0;
+        while self.input.len() - consumed >= resampler.input_frames_next() {
+            let needed = resampler.input_frames_next();
+            let chunk = [&self.input[consumed..consumed + needed]];
+            match resampler.process_into_buffer(&chunk, &mut self.output, None) {
+                Ok((read, written)) => {
+                    consumed += read;
+                    resampled.extend_from_slice(&self.output[0][..written]);
+                }
+                Err(error) => {
+                    eprintln!("[Audio] Capture pipe resampling failed: {error}");
+                    consumed = self.input.len();
+                    break;
+                }
+            }
+        }
+        self.input.drain(..consumed);
+        encode_frames(&resampled, self.config)
+    }
+}
+
+/// Encodes mono `samples` and copies each one into every channel.
+fn encode_frames(samples: &[f32], config: CaptureAudioConfig) -> Vec<u8> {
+    let mono = match config.sample_format {
+        CaptureSampleFormat::S16Le => sona_local_asr::audio::pcm_f32_to_s16le_bytes(samples),
+        CaptureSampleFormat::F32Le => samples
+            .iter()
+            .flat_map(|sample| sample.clamp(-1.0, 1.0).to_le_bytes())
+            .collect(),
+    };
+    if config.channels == 1 {
+        return mono;
+    }
+    mono.chunks_exact(usize::from(config.sample_format.bytes_per_sample()))
+        .flat_map(|sample| sample.repeat(usize::from(config.channels)))
+        .collect()
+}
+
+#[cfg(unix)]
+impl Drop for CapturePipe {
+    fn drop(&mut self) {
+        if self.created {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn fifo_drops_audio_until_a_reader_connects_and_is_removed_on_drop() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.pcm");
+        let mut pipe = CapturePipe::create(
+            path.to_str().unwrap(),
+            CapturePipeFormat::RawPcm,
+            CaptureAudioConfig::default(),
+        )
+        .unwrap();
+
+        // No reader yet: dropped without blocking.
+        pipe.write_samples(&[1.0; 4]);
+
+        let mut receiver = tokio::net::unix::pipe::OpenOptions::new()
+            .open_receiver(&path)
+            .unwrap();
+        let mut bytes = [0; 4];
+        // Chunks are dropped until the reactor has seen the pipe writable.
+        for _ in 0..100 {
+            pipe.write_samples(&[0.5, -2.0]);
+            let read = receiver.read_exact(&mut bytes);
+            if tokio::time::timeout(Duration::from_millis(10), read)
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        assert_eq!(bytes, [0xff, 0x3f, 0x01, 0x80]);
+
+        drop(pipe);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn wav_pipes_send_the_header_before_the_first_samples() {
+        use tokio::io::AsyncReadExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.wav");
+        let mut pipe = CapturePipe::create(
+            path.to_str().unwrap(),
+            CapturePipeFormat::Wav,
+            CaptureAudioConfig::default(),
+        )
+        .unwrap();
+
+        let mut receiver = tokio::net::unix::pipe::OpenOptions::new()
+            .open_receiver(&path)
+            .unwrap();
+        let mut bytes = [0; 48];
+        for _ in 0..100 {
+            pipe.write_samples(&[0.5, -2.0]);
+            let read = receiver.read_exact(&mut bytes);
+            if tokio::time::timeout(Duration::from_millis(10), read)
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        assert_eq!(
+            bytes[..44],
+            sona_core::runtime::capture::streaming_wav_header(CaptureAudioConfig::default())
+        );
+        assert_eq!(bytes[44..], [0xff, 0x3f, 0x01, 0x80]);
+    }
+
+    #[test]
+    fn encoder_copies_samples_into_every_channel() {
+        let mut encoder = PipeEncoder::new(CaptureAudioConfig {
+            sample_rate: 16_000,
+            channels: 2,
+            sample_format: CaptureSampleFormat::F32Le,
+        })
+        .unwrap();
+
+        let bytes = encoder.encode(&[0.5, -2.0]);
+        let samples: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|sample| f32::from_le_bytes(sample.try_into().unwrap()))
+            .collect();
+        assert_eq!(samples, [0.5, 0.5, -1.0, -1.0]);
+    }
+
+    #[test]
+    fn encoder_resamples_to_the_configured_rate() {
+        let mut encoder = PipeEncoder::new(CaptureAudioConfig {
+            sample_rate: 48_000,
+            channels: 1,
+            sample_format: CaptureSampleFormat::S16Le,
+        })
+        .unwrap();
+
+        // Nothing comes out until a full resampler pass is buffered.
+        assert!(encoder.encode(&[0.0; 512]).is_empty());
+        let mut frames = 0;
+        for _ in 0..16 {
+            frames += encoder.encode(&[0.0; 1000]).len() / 2;
+        }
+        // 16.5k input frames minus the one pass still buffered, tripled.
+        assert!((45_000..=49_500).contains(&frames), "{frames}");
+    }
+
+    #[test]
+    fn existing_files_that_are_not_fifos_are_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.pcm");
+        std::fs::write(&path, b"").unwrap();
+
+        assert!(
+            CapturePipe::create(
+                path.to_str().unwrap(),
+                CapturePipeFormat::RawPcm,
+                CaptureAudioConfig::default(),
+            )
+            .is_err()
+        );
+        assert!(path.exists());
+    }
+}
@@ -22,13 +22,22 @@ fn validate_diagnostics_snapshot(snapshot: &DiagnosticsCoreSnapshot) -> Result<(
 pub async fn get_diagnostics_core_snapshot(
     provider: &dyn PathProvider,
     state: State<'_, AsrState>,
-    mut input: DiagnosticsCoreInput,
+    input: DiagnosticsCoreInput,
 ) -> Result<DiagnosticsCoreSnapshot, String> {
-    validate_diagnostics_input(&input)?;
     let models_dir = provider
         .resolve_path(PathKind::AppLocalData)
         .map_err(|error| error.to_string())?
         .join("models");
+    get_diagnostics_core_snapshot_in_models_dir(provider, models_dir, state, input).await
+}
+
+async fn get_diagnostics_core_snapshot_in_models_dir(
+    provider: &dyn PathProvider,
+    models_dir: std::path::PathBuf,
+    state: State<'_, AsrState>,
+    mut input: DiagnosticsCoreInput,
+) -> Result<DiagnosticsCoreSnapshot, String> {
+    validate_diagnostics_input(&input)?;
     let log_dir = provider
         .resolve_path(PathKind::AppLogData)
         .map_err(|error| error.to_string())?;
@@ -53,7 +62,112 @@ pub async fn get_diagnostics_core_snapshot_for_app<R: tauri::Runtime>(
     input: DiagnosticsCoreInput,
 ) -> Result<DiagnosticsCoreSnapshot, String> {
     let provider = crate::platform::paths::TauriPathProvider::from_app(app);
-    get_diagnostics_core_snapshot(&provider, state, input).await
+    let models_dir = crate::platform::paths::models_dir_for_app(app)?;
+    get_diagnostics_core_snapshot_in_models_dir(&provider, models_dir, state, input).await
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsAppInfo {
+    name: String,
+    version: String,
+    os: &'static str,
+    arch: &'static str,
+}
+
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticsFfmpegInfo {
+    #[serde(flatten)]
+    environment: Option<RuntimeEnvironmentStatus>,
+    encoders: Vec<String>,
+    error: Option<String>,
+}
+
+/// Writes a zip bundle for bug reports to `output_path`: the app log
+/// files with credentials redacted, plus app, GPU, system resource and
+/// FFmpeg information. Returns the bundle path.
+pub async fn export_diagnostics<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    output_path: String,
+) -> Result<String, String> {
+    let log_dir = crate::platform::paths::TauriPathProvider::from_app(app)
+        .resolve_path(PathKind::AppLogData)
+        .map_err(|error| error.to_string())?;
+    let package = app.package_info();
+    let app_info = DiagnosticsAppInfo {
+        name: package.name.clone(),
+        version: package.version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+    };
+    let gpus = crate::platform::hardware::list_gpus().await;
+    let resources = crate::platform::hardware::get_system_resources().await;
+
+    crate::platform::blocking::spawn_blocking_map(move || -> Result<String, String> {
+        let (encoders, error) = match sona_local_asr::audio::probe_ffmpeg_encoders() {
+            Ok(encoders) => (encoders, None),
+            Err(error) => (Vec::new(), Some(error.to_string())),
+        };
+        let ffmpeg = DiagnosticsFfmpegInfo {
+            environment:
+                crate::platform::runtime_status::resolve_runtime_environment_status_for_log_dir(
+                    log_dir.clone(),
+                )
+                .ok(),
+            encoders,
+            error,
+        };
+        let resources = match resources {
+            Ok(resources) => serde_json::json!(resources),
+            Err(error) => serde_json::json!({ "error": error }),
+        };
+
+        let staging = tempfile::tempdir().map_err(|error| error.to_string())?;
+        write_diagnostics_json(staging.path(), "app-info.json", &app_info)?;
+        write_diagnostics_json(staging.path(), "gpus.json", &gpus)?;
+        write_diagnostics_json(staging.path(), "system-resources.json", &resources)?;
+        write_diagnostics_json(staging.path(), "ffmpeg.json", &ffmpeg)?;
+        copy_redacted_logs(&log_dir, &staging.path().join("logs"))?;
+
+        sona_archive::create_zip(&staging.path().to_string_lossy(), &output_path)
+            .map_err(|error| error.to_string())?;
+        log::info!("[diagnostics] Exported diagnostics bundle to {output_path}");
+        Ok(output_path)
+    })
+    .await
+}
+
+fn write_diagnostics_json(
+    dir: &std::path::Path,
+    name: &str,
+    value: &impl serde::Serialize,
+) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(value).map_err(|error| error.to_string())?;
+    std::fs::write(dir.join(name), json).map_err(|error| error.to_string())
+}
+
+/// Copies every file in `log_dir` into `target` with credentials masked.
+/// A missing log directory yields an empty `logs` folder.
+fn copy_redacted_logs(log_dir: &std::path::Path, target: &std::path::Path) -> Result<(), String> {
+    std::fs::create_dir_all(target).map_err(|error| error.to_string())?;
+    let entries = match std::fs::read_dir(log_dir) {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(error) => return Err(error.to_string()),
+    };
+    for entry in entries {
+        let entry = entry.map_err(|error| error.to_string())?;
+        if !entry.file_type().is_ok_and(|file_type| file_type.is_file()) {
+            continue;
+        }
+        let bytes = std::fs::read(entry.path()).map_err(|error| error.to_string())?;
+        let redacted =
+            sona_core::runtime::diagnostics::redact_log_secrets(&String::from_utf8_lossy(&bytes));
+        std::fs::write(target.join(entry.file_name()), redacted)
+            .map_err(|error| error.to_string())?;
+    }
+    Ok(())
 }
 
 #[cfg(test)]
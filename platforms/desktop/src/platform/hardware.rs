@@ -24,6 +24,107 @@ pub async fn resolve_gpu_acceleration(gpu_acceleration: Option<&str>) -> Option<
     resolved
 }
 
+/// App setting holding the CUDA device index chosen by the user.
+const PREFERRED_GPU_SETTING_KEY: &str = "preferredGpuIndex";
+const CUDA_VISIBLE_DEVICES_ENV: &str = "CUDA_VISIBLE_DEVICES";
+
+pub async fn list_gpus() -> Vec<sona_core::runtime::gpu::GpuDevice> {
+    sona_local_asr::gpu::list_gpus().await
+}
+
+/// Validates `index` against the detected GPUs, persists it and returns the
+/// device name. CUDA reads the selection once per process, so it takes effect
+/// on the next launch through [`apply_preferred_gpu`].
+pub async fn set_preferred_gpu<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    index: u32,
+) -> Result<String, String> {
+    let gpus = list_gpus().await;
+    let gpu = sona_core::runtime::gpu::resolve_preferred_gpu(index, &gpus)
+        .map_err(|error| error.to_string())?;
+    crate::platform::app_config::set_setting(
+        app,
+        PREFERRED_GPU_SETTING_KEY.to_string(),
+        serde_json::json!(index),
+    )?;
+    log::info!("[hardware] Preferred GPU set to {index} ({})", gpu.name);
+    Ok(gpu.name.clone())
+}
+
+/// Exposes the saved GPU choice to the CUDA runtime via
+/// `CUDA_VISIBLE_DEVICES`. A value already set in the environment wins.
+pub fn apply_preferred_gpu<R: tauri::Runtime>(app: &tauri::AppHandle<R>) {
+    if std::env::var_os(CUDA_VISIBLE_DEVICES_ENV).is_some() {
+        return;
+    }
+    let index = match crate::platform::app_config::get_setting(
+        app,
+        PREFERRED_GPU_SETTING_KEY.to_string(),
+    ) {
+        Ok(value) => value.and_then(|value| value.as_u64()),
+        Err(error) => {
+            log::warn!("[hardware] Failed to read preferred GPU: {error}");
+            return;
+        }
+    };
+    let Some(index) = index else {
+        return;
+    };
+
+    // SAFETY: called once from app setup, before any recognizer is created,
+    // so no other code is reading the environment concurrently.
+    unsafe { std::env::set_var(CUDA_VISIBLE_DEVICES_ENV, index.to_string()) };
+    log::info!("[hardware] Using preferred GPU {index}");
+}
+
+/// Memory and CPU figures used to judge whether a model fits this machine.
+/// Memory values are bytes.
+#[derive(Clone, Copy, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemResources {
+    pub total_memory: u64,
+    pub available_memory: u64,
+    pub logical_cores: usize,
+    pub physical_cores: Option<usize>,
+    /// Whole-system CPU load since the previous call, 0 on the first one.
+    pub cpu_usage_percent: f32,
+    pub process_memory: Option<u64>,
+}
+
+/// Kept between calls so CPU usage has a previous sample to diff against and
+/// polling only refreshes memory, CPU load and this process.
+static RESOURCE_SYSTEM: std::sync::LazyLock<std::sync::Mutex<sysinfo::System>> =
+    std::sync::LazyLock::new(|| std::sync::Mutex::new(sysinfo::System::new()));
+
+pub async fn get_system_resources() -> Result<SystemResources, String> {
+    crate::platform::blocking::spawn_blocking_map(read_system_resources).await
+}
+
+fn read_system_resources() -> Result<SystemResources, String> {
+    use sysinfo::{ProcessRefreshKind, ProcessesToUpdate};
+
+    let mut system = RESOURCE_SYSTEM.lock().map_err(|e| e.to_string())?;
+    system.refresh_memory();
+    system.refresh_cpu_usage();
+    let process_memory = sysinfo::get_current_pid().ok().and_then(|pid| {
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&[pid]),
+            false,
+            ProcessRefreshKind::nothing().with_memory(),
+        );
+        system.process(pid).map(|process| process.memory())
+    });
+
+    Ok(SystemResources {
+        total_memory: system.total_memory(),
+        available_memory: system.available_memory(),
+        logical_cores: std::thread::available_parallelism().map_or(1, |cores| cores.get()),
+        physical_cores: sysinfo::System::physical_core_count(),
+        cpu_usage_percent: system.global_cpu_usage(),
+        process_memory,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -34,6 +135,13 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_get_system_resources() {
+        let resources = get_system_resources().await.unwrap();
+        assert!(resources.total_memory >= resources.available_memory);
+        assert!(resources.logical_cores >= 1);
+    }
+
     #[tokio::test]
     async fn test_resolve_gpu_acceleration() {
         let result = resolve_gpu_acceleration(Some("cuda")).await;
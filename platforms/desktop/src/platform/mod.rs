@@ -6,6 +6,7 @@ pub mod audio_storage;
 pub mod automation_repository;
 pub mod automation_runtime;
 pub(crate) mod blocking;
+pub(crate) mod capture_pipe;
 pub mod dashboard;
 pub mod database;
 pub mod diagnostics;
@@ -16,7 +17,9 @@ pub mod history_repository;
 pub mod llm_usage;
 pub mod media_detector;
 pub mod model_downloads;
+pub mod overall_progress;
 pub mod paths;
+pub mod power;
 pub mod preset_models;
 pub mod recovery_repository;
 pub mod runtime_status;
@@ -32,3 +35,4 @@ pub mod system_audio;
 pub mod tag_repository;
 pub mod task_ledger_repository;
 pub mod time;
+pub(crate) mod write_scope;
@@ -0,0 +1,439 @@
+//! One progress figure for everything the model downloads UI waits on, so a
+//! download followed by the extraction of the same archive shows as a single
+//! bar instead of two the frontend has to stitch together, and the list of
+//! running and recently finished operations behind it.
+
+use sona_archive::ExtractProgress;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+/// Share of a download + extract flow spent downloading. Extracting bz2 is
+/// CPU bound and takes a noticeable part of the total on fast connections.
+const DOWNLOAD_STAGE_WEIGHT: f64 = 0.7;
+/// Finished operations kept for [`ProgressTracker::operations`].
+const MAX_FINISHED_OPERATIONS: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProgressStage {
+    Idle,
+    Downloading,
+    Extracting,
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverallProgress {
+    pub stage: ProgressStage,
+    /// From 0 to 1 across all running operations.
+    pub fraction: f64,
+    /// Id of the download, or the archive entry being extracted.
+    pub detail: Option<String>,
+}
+
+/// Byte totals across the running downloads, for a tray tooltip or a status
+/// line that does not want to add up per-id events.
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadSummary {
+    pub active: usize,
+    pub downloaded: u64,
+    /// Sum of the sizes known so far; downloads whose size is not known yet
+    /// add nothing.
+    pub total: u64,
+    pub bytes_per_sec: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationKind {
+    Download,
+    Extraction,
+    Capture,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OperationStatus {
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+/// One entry of the operations list: a download, an extraction or a capture.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Operation {
+    /// Download id, archive path, or capture source.
+    pub id: String,
+    pub kind: OperationKind,
+    pub status: OperationStatus,
+    /// From 0 to 1. Missing for captures, which have no end, and for
+    /// downloads whose size is not known yet.
+    pub progress: Option<f64>,
+    /// Unix time in milliseconds.
+    pub started_at: u64,
+    /// Output path of a download, entry being extracted, or capture device.
+    pub detail: Option<String>,
+}
+
+struct TrackedDownload {
+    output_path: PathBuf,
+    started_at: u64,
+    /// Share of its flow this download covers.
+    weight: f64,
+    downloaded: u64,
+    total: u64,
+    bytes_per_sec: f64,
+}
+
+struct TrackedExtraction {
+    /// Whether the archive was downloaded by a tracked download, so the
+    /// download stage is already behind it.
+    after_download: bool,
+    started_at: u64,
+    entry: String,
+    read: u64,
+    total: u64,
+}
+
+/// Model archives are downloaded as `<id>.tar.bz2` and extracted right after.
+pub(crate) fn is_model_archive(path: &Path) -> bool {
+    path.to_string_lossy().ends_with(".tar.bz2")
+}
+
+fn download_operation(id: &str, download: &TrackedDownload) -> Operation {
+    Operation {
+        id: id.to_string(),
+        kind: OperationKind::Download,
+        status: OperationStatus::Running,
+        progress: (download.total > 0).then(|| stage_fraction(download.downloaded, download.total)),
+        started_at: download.started_at,
+        detail: Some(download.output_path.to_string_lossy().into_owned()),
+    }
+}
+
+fn extraction_operation(archive_path: &Path, extraction: &TrackedExtraction) -> Operation {
+    Operation {
+        id: archive_path.to_string_lossy().into_owned(),
+        kind: OperationKind::Extraction,
+        status: OperationStatus::Running,
+        progress: (extraction.total > 0).then(|| stage_fraction(extraction.read, extraction.total)),
+        started_at: extraction.started_at,
+        detail: (!extraction.entry.is_empty()).then(|| extraction.entry.clone()),
+    }
+}
+
+fn stage_fraction(done: u64, total: u64) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (done as f64 / total as f64).min(1.0)
+}
+
+#[derive(Default)]
+pub(crate) struct ProgressTracker {
+    downloads: HashMap<String, TrackedDownload>,
+    extractions: HashMap<PathBuf, TrackedExtraction>,
+    /// Archives whose download finished and whose extraction has not started.
+    downloaded_archives: HashSet<PathBuf>,
+    /// Newest last.
+    finished: VecDeque<Operation>,
+}
+
+impl ProgressTracker {
+    /// `extracted_after` marks downloads of an archive that is extracted once
+    /// the download finishes, which then only covers the download stage.
+    pub(crate) fn start_download(&mut self, id: &str, output_path: &Path, extracted_after: bool) {
+        self.downloaded_archives.remove(output_path);
+        self.downloads.insert(
+            id.to_string(),
+            TrackedDownload {
+                output_path: output_path.to_path_buf(),
+                started_at: crate::platform::time::unix_timestamp_millis(),
+                weight: if extracted_after {
+                    DOWNLOAD_STAGE_WEIGHT
+                } else {
+                    1.0
+                },
+                downloaded: 0,
+                total: 0,
+                bytes_per_sec: 0.0,
+            },
+        );
+    }
+
+    pub(crate) fn update_download(&mut self, id: &str, downloaded: u64, total: u64) {
+        if let Some(download) = self.downloads.get_mut(id) {
+            download.downloaded = downloaded;
+            download.total = total;
+        }
+    }
+
+    pub(crate) fn update_download_speed(&mut self, id: &str, bytes_per_sec: f64) {
+        if let Some(download) = self.downloads.get_mut(id) {
+            download.bytes_per_sec = bytes_per_sec;
+        }
+    }
+
+    pub(crate) fn finish_download(&mut self, id: &str, status: OperationStatus) {
+        let Some(download) = self.downloads.remove(id) else {
+            return;
+        };
+        let mut operation = download_operation(id, &download);
+        operation.status = status;
+        if status == OperationStatus::Completed {
+            operation.progress = Some(1.0);
+        }
+        self.push_finished(operation);
+        if status == OperationStatus::Completed && download.weight < 1.0 {
+            self.downloaded_archives.insert(download.output_path);
+        }
+    }
+
+    pub(crate) fn update_extraction(&mut self, archive_path: &Path, progress: &ExtractProgress) {
+        let extraction = self
+            .extractions
+            .entry(archive_path.to_path_buf())
+            .or_insert_with(|| TrackedExtraction {
+                after_download: self.downloaded_archives.remove(archive_path),
+                started_at: crate::platform::time::unix_timestamp_millis(),
+                entry: String::new(),
+                read: 0,
+                total: 0,
+            });
+        extraction.entry = progress.path.to_string();
+        extraction.read = progress.compressed_read;
+        extraction.total = progress.compressed_total;
+    }
+
+    pub(crate) fn finish_extraction(&mut self, archive_path: &Path, status: OperationStatus) {
+        if let Some(extraction) = self.extractions.remove(archive_path) {
+            let mut operation = extraction_operation(archive_path, &extraction);
+            operation.status = status;
+            if status == OperationStatus::Completed {
+                operation.progress = Some(1.0);
+            }
+            self.push_finished(operation);
+        }
+        self.downloaded_archives.remove(archive_path);
+    }
+
+    fn push_finished(&mut self, operation: Operation) {
+        if self.finished.len() == MAX_FINISHED_OPERATIONS {
+            self.finished.pop_front();
+        }
+        self.finished.push_back(operation);
+    }
+
+    /// Running downloads and extractions, oldest first, followed by the
+    /// recently finished ones, newest first.
+    pub(crate) fn operations(&self) -> Vec<Operation> {
+        let mut running = self
+            .downloads
+            .iter()
+            .map(|(id, download)| download_operation(id, download))
+            .chain(
+                self.extractions
+                    .iter()
+                    .map(|(path, extraction)| extraction_operation(path, extraction)),
+            )
+            .collect::<Vec<_>>();
+        running
+            .sort_by(|left, right| (left.started_at, &left.id).cmp(&(right.started_at, &right.id)));
+        running.extend(self.finished.iter().rev().cloned());
+        running
+    }
+
+    pub(crate) fn download_summary(&self) -> DownloadSummary {
+        self.downloads
+            .values()
+            .fold(DownloadSummary::default(), |summary, download| {
+                DownloadSummary {
+                    active: summary.active + 1,
+                    downloaded: summary.downloaded + download.downloaded,
+                    total: summary.total + download.total,
+                    bytes_per_sec: summary.bytes_per_sec + download.bytes_per_sec,
+                }
+            })
+    }
+
+    /// Averages the running operations, each weighted equally. Downloading
+    /// wins the stage while anything is still downloading.
+    pub(crate) fn overall(&self) -> OverallProgress {
+        let download_fractions = self
+            .downloads
+            .values()
+            .map(|download| download.weight * stage_fraction(download.downloaded, download.total));
+        let extraction_fractions = self.extractions.values().map(|extraction| {
+            let fraction = stage_fraction(extraction.read, extraction.total);
+            if extraction.after_download {
+                DOWNLOAD_STAGE_WEIGHT + (1.0 - DOWNLOAD_STAGE_WEIGHT) * fraction
+            } else {
+                fraction
+            }
+        });
+        let count = self.downloads.len() + self.extractions.len();
+        if count == 0 {
+            return OverallProgress {
+                stage: ProgressStage::Idle,
+                fraction: 0.0,
+                detail: None,
+            };
+        }
+        let fraction = download_fractions.chain(extraction_fractions).sum::<f64>() / count as f64;
+
+        let (stage, detail) = match self.downloads.keys().min() {
+            Some(id) => (ProgressStage::Downloading, id.clone()),
+            None => {
+                let path = self
+                    .extractions
+                    .keys()
+                    .min()
+                    .expect("an extraction is running");
+                (
+                    ProgressStage::Extracting,
+                    self.extractions[path].entry.clone(),
+                )
+            }
+        };
+        OverallProgress {
+            stage,
+            fraction,
+            detail: Some(detail),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extract_progress(path: &str, read: u64, total: u64) -> ExtractProgress<'_> {
+        ExtractProgress {
+            path,
+            compressed_read: read,
+            compressed_total: total,
+            bytes_done: 0,
+            bytes_total: None,
+        }
+    }
+
+    #[test]
+    fn download_then_extract_reports_one_rising_fraction() {
+        let archive = Path::new("/models/model.tar.bz2");
+        let mut tracker = ProgressTracker::default();
+        assert_eq!(tracker.overall().stage, ProgressStage::Idle);
+
+        tracker.start_download("model", archive, true);
+        tracker.update_download("model", 50, 100);
+        let downloading = tracker.overall();
+        assert_eq!(downloading.stage, ProgressStage::Downloading);
+        assert_eq!(downloading.detail.as_deref(), Some("model"));
+        assert!((downloading.fraction - DOWNLOAD_STAGE_WEIGHT / 2.0).abs() < 1e-9);
+
+        tracker.finish_download("model", OperationStatus::Completed);
+        tracker.update_extraction(archive, &extract_progress("model/tokens.txt", 0, 40));
+        let extracting = tracker.overall();
+        assert_eq!(extracting.stage, ProgressStage::Extracting);
+        assert_eq!(extracting.detail.as_deref(), Some("model/tokens.txt"));
+        assert!((extracting.fraction - DOWNLOAD_STAGE_WEIGHT).abs() < 1e-9);
+
+        tracker.update_extraction(archive, &extract_progress("model/model.onnx", 40, 40));
+        assert!((tracker.overall().fraction - 1.0).abs() < 1e-9);
+
+        tracker.finish_extraction(archive, OperationStatus::Completed);
+        assert_eq!(tracker.overall().stage, ProgressStage::Idle);
+    }
+
+    #[test]
+    fn operations_list_running_work_then_finished_work_newest_first() {
+        let archive = Path::new("/models/model.tar.bz2");
+        let mut tracker = ProgressTracker::default();
+        tracker.start_download("model", archive, true);
+        tracker.start_download("tokens", Path::new("/models/tokens.txt"), false);
+        tracker.update_download("tokens", 5, 10);
+
+        let running = tracker.operations();
+        assert_eq!(running.len(), 2);
+        assert!(
+            running
+                .iter()
+                .all(|operation| operation.status == OperationStatus::Running)
+        );
+        let tokens = running.iter().find(|operation| operation.id == "tokens");
+        assert_eq!(tokens.and_then(|operation| operation.progress), Some(0.5));
+
+        tracker.finish_download("tokens", OperationStatus::Cancelled);
+        tracker.finish_download("model", OperationStatus::Completed);
+        tracker.update_extraction(archive, &extract_progress("model/model.onnx", 1, 4));
+
+        let operations = tracker.operations();
+        let summary = operations
+            .iter()
+            .map(|operation| (operation.kind, operation.status, operation.progress))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    OperationKind::Extraction,
+                    OperationStatus::Running,
+                    Some(0.25)
+                ),
+                (
+                    OperationKind::Download,
+                    OperationStatus::Completed,
+                    Some(1.0)
+                ),
+                (
+                    OperationKind::Download,
+                    OperationStatus::Cancelled,
+                    Some(0.5)
+                ),
+            ]
+        );
+        assert_eq!(operations[0].detail.as_deref(), Some("model/model.onnx"));
+    }
+
+    #[test]
+    fn download_summary_adds_up_running_downloads() {
+        let mut tracker = ProgressTracker::default();
+        assert_eq!(tracker.download_summary(), DownloadSummary::default());
+
+        tracker.start_download("model", Path::new("/models/model.tar.bz2"), true);
+        tracker.start_download("tokens", Path::new("/models/tokens.txt"), false);
+        tracker.start_download("vad", Path::new("/models/vad.onnx"), false);
+        tracker.update_download("model", 40, 100);
+        tracker.update_download_speed("model", 1500.0);
+        tracker.update_download("tokens", 5, 10);
+        tracker.update_download_speed("tokens", 500.0);
+        tracker.finish_download("vad", OperationStatus::Cancelled);
+
+        assert_eq!(
+            tracker.download_summary(),
+            DownloadSummary {
+                active: 2,
+                downloaded: 45,
+                total: 110,
+                bytes_per_sec: 2000.0,
+            }
+        );
+    }
+
+    #[test]
+    fn standalone_operations_cover_the_whole_bar() {
+        let mut tracker = ProgressTracker::default();
+        tracker.start_download("file", Path::new("/models/model.onnx"), false);
+        tracker.update_download("file", 100, 100);
+        assert!((tracker.overall().fraction - 1.0).abs() < 1e-9);
+        tracker.finish_download("file", OperationStatus::Completed);
+
+        // Not downloaded by a tracked download, so extraction is all there is.
+        let archive = Path::new("/imports/model.tar.bz2");
+        tracker.update_extraction(archive, &extract_progress("model.onnx", 10, 20));
+        assert!((tracker.overall().fraction - 0.5).abs() < 1e-9);
+    }
+}
@@ -57,6 +57,28 @@ impl<R: Runtime> PathProvider for TauriPathProvider<R> {
     }
 }
 
+/// App setting holding the models directory chosen by `relocate_models_dir`.
+pub const MODELS_DIR_SETTING_KEY: &str = "modelsDir";
+
+/// Returns the relocated models directory when one was saved, otherwise
+/// `models` under the app-local data dir.
+pub fn models_dir_for_app<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, String> {
+    match crate::platform::app_config::get_setting(app, MODELS_DIR_SETTING_KEY.to_string()) {
+        Ok(Some(value)) => {
+            if let Some(dir) = value.as_str().map(str::trim).filter(|dir| !dir.is_empty()) {
+                return Ok(PathBuf::from(dir));
+            }
+        }
+        Ok(None) => {}
+        Err(error) => log::warn!("[paths] Failed to read models directory setting: {error}"),
+    }
+
+    TauriPathProvider::from_app(app)
+        .resolve_path(PathKind::AppLocalData)
+        .map(|dir| dir.join("models"))
+        .map_err(|error| error.to_string())
+}
+
 #[cfg(test)]
 pub struct MockPathProvider {
     entries: HashMap<PathKind, Result<PathBuf, PathProviderError>>,
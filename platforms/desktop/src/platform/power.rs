@@ -0,0 +1,112 @@
+//! Stops downloads and captures before the OS suspends and restarts the
+//! downloads afterwards. Tauri reports no power events, so the frontend calls
+//! these from its own suspend and resume listeners.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tauri::{Emitter, Manager, Runtime};
+
+use crate::integrations::audio::{AudioState, StoppedCapture};
+use crate::platform::model_downloads::DownloadState;
+
+const SYSTEM_SUSPEND_PREPARED_EVENT: &str = "system-suspend-prepared";
+const SYSTEM_RESUMED_EVENT: &str = "system-resumed";
+/// How long suspend waits for paused downloads to flush their partial file
+/// and sidecar before reporting back.
+const SUSPEND_DRAIN_TIMEOUT: Duration = Duration::from_secs(5);
+const SUSPEND_DRAIN_POLL: Duration = Duration::from_millis(50);
+
+/// Captures stopped by the last [`prepare_for_suspend`], kept until
+/// [`resume_from_suspend`] hands them back to the frontend.
+#[derive(Default)]
+pub struct SuspendState {
+    stopped_captures: Mutex<Vec<StoppedCapture>>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SuspendReport {
+    /// Downloads that keep their partial file and restart on resume.
+    paused_downloads: Vec<String>,
+    /// Downloads that cannot be continued, such as streamed extractions.
+    cancelled_downloads: Vec<String>,
+    stopped_captures: Vec<StoppedCapture>,
+}
+
+#[derive(Clone, Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResumeReport {
+    resumed_downloads: Vec<String>,
+    /// Captures stopped for the suspend. Their device and recording settings
+    /// live in the frontend, which starts them again.
+    captures_to_restart: Vec<StoppedCapture>,
+}
+
+pub async fn prepare_for_suspend<R: Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<SuspendReport, String> {
+    let downloads = app.state::<DownloadState>();
+    let (paused_downloads, cancelled_downloads) = downloads.pause_all_downloads().await;
+    let started = std::time::Instant::now();
+    while downloads.has_active_downloads().await && started.elapsed() < SUSPEND_DRAIN_TIMEOUT {
+        tokio::time::sleep(SUSPEND_DRAIN_POLL).await;
+    }
+
+    let stopped_captures =
+        crate::integrations::audio::stop_and_report_all_audio_captures(&app.state::<AudioState>())
+            .await?;
+    if let Ok(mut captures) = app.state::<SuspendState>().stopped_captures.lock() {
+        captures.extend(stopped_captures.iter().cloned());
+    }
+    crate::app::tray::schedule_tray_menu_refresh(&app);
+
+    log::info!(
+        "[power] Prepared for suspend: paused {} download(s), cancelled {}, stopped {} capture(s)",
+        paused_downloads.len(),
+        cancelled_downloads.len(),
+        stopped_captures.len()
+    );
+    let report = SuspendReport {
+        paused_downloads,
+        cancelled_downloads,
+        stopped_captures,
+    };
+    let _ = app.emit(SYSTEM_SUSPEND_PREPARED_EVENT, &report);
+    Ok(report)
+}
+
+pub async fn resume_from_suspend<R: Runtime>(
+    app: tauri::AppHandle<R>,
+) -> Result<ResumeReport, String> {
+    let downloads = app.state::<DownloadState>();
+    // Downloads paused for focus mode stay paused until it ends.
+    let paused = if downloads.focus_mode() {
+        Vec::new()
+    } else {
+        downloads.take_paused_downloads().await
+    };
+    let mut resumed_downloads = Vec::with_capacity(paused.len());
+    for (id, restart) in paused {
+        resumed_downloads.push(id.clone());
+        crate::platform::model_downloads::restart_download(app.clone(), id, restart);
+    }
+
+    let captures_to_restart = app
+        .state::<SuspendState>()
+        .stopped_captures
+        .lock()
+        .map(|mut captures| std::mem::take(&mut *captures))
+        .unwrap_or_default();
+
+    log::info!(
+        "[power] Resumed from suspend: restarted {} download(s)",
+        resumed_downloads.len()
+    );
+    let report = ResumeReport {
+        resumed_downloads,
+        captures_to_restart,
+    };
+    let _ = app.emit(SYSTEM_RESUMED_EVENT, &report);
+    Ok(report)
+}
@@ -1,9 +1,11 @@
-use tauri::{AppHandle, Manager, Runtime};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 
 use crate::platform::blocking::{map_err_string, spawn_blocking_map, with_sqlite_context};
 use crate::platform::paths::{PathKind, PathProvider, TauriPathProvider};
 pub use sona_core::storage_usage::{StorageUsageSnapshot, WebviewBrowsingDataClearResult};
 
+const DIRECTORY_SIZE_PROGRESS_EVENT: &str = "directory-size-progress";
+
 pub async fn get_usage_snapshot<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<StorageUsageSnapshot, String> {
@@ -20,6 +22,32 @@ pub async fn get_usage_snapshot<R: Runtime>(
     Ok(snapshot)
 }
 
+/// Total bytes of regular files under `path`, walked on the blocking pool.
+/// Emits `(files, bytes, path)` progress while scanning very large trees.
+pub async fn get_directory_size<R: Runtime>(
+    app: AppHandle<R>,
+    path: String,
+) -> Result<u64, String> {
+    spawn_blocking_map(move || {
+        sona_runtime_fs::directory_size(std::path::Path::new(&path), |files, bytes| {
+            let _ = app.emit(DIRECTORY_SIZE_PROGRESS_EVENT, (files, bytes, &path));
+        })
+    })
+    .await
+}
+
+/// Times a synced write and read-back of a small probe file in `dir` so
+/// slow downloads can be told apart from a slow target disk.
+pub async fn benchmark_disk(dir: String) -> Result<sona_runtime_fs::DiskBenchmark, String> {
+    spawn_blocking_map(move || {
+        sona_runtime_fs::benchmark_disk(
+            std::path::Path::new(&dir),
+            sona_runtime_fs::DISK_BENCHMARK_BYTES,
+        )
+    })
+    .await
+}
+
 pub async fn clear_webview_browsing_data<R: Runtime>(
     app: &AppHandle<R>,
 ) -> Result<WebviewBrowsingDataClearResult, String> {
@@ -727,19 +727,139 @@ pub fn greet(name: &str) -> String {
     format!("Hello, {}! You've been greeted from Rust!", name)
 }
 
+/// Milliseconds on a monotonic clock anchored at the first call. Touches no
+/// shared state, so it stays a reliable liveness probe while the blocking pool
+/// is busy.
+pub fn ping() -> u64 {
+    static EPOCH: std::sync::OnceLock<std::time::Instant> = std::sync::OnceLock::new();
+    EPOCH
+        .get_or_init(std::time::Instant::now)
+        .elapsed()
+        .as_millis() as u64
+}
+
 pub fn force_exit<R: tauri::Runtime>(app: tauri::AppHandle<R>) {
     app.exit(0);
 }
 
+/// Work that quitting would interrupt, read from the backend state rather
+/// than the frontend's view of it.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuitStatus {
+    /// Nothing would be interrupted.
+    pub safe: bool,
+    pub active_downloads: Vec<String>,
+    pub active_captures: Vec<crate::integrations::audio::RunningCapturePayload>,
+}
+
+pub async fn can_quit_safely(app: tauri::AppHandle) -> Result<QuitStatus, String> {
+    use tauri::Manager;
+
+    let active_downloads = app
+        .state::<crate::platform::model_downloads::DownloadState>()
+        .active_download_ids()
+        .await;
+    let active_captures = crate::integrations::audio::running_captures(
+        &app.state::<crate::integrations::audio::AudioState>(),
+    )?;
+    Ok(QuitStatus {
+        safe: active_downloads.is_empty() && active_captures.is_empty(),
+        active_downloads,
+        active_captures,
+    })
+}
+
+/// Running captures, downloads and extractions, then recently finished
+/// downloads and extractions, for a tasks view that can be rebuilt at any
+/// time instead of from events it may have missed.
+pub fn get_operations(
+    app: &tauri::AppHandle,
+) -> Result<Vec<crate::platform::overall_progress::Operation>, String> {
+    use tauri::Manager;
+
+    let mut operations = crate::integrations::audio::capture_operations(
+        &app.state::<crate::integrations::audio::AudioState>(),
+    )?;
+    operations.extend(
+        app.state::<crate::platform::model_downloads::DownloadState>()
+            .operations(),
+    );
+    Ok(operations)
+}
+
+/// How long quitting or restarting waits for cancelled downloads to flush
+/// their partial files.
+const DOWNLOAD_STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Cancels downloads and stops captures so partial files and recordings are
+/// left in a consistent state before the process goes away.
+async fn stop_background_work(app: &tauri::AppHandle) -> Result<(), String> {
+    use crate::platform::model_downloads::DownloadState;
+    use tauri::Manager;
+
+    let downloads = app.state::<DownloadState>();
+    let active = downloads.active_download_ids().await;
+    crate::platform::model_downloads::cancel_all_downloads(app.state::<DownloadState>()).await?;
+    downloads
+        .wait_until_stopped(&active, DOWNLOAD_STOP_TIMEOUT)
+        .await;
+    crate::integrations::audio::stop_all_audio_captures(
+        app.state::<crate::integrations::audio::AudioState>(),
+    )
+    .await
+}
+
+/// Quits after the cleanup [`restart_app`] does, for a quit the user has
+/// confirmed after [`can_quit_safely`].
+pub async fn quit_app(app: tauri::AppHandle) -> Result<(), String> {
+    stop_background_work(&app).await?;
+    log::info!("[System] Quitting after stopping background work");
+    app.exit(0);
+    Ok(())
+}
+
+#[cfg(not(desktop))]
+pub(crate) const RESTART_REQUIRED_EVENT: &str = "restart-required";
+
+/// Relaunches the app after cancelling downloads and stopping captures so
+/// partial files and recordings are left in a consistent state.
+///
+/// Where the runtime cannot relaunch itself, the frontend receives a
+/// `restart-required` event so it can ask the user to reopen the app, and the
+/// process exits cleanly.
+pub async fn restart_app(app: tauri::AppHandle) -> Result<(), String> {
+    stop_background_work(&app).await?;
+
+    #[cfg(desktop)]
+    app.restart();
+
+    #[cfg(not(desktop))]
+    {
+        use tauri::Emitter;
+
+        let _ = app.emit(RESTART_REQUIRED_EVENT, ());
+        app.exit(0);
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
         InjectionStep, InjectionStepKind, ModifierWaitResult, ShortcutModifier,
         UnicodeKeyInputSpec, build_injection_steps, build_unicode_key_input_specs,
-        format_modifier_wait_result, injection_plan_mode,
+        format_modifier_wait_result, injection_plan_mode, ping,
         poll_shortcut_modifiers_release_with_probe, remaining_text_after_sent_prefix,
     };
 
+    #[test]
+    fn ping_is_monotonic() {
+        let first = ping();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        assert!(ping() >= first + 5);
+    }
+
     #[test]
     fn builds_injection_steps_for_ascii_digits_and_full_width_punctuation() {
         assert_eq!(
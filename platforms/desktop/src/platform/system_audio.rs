@@ -1,10 +1,82 @@
+#[cfg(target_os = "linux")]
+use std::collections::HashMap;
+
+/// Output (render) device that mute, volume and default-device commands
+/// target. `id` is the platform identifier to pass back: an MMDevice id on
+/// Windows, a sink name on Linux and the device name on macOS.
+#[derive(Clone, Debug, PartialEq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputDevice {
+    id: String,
+    name: String,
+    is_default: bool,
+}
+
 #[cfg(target_os = "windows")]
-fn set_mute_windows(mute: bool) -> Result<(), String> {
-    use windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume;
+unsafe fn mm_device_id(device: &windows::Win32::Media::Audio::IMMDevice) -> Result<String, String> {
+    use windows::Win32::System::Com::CoTaskMemFree;
+
+    unsafe {
+        let raw = device.GetId().map_err(|e| e.to_string())?;
+        let id = raw.to_string().map_err(|e| e.to_string());
+        CoTaskMemFree(Some(raw.0 as *const _));
+        id
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn list_output_devices_windows() -> Result<Vec<OutputDevice>, String> {
+    use windows::Win32::Devices::FunctionDiscovery::PKEY_Device_FriendlyName;
+    use windows::Win32::Media::Audio::{
+        DEVICE_STATE_ACTIVE, IMMDeviceEnumerator, MMDeviceEnumerator, eConsole, eRender,
+    };
+    use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitialize, STGM_READ};
+
+    unsafe {
+        let _ = CoInitialize(None);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|e| e.to_string())?;
+        let default_id = enumerator
+            .GetDefaultAudioEndpoint(eRender, eConsole)
+            .ok()
+            .and_then(|device| mm_device_id(&device).ok());
+
+        let collection = enumerator
+            .EnumAudioEndpoints(eRender, DEVICE_STATE_ACTIVE)
+            .map_err(|e| e.to_string())?;
+        let count = collection.GetCount().map_err(|e| e.to_string())?;
+
+        let mut devices = Vec::with_capacity(count as usize);
+        for index in 0..count {
+            let device = collection.Item(index).map_err(|e| e.to_string())?;
+            let id = mm_device_id(&device)?;
+            let name = device
+                .OpenPropertyStore(STGM_READ)
+                .and_then(|store| store.GetValue(&PKEY_Device_FriendlyName))
+                .map(|value| value.to_string())
+                .unwrap_or_else(|_| id.clone());
+            devices.push(OutputDevice {
+                is_default: default_id.as_deref() == Some(id.as_str()),
+                id,
+                name,
+            });
+        }
+        Ok(devices)
+    }
+}
+
+/// Endpoint volume of `device_id`, or of the default console output when
+/// `None`.
+#[cfg(target_os = "windows")]
+fn endpoint_volume_windows(
+    device_id: Option<&str>,
+) -> Result<windows::Win32::Media::Audio::Endpoints::IAudioEndpointVolume, String> {
     use windows::Win32::Media::Audio::{
         IMMDeviceEnumerator, MMDeviceEnumerator, eConsole, eRender,
     };
     use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitialize};
+    use windows::core::HSTRING;
 
     unsafe {
         // CoInitialize may already be called by Tauri on this thread.
@@ -13,25 +85,211 @@ fn set_mute_windows(mute: bool) -> Result<(), String> {
         let enumerator: IMMDeviceEnumerator =
             CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|e| e.to_string())?;
 
-        let device = enumerator
-            .GetDefaultAudioEndpoint(eRender, eConsole)
-            .map_err(|e| e.to_string())?;
+        let device = match device_id {
+            Some(id) => enumerator.GetDevice(&HSTRING::from(id)),
+            None => enumerator.GetDefaultAudioEndpoint(eRender, eConsole),
+        }
+        .map_err(|e| e.to_string())?;
 
-        let volume: IAudioEndpointVolume = device
-            .Activate(CLSCTX_ALL, None)
-            .map_err(|e| e.to_string())?;
+        device.Activate(CLSCTX_ALL, None).map_err(|e| e.to_string())
+    }
+}
 
+#[cfg(target_os = "windows")]
+fn set_mute_windows(mute: bool, device_id: Option<&str>) -> Result<String, String> {
+    let volume = endpoint_volume_windows(device_id)?;
+    unsafe {
         volume
             .SetMute(mute, std::ptr::null())
             .map_err(|e: windows::core::Error| e.to_string())?;
     }
+    Ok("endpoint-volume".to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn get_mute_windows(device_id: Option<&str>) -> Result<bool, String> {
+    let volume = endpoint_volume_windows(device_id)?;
+    unsafe { volume.GetMute() }
+        .map(|muted| muted.as_bool())
+        .map_err(|e| e.to_string())
+}
+
+/// Windows takes the same 0.0–1.0 scalar the volume mixer slider shows.
+#[cfg(target_os = "windows")]
+fn set_volume_windows(level: f32, device_id: Option<&str>) -> Result<String, String> {
+    let volume = endpoint_volume_windows(device_id)?;
+    unsafe {
+        volume
+            .SetMasterVolumeLevelScalar(level, std::ptr::null())
+            .map_err(|e: windows::core::Error| e.to_string())?;
+    }
+    Ok("endpoint-volume".to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn get_volume_windows(device_id: Option<&str>) -> Result<f32, String> {
+    let volume = endpoint_volume_windows(device_id)?;
+    unsafe { volume.GetMasterVolumeLevelScalar() }.map_err(|e| e.to_string())
+}
+
+/// Layout of `IPolicyConfig`, the undocumented interface the Sound control
+/// panel uses to change default endpoints. Only `SetDefaultEndpoint` is
+/// called, so the ten methods before it are left untyped.
+#[cfg(target_os = "windows")]
+#[repr(C)]
+struct PolicyConfigVtbl {
+    base: windows::core::IUnknown_Vtbl,
+    _preceding: [usize; 10],
+    set_default_endpoint: unsafe extern "system" fn(
+        this: *mut std::ffi::c_void,
+        device_id: windows::core::PCWSTR,
+        role: windows::Win32::Media::Audio::ERole,
+    ) -> windows::core::HRESULT,
+}
+
+/// Sets the console and multimedia defaults, as "Set Default" in the Sound
+/// control panel does; the communications default is left alone.
+#[cfg(target_os = "windows")]
+fn set_default_output_windows(device_id: &str) -> Result<(), String> {
+    use windows::Win32::Media::Audio::{eConsole, eMultimedia};
+    use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitialize};
+    use windows::core::{GUID, HSTRING, IUnknown, Interface, PCWSTR};
+
+    const CLSID_POLICY_CONFIG_CLIENT: GUID =
+        GUID::from_u128(0x870af99c_171d_4f9e_af0d_e63df40c2bc9);
+    const IID_POLICY_CONFIG: GUID = GUID::from_u128(0xf8679f50_850a_41cf_9c72_430f290290c8);
+
+    let device_id = HSTRING::from(device_id);
+    unsafe {
+        let _ = CoInitialize(None);
+
+        let client: IUnknown = CoCreateInstance(&CLSID_POLICY_CONFIG_CLIENT, None, CLSCTX_ALL)
+            .map_err(|e| e.to_string())?;
+        let mut raw = std::ptr::null_mut();
+        client
+            .query(&IID_POLICY_CONFIG, &mut raw)
+            .ok()
+            .map_err(|e| e.to_string())?;
+        // Owns the reference `query` added and releases it on drop.
+        let policy = IUnknown::from_raw(raw);
+        let vtable = &**(raw as *const *const PolicyConfigVtbl);
+        for role in [eConsole, eMultimedia] {
+            (vtable.set_default_endpoint)(policy.as_raw(), PCWSTR(device_id.as_ptr()), role)
+                .ok()
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(())
+}
+
+/// Windows keeps one default endpoint for calls and another for everything
+/// else, and headset users often want the communications one. The device
+/// lists offer each role as a pseudo-device, resolved when capture starts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum DefaultDeviceRole {
+    Communications,
+    Multimedia,
+}
+
+impl DefaultDeviceRole {
+    pub(crate) const ALL: [Self; 2] = [Self::Communications, Self::Multimedia];
+
+    /// Name the pseudo-device is listed and requested under.
+    pub(crate) fn device_name(self) -> &'static str {
+        match self {
+            Self::Communications => "Default communications device",
+            Self::Multimedia => "Default multimedia device",
+        }
+    }
+
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Self::Communications => "communications",
+            Self::Multimedia => "multimedia",
+        }
+    }
+
+    pub(crate) fn from_device_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|role| role.device_name() == name)
+    }
+}
+
+/// MMDevice id of the default capture (or render) endpoint for `role`.
+#[cfg(target_os = "windows")]
+pub(crate) fn default_endpoint_id(
+    role: DefaultDeviceRole,
+    capture: bool,
+) -> Result<String, String> {
+    use windows::Win32::Media::Audio::{
+        IMMDeviceEnumerator, MMDeviceEnumerator, eCapture, eCommunications, eMultimedia, eRender,
+    };
+    use windows::Win32::System::Com::{CLSCTX_ALL, CoCreateInstance, CoInitialize};
+
+    let flow = if capture { eCapture } else { eRender };
+    let role = match role {
+        DefaultDeviceRole::Communications => eCommunications,
+        DefaultDeviceRole::Multimedia => eMultimedia,
+    };
+    unsafe {
+        let _ = CoInitialize(None);
+
+        let enumerator: IMMDeviceEnumerator =
+            CoCreateInstance(&MMDeviceEnumerator, None, CLSCTX_ALL).map_err(|e| e.to_string())?;
+        let device = enumerator
+            .GetDefaultAudioEndpoint(flow, role)
+            .map_err(|e| e.to_string())?;
+        mm_device_id(&device)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn list_output_devices_macos() -> Result<Vec<OutputDevice>, String> {
+    use cpal::traits::HostTrait;
+
+    let host = cpal::default_host();
+    let default_name = host
+        .default_output_device()
+        .map(|device| device.to_string());
+    let devices = host.output_devices().map_err(|e| e.to_string())?;
+
+    Ok(devices
+        .map(|device| {
+            let name = device.to_string();
+            OutputDevice {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                id: name.clone(),
+                name,
+            }
+        })
+        .collect())
+}
+
+/// AppleScript only reaches the default output, so refuse to silently act on
+/// a different device than the one requested.
+#[cfg(target_os = "macos")]
+fn require_default_output_macos(device_id: Option<&str>, action: &str) -> Result<(), String> {
+    let Some(id) = device_id else {
+        return Ok(());
+    };
+    let is_default = list_output_devices_macos()?
+        .iter()
+        .any(|device| device.is_default && device.id == id);
+    if !is_default {
+        return Err(format!(
+            "{action} a non-default output device is not supported on macOS: {id}"
+        ));
+    }
     Ok(())
 }
 
 #[cfg(target_os = "macos")]
-fn set_mute_macos(mute: bool) -> Result<(), String> {
+fn set_mute_macos(mute: bool, device_id: Option<&str>) -> Result<String, String> {
     use std::process::Command;
 
+    require_default_output_macos(device_id, "Muting")?;
+
     let state = if mute { "true" } else { "false" };
     let output = Command::new("osascript")
         .arg("-e")
@@ -39,6 +297,103 @@ fn set_mute_macos(mute: bool) -> Result<(), String> {
         .output()
         .map_err(|e| e.to_string())?;
 
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok("osascript".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn get_mute_macos(device_id: Option<&str>) -> Result<bool, String> {
+    use std::process::Command;
+
+    require_default_output_macos(device_id, "Reading the mute state of")?;
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg("output muted of (get volume settings)")
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    parse_osascript_muted(&String::from_utf8_lossy(&output.stdout))
+        .ok_or_else(|| "Could not read the output mute state".to_string())
+}
+
+/// `output muted of (get volume settings)` prints `true` or `false`, or
+/// `missing value` when the output device has no mute control.
+#[cfg(any(target_os = "macos", all(test, target_os = "linux")))]
+fn parse_osascript_muted(output: &str) -> Option<bool> {
+    match output.trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+/// AppleScript's output volume is an integer percentage.
+#[cfg(target_os = "macos")]
+fn set_volume_macos(level: f32, device_id: Option<&str>) -> Result<String, String> {
+    use std::process::Command;
+
+    require_default_output_macos(device_id, "Changing the volume of")?;
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg(format!(
+            "set volume output volume {}",
+            volume_percent(level)
+        ))
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    Ok("osascript".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn get_volume_macos(device_id: Option<&str>) -> Result<f32, String> {
+    use std::process::Command;
+
+    require_default_output_macos(device_id, "Reading the volume of")?;
+
+    let output = Command::new("osascript")
+        .arg("-e")
+        .arg("output volume of (get volume settings)")
+        .output()
+        .map_err(|e| e.to_string())?;
+
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    }
+    // `missing value` when the output device has no volume control.
+    String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse::<u32>()
+        .map(level_from_percent)
+        .map_err(|_| "Could not read the output volume".to_string())
+}
+
+/// macOS has no command-line switch for the default output, so this relies
+/// on `SwitchAudioSource` (Homebrew `switchaudio-osx`) being installed.
+#[cfg(target_os = "macos")]
+fn set_default_output_macos(device_id: &str) -> Result<(), String> {
+    use std::process::Command;
+
+    let output = Command::new("SwitchAudioSource")
+        .args(["-t", "output", "-s", device_id])
+        .output()
+        .map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => "Switching the default output device on macOS needs \
+                 SwitchAudioSource (brew install switchaudio-osx)"
+                .to_string(),
+            _ => e.to_string(),
+        })?;
+
     if !output.status.success() {
         return Err(String::from_utf8_lossy(&output.stderr).to_string());
     }
@@ -46,48 +401,673 @@ fn set_mute_macos(mute: bool) -> Result<(), String> {
 }
 
 #[cfg(target_os = "linux")]
-fn set_mute_linux(mute: bool) -> Result<(), String> {
+struct LinuxMuteBackend {
+    name: &'static str,
+    program: &'static str,
+    mute_args: &'static [&'static str],
+    unmute_args: &'static [&'static str],
+    read_args: &'static [&'static str],
+    parse_muted: fn(&str) -> Option<bool>,
+}
+
+/// Tried in order. `amixer` "Master" is not guaranteed to be the default sink,
+/// so every backend reads the state back before it counts as applied.
+#[cfg(target_os = "linux")]
+const LINUX_MUTE_BACKENDS: &[LinuxMuteBackend] = &[
+    LinuxMuteBackend {
+        name: "wpctl",
+        program: "wpctl",
+        mute_args: &["set-mute", "@DEFAULT_AUDIO_SINK@", "1"],
+        unmute_args: &["set-mute", "@DEFAULT_AUDIO_SINK@", "0"],
+        read_args: &["get-volume", "@DEFAULT_AUDIO_SINK@"],
+        parse_muted: parse_wpctl_muted,
+    },
+    LinuxMuteBackend {
+        name: "pactl",
+        program: "pactl",
+        mute_args: &["set-sink-mute", "@DEFAULT_SINK@", "1"],
+        unmute_args: &["set-sink-mute", "@DEFAULT_SINK@", "0"],
+        read_args: &["get-sink-mute", "@DEFAULT_SINK@"],
+        parse_muted: parse_pactl_muted,
+    },
+    LinuxMuteBackend {
+        name: "amixer-pulse",
+        program: "amixer",
+        mute_args: &["-D", "pulse", "set", "Master", "mute"],
+        unmute_args: &["-D", "pulse", "set", "Master", "unmute"],
+        read_args: &["-D", "pulse", "get", "Master"],
+        parse_muted: parse_amixer_muted,
+    },
+    LinuxMuteBackend {
+        name: "amixer",
+        program: "amixer",
+        mute_args: &["set", "Master", "mute"],
+        unmute_args: &["set", "Master", "unmute"],
+        read_args: &["get", "Master"],
+        parse_muted: parse_amixer_muted,
+    },
+];
+
+#[cfg(target_os = "linux")]
+struct LinuxVolumeBackend {
+    name: &'static str,
+    program: &'static str,
+    /// The formatted level is appended to these.
+    set_args: &'static [&'static str],
+    format_level: fn(u32) -> String,
+    read_args: &'static [&'static str],
+    parse_percent: fn(&str) -> Option<u32>,
+}
+
+/// Same order and readback rule as [`LINUX_MUTE_BACKENDS`].
+#[cfg(target_os = "linux")]
+const LINUX_VOLUME_BACKENDS: &[LinuxVolumeBackend] = &[
+    LinuxVolumeBackend {
+        name: "wpctl",
+        program: "wpctl",
+        set_args: &["set-volume", "@DEFAULT_AUDIO_SINK@"],
+        format_level: |percent| format!("{:.2}", f64::from(percent) / 100.0),
+        read_args: &["get-volume", "@DEFAULT_AUDIO_SINK@"],
+        parse_percent: parse_wpctl_volume,
+    },
+    LinuxVolumeBackend {
+        name: "pactl",
+        program: "pactl",
+        set_args: &["set-sink-volume", "@DEFAULT_SINK@"],
+        format_level: |percent| format!("{percent}%"),
+        read_args: &["get-sink-volume", "@DEFAULT_SINK@"],
+        parse_percent: parse_volume_percent,
+    },
+    LinuxVolumeBackend {
+        name: "amixer-pulse",
+        program: "amixer",
+        set_args: &["-D", "pulse", "set", "Master"],
+        format_level: |percent| format!("{percent}%"),
+        read_args: &["-D", "pulse", "get", "Master"],
+        parse_percent: parse_volume_percent,
+    },
+    LinuxVolumeBackend {
+        name: "amixer",
+        program: "amixer",
+        set_args: &["set", "Master"],
+        format_level: |percent| format!("{percent}%"),
+        read_args: &["get", "Master"],
+        parse_percent: parse_volume_percent,
+    },
+];
+
+/// Hardware mixers have coarse steps, so a readback this close counts as the
+/// requested level.
+#[cfg(target_os = "linux")]
+const VOLUME_READBACK_TOLERANCE: u32 = 2;
+
+/// `wpctl get-volume` prints `Volume: 0.40` with a trailing `[MUTED]` marker.
+#[cfg(target_os = "linux")]
+fn parse_wpctl_muted(output: &str) -> Option<bool> {
+    let line = output
+        .lines()
+        .find(|line| line.trim_start().starts_with("Volume:"))?;
+    Some(line.contains("[MUTED]"))
+}
+
+/// `pactl get-sink-mute` prints `Mute: yes` / `Mute: no`.
+#[cfg(target_os = "linux")]
+fn parse_pactl_muted(output: &str) -> Option<bool> {
+    output
+        .lines()
+        .find_map(|line| match line.trim().strip_prefix("Mute:")?.trim() {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        })
+}
+
+/// `amixer get` prints one `[on]`/`[off]` switch per channel. Mixed channel
+/// states are reported as unknown so the caller falls through.
+#[cfg(target_os = "linux")]
+fn parse_amixer_muted(output: &str) -> Option<bool> {
+    match (output.contains("[off]"), output.contains("[on]")) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    }
+}
+
+/// `wpctl get-volume` prints `Volume: 0.40`, going past `1.00` when the sink
+/// is boosted.
+#[cfg(target_os = "linux")]
+fn parse_wpctl_volume(output: &str) -> Option<u32> {
+    let volume = output
+        .lines()
+        .find_map(|line| line.trim_start().strip_prefix("Volume:"))?
+        .split_whitespace()
+        .next()?
+        .parse::<f64>()
+        .ok()?;
+    (volume >= 0.0).then(|| (volume * 100.0).round() as u32)
+}
+
+/// Averages the per-channel percentages `pactl get-sink-volume` (`40%`) and
+/// `amixer get` (`[40%]`) print.
+#[cfg(target_os = "linux")]
+fn parse_volume_percent(output: &str) -> Option<u32> {
+    let percents: Vec<u32> = output
+        .split_whitespace()
+        .filter_map(|token| {
+            token
+                .trim_matches(|c| matches!(c, '[' | ']' | ','))
+                .strip_suffix('%')?
+                .parse()
+                .ok()
+        })
+        .collect();
+    if percents.is_empty() {
+        return None;
+    }
+    let count = percents.len() as u32;
+    Some((percents.iter().sum::<u32>() + count / 2) / count)
+}
+
+#[cfg(target_os = "linux")]
+fn run_linux_audio_command(program: &str, args: &[&str]) -> Result<String, String> {
     use std::process::Command;
 
+    // Readback parsing relies on untranslated output.
+    let output = Command::new(program)
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        return Err(if stderr.is_empty() {
+            format!("exited with {}", output.status)
+        } else {
+            stderr
+        });
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "linux")]
+fn read_linux_mute_backend(backend: &LinuxMuteBackend) -> Result<bool, String> {
+    let readback = run_linux_audio_command(backend.program, backend.read_args)?;
+    (backend.parse_muted)(&readback).ok_or_else(|| "could not read back mute state".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn apply_linux_mute_backend(backend: &LinuxMuteBackend, mute: bool) -> Result<(), String> {
+    let set_args = if mute {
+        backend.mute_args
+    } else {
+        backend.unmute_args
+    };
+    run_linux_audio_command(backend.program, set_args)?;
+
+    match read_linux_mute_backend(backend)? {
+        muted if muted == mute => Ok(()),
+        muted => Err(format!("state read back as muted={muted}")),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn read_linux_volume_backend(backend: &LinuxVolumeBackend) -> Result<u32, String> {
+    let readback = run_linux_audio_command(backend.program, backend.read_args)?;
+    (backend.parse_percent)(&readback).ok_or_else(|| "could not read back volume".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn apply_linux_volume_backend(backend: &LinuxVolumeBackend, percent: u32) -> Result<(), String> {
+    let level = (backend.format_level)(percent);
+    let mut args = backend.set_args.to_vec();
+    args.push(&level);
+    run_linux_audio_command(backend.program, &args)?;
+
+    match read_linux_volume_backend(backend)? {
+        read if read.abs_diff(percent) <= VOLUME_READBACK_TOLERANCE => Ok(()),
+        read => Err(format!("volume read back as {read}%")),
+    }
+}
+
+/// Parses `pactl list short sinks` rows: `index<TAB>name<TAB>driver<TAB>...`.
+#[cfg(target_os = "linux")]
+fn parse_pactl_short_sinks(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Maps sink names to their `Description:` from `pactl list sinks`.
+#[cfg(target_os = "linux")]
+fn parse_pactl_sink_descriptions(output: &str) -> HashMap<String, String> {
+    let mut descriptions = HashMap::new();
+    let mut current_name: Option<String> = None;
+    for line in output.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix("Name:") {
+            current_name = Some(name.trim().to_string());
+        } else if let Some(description) = line.strip_prefix("Description:")
+            && let Some(name) = current_name.take()
+        {
+            descriptions.insert(name, description.trim().to_string());
+        }
+    }
+    descriptions
+}
+
+#[cfg(target_os = "linux")]
+fn list_output_devices_linux() -> Result<Vec<OutputDevice>, String> {
+    let sinks = run_linux_audio_command("pactl", &["list", "short", "sinks"])
+        .map_err(|error| format!("Failed to list output devices via pactl: {error}"))?;
+    let default_sink = run_linux_audio_command("pactl", &["get-default-sink"])
+        .map(|output| output.trim().to_string())
+        .ok();
+    let descriptions = run_linux_audio_command("pactl", &["list", "sinks"])
+        .map(|output| parse_pactl_sink_descriptions(&output))
+        .unwrap_or_default();
+
+    Ok(parse_pactl_short_sinks(&sinks)
+        .into_iter()
+        .map(|id| OutputDevice {
+            name: descriptions.get(&id).cloned().unwrap_or_else(|| id.clone()),
+            is_default: default_sink.as_deref() == Some(id.as_str()),
+            id,
+        })
+        .collect())
+}
+
+/// Mutes one named sink. Only pactl can address sinks by name, so there is no
+/// fallback chain for targeted devices.
+#[cfg(target_os = "linux")]
+fn set_sink_mute_linux(sink: &str, mute: bool) -> Result<String, String> {
     let state = if mute { "1" } else { "0" };
-    let pactl_res = Command::new("pactl")
-        .args(["set-sink-mute", "@DEFAULT_SINK@", state])
-        .output();
-    if pactl_res.map(|out| out.status.success()).unwrap_or(false) {
-        return Ok(());
+    run_linux_audio_command("pactl", &["set-sink-mute", sink, state])
+        .and_then(|_| run_linux_audio_command("pactl", &["get-sink-mute", sink]))
+        .and_then(|readback| match parse_pactl_muted(&readback) {
+            Some(muted) if muted == mute => Ok("pactl".to_string()),
+            Some(muted) => Err(format!("state read back as muted={muted}")),
+            None => Err("could not read back mute state".to_string()),
+        })
+        .map_err(|error| format!("Failed to set mute state for sink {sink}: {error}"))
+}
+
+/// Returns the name of the backend whose mute state was verified.
+#[cfg(target_os = "linux")]
+fn set_mute_linux(mute: bool, device_id: Option<&str>) -> Result<String, String> {
+    if let Some(sink) = device_id {
+        return set_sink_mute_linux(sink, mute);
     }
 
-    let amixer_state = if mute { "mute" } else { "unmute" };
-    if Command::new("amixer")
-        .args(["-D", "pulse", "set", "Master", amixer_state])
-        .output()
-        .map(|out| out.status.success())
-        .unwrap_or(false)
+    let mut failures = Vec::new();
+    for backend in LINUX_MUTE_BACKENDS {
+        match apply_linux_mute_backend(backend, mute) {
+            Ok(()) => return Ok(backend.name.to_string()),
+            Err(error) => failures.push(format!("{}: {}", backend.name, error)),
+        }
+    }
+
+    Err(format!(
+        "Failed to set mute state on Linux ({})",
+        failures.join("; ")
+    ))
+}
+
+/// Reads the mute state of `device_id`, or of the default output through the
+/// first backend in [`LINUX_MUTE_BACKENDS`] that reports one.
+#[cfg(target_os = "linux")]
+fn get_mute_linux(device_id: Option<&str>) -> Result<bool, String> {
+    if let Some(sink) = device_id {
+        return run_linux_audio_command("pactl", &["get-sink-mute", sink])
+            .and_then(|output| {
+                parse_pactl_muted(&output).ok_or_else(|| "could not read mute state".to_string())
+            })
+            .map_err(|error| format!("Failed to read mute state for sink {sink}: {error}"));
+    }
+
+    let mut failures = Vec::new();
+    for backend in LINUX_MUTE_BACKENDS {
+        match read_linux_mute_backend(backend) {
+            Ok(muted) => return Ok(muted),
+            Err(error) => failures.push(format!("{}: {}", backend.name, error)),
+        }
+    }
+
+    Err(format!(
+        "Failed to read mute state on Linux ({})",
+        failures.join("; ")
+    ))
+}
+
+/// Sets the volume as a percentage of the sink's nominal volume. Only pactl
+/// can address sinks by name, so targeted devices have no fallback chain.
+#[cfg(target_os = "linux")]
+fn set_volume_linux(level: f32, device_id: Option<&str>) -> Result<String, String> {
+    let percent = volume_percent(level);
+    if let Some(sink) = device_id {
+        return run_linux_audio_command(
+            "pactl",
+            &["set-sink-volume", sink, &format!("{percent}%")],
+        )
+        .and_then(|_| run_linux_audio_command("pactl", &["get-sink-volume", sink]))
+        .and_then(|readback| match parse_volume_percent(&readback) {
+            Some(read) if read.abs_diff(percent) <= VOLUME_READBACK_TOLERANCE => {
+                Ok("pactl".to_string())
+            }
+            Some(read) => Err(format!("volume read back as {read}%")),
+            None => Err("could not read back volume".to_string()),
+        })
+        .map_err(|error| format!("Failed to set volume for sink {sink}: {error}"));
+    }
+
+    let mut failures = Vec::new();
+    for backend in LINUX_VOLUME_BACKENDS {
+        match apply_linux_volume_backend(backend, percent) {
+            Ok(()) => return Ok(backend.name.to_string()),
+            Err(error) => failures.push(format!("{}: {}", backend.name, error)),
+        }
+    }
+
+    Err(format!(
+        "Failed to set volume on Linux ({})",
+        failures.join("; ")
+    ))
+}
+
+/// Boosted sinks read above 100% and are reported as 1.0.
+#[cfg(target_os = "linux")]
+fn get_volume_linux(device_id: Option<&str>) -> Result<f32, String> {
+    if let Some(sink) = device_id {
+        return run_linux_audio_command("pactl", &["get-sink-volume", sink])
+            .and_then(|output| {
+                parse_volume_percent(&output).ok_or_else(|| "could not read volume".to_string())
+            })
+            .map(level_from_percent)
+            .map_err(|error| format!("Failed to read volume for sink {sink}: {error}"));
+    }
+
+    let mut failures = Vec::new();
+    for backend in LINUX_VOLUME_BACKENDS {
+        match read_linux_volume_backend(backend) {
+            Ok(percent) => return Ok(level_from_percent(percent)),
+            Err(error) => failures.push(format!("{}: {}", backend.name, error)),
+        }
+    }
+
+    Err(format!(
+        "Failed to read volume on Linux ({})",
+        failures.join("; ")
+    ))
+}
+
+/// Clamps a requested volume into 0.0–1.0; NaN is refused rather than
+/// guessed at.
+fn clamp_volume(level: f32) -> Result<f32, String> {
+    if level.is_nan() {
+        return Err("Volume level is not a number".to_string());
+    }
+    Ok(level.clamp(0.0, 1.0))
+}
+
+/// Whole percentage for the backends that take one.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn volume_percent(level: f32) -> u32 {
+    (level.clamp(0.0, 1.0) * 100.0).round() as u32
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn level_from_percent(percent: u32) -> f32 {
+    percent.min(100) as f32 / 100.0
+}
+
+pub async fn get_output_devices() -> Result<Vec<OutputDevice>, String> {
+    #[cfg(target_os = "windows")]
+    return list_output_devices_windows();
+
+    #[cfg(target_os = "macos")]
+    return list_output_devices_macos();
+
+    #[cfg(target_os = "linux")]
+    return list_output_devices_linux();
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    Err("Unsupported platform".to_string())
+}
+
+pub async fn get_default_output_device() -> Result<Option<OutputDevice>, String> {
+    Ok(get_output_devices()
+        .await?
+        .into_iter()
+        .find(|device| device.is_default))
+}
+
+fn apply_default_output_device(device_id: &str) -> Result<(), String> {
+    #[cfg(target_os = "windows")]
+    return set_default_output_windows(device_id);
+
+    #[cfg(target_os = "macos")]
+    return set_default_output_macos(device_id);
+
+    #[cfg(target_os = "linux")]
+    return run_linux_audio_command("pactl", &["set-default-sink", device_id])
+        .map(|_| ())
+        .map_err(|error| format!("Failed to set default sink {device_id}: {error}"));
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        return Ok(());
+        let _ = device_id;
+        Err("Unsupported platform".to_string())
     }
-    if Command::new("amixer")
-        .args(["set", "Master", amixer_state])
-        .output()
-        .map(|out| out.status.success())
-        .unwrap_or(false)
+}
+
+/// Makes `device_id`, an [`OutputDevice`] id from [`get_output_devices`], the
+/// default output and returns it as read back afterwards.
+pub async fn set_default_output_device(device_id: String) -> Result<OutputDevice, String> {
+    let device_id = device_id.trim();
+    if device_id.is_empty() {
+        return Err("Output device id is empty".to_string());
+    }
+
+    apply_default_output_device(device_id)?;
+    get_default_output_device()
+        .await?
+        .filter(|device| device.id == device_id)
+        .ok_or_else(|| format!("Default output device did not change to {device_id}"))
+}
+
+/// Mutes or unmutes `device_id` (the default output when `None`) and returns
+/// the backend that applied it.
+pub async fn set_system_audio_mute(
+    mute: bool,
+    device_id: Option<String>,
+) -> Result<String, String> {
+    let device_id = device_id.as_deref().filter(|id| !id.trim().is_empty());
+
+    #[cfg(target_os = "windows")]
+    return set_mute_windows(mute, device_id);
+
+    #[cfg(target_os = "macos")]
+    return set_mute_macos(mute, device_id);
+
+    #[cfg(target_os = "linux")]
+    return set_mute_linux(mute, device_id);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
     {
-        return Ok(());
+        let _ = (mute, device_id);
+        Err("Unsupported platform".to_string())
     }
+}
+
+/// Whether `device_id` (the default output when `None`) is currently muted.
+pub async fn get_system_audio_mute(device_id: Option<String>) -> Result<bool, String> {
+    let device_id = device_id.as_deref().filter(|id| !id.trim().is_empty());
+
+    #[cfg(target_os = "windows")]
+    return get_mute_windows(device_id);
+
+    #[cfg(target_os = "macos")]
+    return get_mute_macos(device_id);
 
-    Err("Failed to set mute state on Linux".to_string())
+    #[cfg(target_os = "linux")]
+    return get_mute_linux(device_id);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = device_id;
+        Err("Unsupported platform".to_string())
+    }
 }
 
-pub async fn set_system_audio_mute(mute: bool) -> Result<(), String> {
+/// Sets the volume of `device_id` (the default output when `None`) to
+/// `level`, clamped into 0.0–1.0, and returns the backend that applied it.
+///
+/// Windows applies the level as the endpoint's volume scalar, as the volume
+/// mixer does. macOS and Linux round it to a whole percentage: of AppleScript's
+/// output volume on macOS, and of the sink's nominal (unboosted) volume on
+/// Linux.
+pub async fn set_system_volume(level: f32, device_id: Option<String>) -> Result<String, String> {
+    let level = clamp_volume(level)?;
+    let device_id = device_id.as_deref().filter(|id| !id.trim().is_empty());
+
     #[cfg(target_os = "windows")]
-    return set_mute_windows(mute);
+    return set_volume_windows(level, device_id);
 
     #[cfg(target_os = "macos")]
-    return set_mute_macos(mute);
+    return set_volume_macos(level, device_id);
 
     #[cfg(target_os = "linux")]
-    return set_mute_linux(mute);
+    return set_volume_linux(level, device_id);
 
     #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
-    Err("Unsupported platform".to_string())
+    {
+        let _ = (level, device_id);
+        Err("Unsupported platform".to_string())
+    }
+}
+
+/// Volume of `device_id` (the default output when `None`) on the same 0.0–1.0
+/// scale [`set_system_volume`] takes.
+pub async fn get_system_volume(device_id: Option<String>) -> Result<f32, String> {
+    let device_id = device_id.as_deref().filter(|id| !id.trim().is_empty());
+
+    #[cfg(target_os = "windows")]
+    return get_volume_windows(device_id);
+
+    #[cfg(target_os = "macos")]
+    return get_volume_macos(device_id);
+
+    #[cfg(target_os = "linux")]
+    return get_volume_linux(device_id);
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = device_id;
+        Err("Unsupported platform".to_string())
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_device_roles_round_trip_through_their_names() {
+        for role in DefaultDeviceRole::ALL {
+            assert_eq!(
+                DefaultDeviceRole::from_device_name(role.device_name()),
+                Some(role)
+            );
+        }
+        assert_eq!(
+            DefaultDeviceRole::from_device_name("Headset Microphone"),
+            None
+        );
+    }
+
+    #[test]
+    fn parses_wpctl_mute_marker() {
+        assert_eq!(parse_wpctl_muted("Volume: 0.40 [MUTED]\n"), Some(true));
+        assert_eq!(parse_wpctl_muted("Volume: 0.40\n"), Some(false));
+        assert_eq!(parse_wpctl_muted("Could not find sink\n"), None);
+    }
+
+    #[test]
+    fn parses_pactl_mute_state() {
+        assert_eq!(parse_pactl_muted("Mute: yes\n"), Some(true));
+        assert_eq!(parse_pactl_muted("Mute: no\n"), Some(false));
+        assert_eq!(parse_pactl_muted("Stumm: ja\n"), None);
+    }
+
+    #[test]
+    fn parses_osascript_mute_state() {
+        assert_eq!(parse_osascript_muted("true\n"), Some(true));
+        assert_eq!(parse_osascript_muted("false\n"), Some(false));
+        assert_eq!(parse_osascript_muted("missing value\n"), None);
+    }
+
+    #[test]
+    fn volume_levels_are_clamped_and_scaled_to_percent() {
+        assert_eq!(clamp_volume(1.5), Ok(1.0));
+        assert_eq!(clamp_volume(-0.2), Ok(0.0));
+        assert!(clamp_volume(f32::NAN).is_err());
+        assert_eq!(volume_percent(0.404), 40);
+        assert_eq!(level_from_percent(150), 1.0);
+    }
+
+    #[test]
+    fn parses_volume_readbacks() {
+        let pactl = "Volume: front-left: 26214 /  40% / -23.88 dB,   front-right: 28180 /  43% / -21.99 dB\n        balance 0.07\n";
+        let amixer = "  Limits: Playback 0 - 65536\n  Front Left: Playback 41943 [64%] [on]\n  Front Right: Playback 41943 [64%] [on]\n";
+
+        assert_eq!(parse_volume_percent(pactl), Some(42));
+        assert_eq!(parse_volume_percent(amixer), Some(64));
+        assert_eq!(parse_volume_percent("No sink\n"), None);
+        assert_eq!(parse_wpctl_volume("Volume: 0.40 [MUTED]\n"), Some(40));
+        assert_eq!(parse_wpctl_volume("Volume: 1.25\n"), Some(125));
+        assert_eq!(parse_wpctl_volume("Could not find sink\n"), None);
+    }
+
+    #[test]
+    fn parses_pactl_sink_names_and_descriptions() {
+        let short = "47\talsa_output.pci.analog-stereo\tPipeWire\ts32le 2ch 48000Hz\tSUSPENDED\n\
+                     52\tbluez_output.headset.1\tPipeWire\ts16le 2ch 48000Hz\tRUNNING\n";
+        let long = "Sink #47\n\tState: SUSPENDED\n\tName: alsa_output.pci.analog-stereo\n\
+                    \tDescription: Built-in Audio Analog Stereo\n\
+                    Sink #52\n\tName: bluez_output.headset.1\n\tDescription: Headset\n";
+
+        assert_eq!(
+            parse_pactl_short_sinks(short),
+            vec![
+                "alsa_output.pci.analog-stereo".to_string(),
+                "bluez_output.headset.1".to_string(),
+            ]
+        );
+        let descriptions = parse_pactl_sink_descriptions(long);
+        assert_eq!(
+            descriptions
+                .get("alsa_output.pci.analog-stereo")
+                .map(String::as_str),
+            Some("Built-in Audio Analog Stereo")
+        );
+        assert_eq!(
+            descriptions
+                .get("bluez_output.headset.1")
+                .map(String::as_str),
+            Some("Headset")
+        );
+    }
+
+    #[test]
+    fn parses_amixer_switches_and_rejects_mixed_channels() {
+        let muted = "  Front Left: Playback 65536 [100%] [off]\n  Front Right: Playback 65536 [100%] [off]\n";
+        let unmuted = "  Mono: Playback 40 [63%] [-24.00dB] [on]\n";
+        let mixed = "  Front Left: Playback [off]\n  Front Right: Playback [on]\n";
+
+        assert_eq!(parse_amixer_muted(muted), Some(true));
+        assert_eq!(parse_amixer_muted(unmuted), Some(false));
+        assert_eq!(parse_amixer_muted(mixed), None);
+    }
 }
@@ -0,0 +1,118 @@
+//! Keeps the download and extraction commands from writing outside the
+//! directories the app owns, so a compromised frontend cannot use them to
+//! drop files anywhere on disk. Those commands write with plain `tokio::fs`,
+//! which the `fs` plugin's capability scope does not cover.
+
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_fs::FsExt;
+
+use crate::platform::model_downloads::DownloadState;
+use crate::platform::paths::{PathKind, PathProvider, TauriPathProvider};
+
+/// Set in a debug build to skip the check while testing against arbitrary
+/// directories. Release builds ignore it.
+const UNSCOPED_WRITES_ENV: &str = "SONA_DEV_UNSCOPED_WRITES";
+
+/// Directories downloads and extractions may write into: the app data
+/// directories, the models directory, the download temp directory and the
+/// system temp directory.
+fn allowed_write_roots<R: Runtime>(app: &AppHandle<R>) -> Vec<PathBuf> {
+    let provider = TauriPathProvider::from_app(app);
+    let mut roots = [PathKind::AppData, PathKind::AppLocalData]
+        .into_iter()
+        .filter_map(|kind| provider.resolve_path(kind).ok())
+        .collect::<Vec<_>>();
+    roots.extend(crate::platform::paths::models_dir_for_app(app).ok());
+    if let Some(downloads) = app.try_state::<DownloadState>() {
+        roots.extend(downloads.client().temp_dir().map(Path::to_path_buf));
+    }
+    roots.push(std::env::temp_dir());
+    roots
+}
+
+/// `path` with its deepest existing ancestor canonicalized, so symlinks and
+/// platform prefixes compare like the roots do. `None` for relative paths and
+/// paths with `..`, which are never allowed.
+fn resolve_for_check(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute()
+        || path
+            .components()
+            .any(|component| component == Component::ParentDir)
+    {
+        return None;
+    }
+    let mut existing = path;
+    let mut rest = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return Some(
+                rest.into_iter()
+                    .rev()
+                    .fold(canonical, |path, name| path.join(name)),
+            );
+        }
+        rest.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+/// Whether `path` lies inside one of `roots`.
+fn is_within_roots(path: &Path, roots: &[PathBuf]) -> bool {
+    let Some(path) = resolve_for_check(path) else {
+        return false;
+    };
+    roots
+        .iter()
+        .filter_map(|root| resolve_for_check(root))
+        .any(|root| path.starts_with(root))
+}
+
+/// Fails unless `path` is inside one of the app's own directories or a path
+/// the `fs` plugin scope allows, such as a folder picked in a dialog.
+pub(crate) fn ensure_write_allowed<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &Path,
+) -> Result<(), String> {
+    if cfg!(debug_assertions) && std::env::var_os(UNSCOPED_WRITES_ENV).is_some() {
+        return Ok(());
+    }
+    let in_plugin_scope = resolve_for_check(path).is_some()
+        && app
+            .try_fs_scope()
+            .is_some_and(|scope| scope.is_allowed(path));
+    if in_plugin_scope || is_within_roots(path, &allowed_write_roots(app)) {
+        return Ok(());
+    }
+    log::warn!(
+        "[downloads] Refused to write outside the allowed scope: {}",
+        path.display()
+    );
+    Err(format!("path outside allowed scope: {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_paths_inside_a_root_are_allowed() {
+        let root = std::env::temp_dir().join("sona-write-scope").join("models");
+        let roots = vec![root.clone()];
+
+        assert!(is_within_roots(
+            &root.join("whisper").join("model.onnx"),
+            &roots
+        ));
+        assert!(is_within_roots(&root, &roots));
+        assert!(!is_within_roots(
+            &root.with_file_name("models-other"),
+            &roots
+        ));
+        assert!(!is_within_roots(
+            &root.join("..").join("..").join("escape.txt"),
+            &roots
+        ));
+        assert!(!is_within_roots(Path::new("models/model.onnx"), &roots));
+    }
+}